@@ -0,0 +1,119 @@
+use maptos_execution_util::config::client::Config as ClientConfig;
+use std::time::Duration;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+/// Builds the gRPC channel to the indexer.
+///
+/// An `https` url is connected over TLS, anything else over plain http/2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerChannel {
+	pub url: String,
+	pub connect_timeout: Duration,
+	/// Bounds the wait for the response headers, not the lifetime of a response stream.
+	pub request_timeout: Duration,
+	pub keepalive_interval: Duration,
+	pub keepalive_timeout: Duration,
+}
+
+impl IndexerChannel {
+	pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+	pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+	pub const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
+	pub const DEFAULT_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(20);
+
+	pub fn new(url: impl Into<String>) -> Self {
+		Self {
+			url: url.into(),
+			connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
+			request_timeout: Self::DEFAULT_REQUEST_TIMEOUT,
+			keepalive_interval: Self::DEFAULT_KEEPALIVE_INTERVAL,
+			keepalive_timeout: Self::DEFAULT_KEEPALIVE_TIMEOUT,
+		}
+	}
+
+	/// Uses the `maptos_indexer_grpc_connection_*` fields of the client config.
+	///
+	/// The hostname may carry a scheme, e.g. `https://indexer.example.com`, and defaults to http.
+	pub fn from_config(config: &ClientConfig) -> Self {
+		Self::new(indexer_url(
+			&config.maptos_indexer_grpc_connection_hostname,
+			config.maptos_indexer_grpc_connection_port,
+		))
+	}
+
+	pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+		self.connect_timeout = connect_timeout;
+		self
+	}
+
+	pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+		self.request_timeout = request_timeout;
+		self
+	}
+
+	pub fn with_keepalive(mut self, interval: Duration, timeout: Duration) -> Self {
+		self.keepalive_interval = interval;
+		self.keepalive_timeout = timeout;
+		self
+	}
+
+	/// Configures the endpoint without connecting.
+	pub fn endpoint(&self) -> Result<Endpoint, anyhow::Error> {
+		let endpoint = Endpoint::from_shared(self.url.clone())?
+			.connect_timeout(self.connect_timeout)
+			.timeout(self.request_timeout)
+			.tcp_keepalive(Some(self.keepalive_interval))
+			.http2_keep_alive_interval(self.keepalive_interval)
+			.keep_alive_timeout(self.keepalive_timeout)
+			.keep_alive_while_idle(true);
+
+		let uri = endpoint.uri().clone();
+		if uri.scheme_str() != Some("https") {
+			return Ok(endpoint);
+		}
+		let domain = uri
+			.host()
+			.ok_or_else(|| anyhow::anyhow!("Indexer url {} has no host", self.url))?;
+		Ok(endpoint.tls_config(ClientTlsConfig::new().domain_name(domain))?)
+	}
+
+	pub async fn connect(&self) -> Result<Channel, anyhow::Error> {
+		Ok(self.endpoint()?.connect().await?)
+	}
+}
+
+/// Joins the hostname and port into a url, keeping a scheme given with the hostname.
+pub fn indexer_url(hostname: &str, port: u16) -> String {
+	if hostname.contains("://") {
+		format!("{}:{}", hostname.trim_end_matches('/'), port)
+	} else {
+		format!("http://{}:{}", hostname, port)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_indexer_url() {
+		assert_eq!(indexer_url("0.0.0.0", 30734), "http://0.0.0.0:30734");
+		assert_eq!(
+			indexer_url("https://indexer.example.com/", 443),
+			"https://indexer.example.com:443"
+		);
+	}
+
+	#[test]
+	fn test_endpoint_timeouts() -> Result<(), anyhow::Error> {
+		let channel = IndexerChannel::new("http://0.0.0.0:30734")
+			.with_connect_timeout(Duration::from_secs(1));
+		assert_eq!(channel.endpoint()?.uri().scheme_str(), Some("http"));
+		assert_eq!(channel.connect_timeout, Duration::from_secs(1));
+
+		let channel = IndexerChannel::new("https://indexer.example.com:443");
+		assert_eq!(channel.endpoint()?.uri().host(), Some("indexer.example.com"));
+
+		Ok(())
+	}
+}
@@ -0,0 +1,186 @@
+use aptos_protos::transaction::v1::Transaction;
+use futures::{Stream, StreamExt};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Persists the version an indexer consumer resumes from, so it restarts where it left off.
+#[async_trait::async_trait]
+pub trait CheckpointSink: Send + Sync {
+	/// The stored version to resume from, `None` if nothing was stored yet.
+	async fn load(&self) -> Result<Option<u64>, anyhow::Error>;
+
+	/// Stores the version to resume from.
+	async fn store(&self, next_version: u64) -> Result<(), anyhow::Error>;
+}
+
+/// Stores the checkpoint as a decimal version in a file.
+///
+/// Writes go to a sibling temporary file which is synced and then renamed over the checkpoint,
+/// and the directory is synced after the rename, so neither a crash mid-write nor a power loss
+/// leaves a truncated or missing checkpoint behind.
+#[derive(Debug, Clone)]
+pub struct FileCheckpoint {
+	path: PathBuf,
+}
+
+impl FileCheckpoint {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+}
+
+#[async_trait::async_trait]
+impl CheckpointSink for FileCheckpoint {
+	async fn load(&self) -> Result<Option<u64>, anyhow::Error> {
+		match tokio::fs::read_to_string(&self.path).await {
+			Ok(contents) => Ok(Some(contents.trim().parse().map_err(|e| {
+				anyhow::anyhow!("Invalid checkpoint in {}: {}", self.path.display(), e)
+			})?)),
+			Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn store(&self, next_version: u64) -> Result<(), anyhow::Error> {
+		let temporary_path = self.path.with_extension("tmp");
+		let mut file = tokio::fs::File::create(&temporary_path).await?;
+		file.write_all(next_version.to_string().as_bytes()).await?;
+		file.sync_all().await?;
+		drop(file);
+		tokio::fs::rename(&temporary_path, &self.path).await?;
+
+		// the rename is only durable once the directory entry is
+		let directory = match self.path.parent() {
+			Some(parent) if !parent.as_os_str().is_empty() => parent,
+			_ => Path::new("."),
+		};
+		tokio::fs::File::open(directory).await?.sync_all().await?;
+		Ok(())
+	}
+}
+
+/// Stores the version following the processed transactions in `sink` every `interval`
+/// transactions, and once more when the stream ends or fails.
+///
+/// A transaction counts as processed once the consumer polls for the next one, so after a crash
+/// the consumer resumes at the latest checkpoint and sees at most `interval` transactions again,
+/// giving at-least-once processing.
+pub fn checkpointed<C: CheckpointSink>(
+	stream: impl Stream<Item = Result<Transaction, anyhow::Error>>,
+	sink: C,
+	interval: u64,
+) -> impl Stream<Item = Result<Transaction, anyhow::Error>> {
+	let interval = interval.max(1);
+	async_stream::try_stream! {
+		let mut stream = std::pin::pin!(stream);
+		// the version following the processed transactions, while it isn't stored
+		let mut unstored = None;
+		let mut pending = 0;
+		let result = loop {
+			match stream.next().await {
+				Some(Ok(transaction)) => {
+					let next_version = transaction.version + 1;
+					yield transaction;
+
+					// the consumer asked for the next transaction, so it processed this one
+					pending += 1;
+					if pending >= interval {
+						sink.store(next_version).await?;
+						pending = 0;
+						unstored = None;
+					} else {
+						unstored = Some(next_version);
+					}
+				}
+				Some(Err(e)) => break Err(e),
+				None => break Ok(()),
+			}
+		};
+		if let Some(next_version) = unstored {
+			sink.store(next_version).await?;
+		}
+		result?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::stream;
+	use std::sync::{Arc, Mutex};
+
+	#[derive(Default, Clone)]
+	struct MemoryCheckpoint(Arc<Mutex<Option<u64>>>);
+
+	#[async_trait::async_trait]
+	impl CheckpointSink for MemoryCheckpoint {
+		async fn load(&self) -> Result<Option<u64>, anyhow::Error> {
+			Ok(*self.0.lock().unwrap())
+		}
+
+		async fn store(&self, next_version: u64) -> Result<(), anyhow::Error> {
+			*self.0.lock().unwrap() = Some(next_version);
+			Ok(())
+		}
+	}
+
+	fn transactions(
+		versions: impl IntoIterator<Item = u64>,
+	) -> Vec<Result<Transaction, anyhow::Error>> {
+		versions
+			.into_iter()
+			.map(|version| Ok(Transaction { version, ..Default::default() }))
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn test_checkpointed_stores_processed_versions() -> Result<(), anyhow::Error> {
+		let sink = MemoryCheckpoint::default();
+		let stream = checkpointed(stream::iter(transactions(1..=5)), sink.clone(), 2);
+		let mut stream = std::pin::pin!(stream);
+
+		assert_eq!(stream.next().await.unwrap()?.version, 1);
+		assert_eq!(stream.next().await.unwrap()?.version, 2);
+		assert_eq!(sink.load().await?, None);
+		// asking for 3 marks 2 as processed
+		assert_eq!(stream.next().await.unwrap()?.version, 3);
+		assert_eq!(sink.load().await?, Some(3));
+
+		assert_eq!(stream.count().await, 2);
+		assert_eq!(sink.load().await?, Some(6));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_checkpointed_stores_before_failing() -> Result<(), anyhow::Error> {
+		let sink = MemoryCheckpoint::default();
+		let mut items = transactions([7]);
+		items.push(Err(anyhow::anyhow!("gave up")));
+		let results: Vec<_> = checkpointed(stream::iter(items), sink.clone(), 10).collect().await;
+
+		assert_eq!(results.len(), 2);
+		assert!(results[1].is_err());
+		assert_eq!(sink.load().await?, Some(8));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_file_checkpoint() -> Result<(), anyhow::Error> {
+		let path = std::env::temp_dir()
+			.join(format!("suzuka-client-indexer-checkpoint-{}", std::process::id()));
+		let checkpoint = FileCheckpoint::new(&path);
+		assert_eq!(checkpoint.load().await?, None);
+
+		checkpoint.store(42).await?;
+		assert_eq!(FileCheckpoint::new(&path).load().await?, Some(42));
+
+		tokio::fs::write(&path, "not a version").await?;
+		assert!(checkpoint.load().await.is_err());
+		tokio::fs::remove_file(&path).await?;
+
+		Ok(())
+	}
+}
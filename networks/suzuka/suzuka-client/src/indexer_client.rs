@@ -0,0 +1,360 @@
+use crate::indexer_channel::IndexerChannel;
+use crate::indexer_stream::IndexerStream;
+use aptos_protos::indexer::v1::{raw_data_client::RawDataClient, GetTransactionsRequest};
+use aptos_protos::transaction::v1::{transaction::TxnData, Event, Transaction};
+use aptos_sdk::rest_client::Client as RestClient;
+use aptos_sdk::types::account_address::AccountAddress;
+use futures::{future, Future, Stream, TryStreamExt};
+use std::str::FromStr;
+use std::time::Duration;
+use tonic::transport::Channel;
+
+/// Selects the transactions yielded by [SuzukaIndexerClient::transactions].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionFilter {
+	All,
+	/// Transactions sent by the account or emitting an event under its address.
+	TouchesAccount(AccountAddress),
+}
+
+impl TransactionFilter {
+	pub fn matches(&self, transaction: &DecodedTransaction) -> bool {
+		match self {
+			TransactionFilter::All => true,
+			TransactionFilter::TouchesAccount(account) => {
+				transaction.sender == Some(*account)
+					|| transaction.events.iter().any(|event| event_account(event) == Some(*account))
+			}
+		}
+	}
+}
+
+/// An indexer transaction with the fields consumers commonly need pulled out of the proto.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTransaction {
+	/// The cursor of the stream, resume from `version + 1`.
+	pub version: u64,
+	pub block_height: u64,
+	pub hash: Vec<u8>,
+	pub success: bool,
+	/// Only set for user transactions.
+	pub sender: Option<AccountAddress>,
+	pub events: Vec<Event>,
+	pub transaction: Transaction,
+}
+
+impl From<Transaction> for DecodedTransaction {
+	fn from(transaction: Transaction) -> Self {
+		let (sender, events) = match &transaction.txn_data {
+			Some(TxnData::User(user)) => (
+				user.request.as_ref().and_then(|request| parse_address(&request.sender)),
+				user.events.clone(),
+			),
+			Some(TxnData::BlockMetadata(block_metadata)) => (None, block_metadata.events.clone()),
+			Some(TxnData::Genesis(genesis)) => (None, genesis.events.clone()),
+			_ => (None, Vec::new()),
+		};
+		let (hash, success) = transaction
+			.info
+			.as_ref()
+			.map(|info| (info.hash.clone(), info.success))
+			.unwrap_or_default();
+
+		Self {
+			version: transaction.version,
+			block_height: transaction.block_height,
+			hash,
+			success,
+			sender,
+			events,
+			transaction,
+		}
+	}
+}
+
+fn parse_address(address: &str) -> Option<AccountAddress> {
+	AccountAddress::from_str(address).ok()
+}
+
+fn event_account(event: &Event) -> Option<AccountAddress> {
+	event.key.as_ref().and_then(|key| parse_address(&key.account_address))
+}
+
+/// Typed access to the indexer transaction stream.
+#[derive(Debug, Clone)]
+pub struct SuzukaIndexerClient {
+	pub channel: IndexerChannel,
+	pub batch_size: Option<u64>,
+	/// The node the indexer follows, required by [SuzukaIndexerClient::indexer_lag].
+	pub rest_client: Option<RestClient>,
+	/// How long [SuzukaIndexerClient::indexer_lag] waits for the indexer to serve a version,
+	/// which bounds each of its rounds of probes.
+	pub probe_timeout: Duration,
+	/// How long [SuzukaIndexerClient::indexer_lag] takes at most, across all of its probes.
+	pub lag_timeout: Duration,
+}
+
+impl SuzukaIndexerClient {
+	pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+	pub const DEFAULT_LAG_TIMEOUT: Duration = Duration::from_secs(30);
+
+	pub fn new(channel: IndexerChannel) -> Self {
+		Self {
+			channel,
+			batch_size: None,
+			rest_client: None,
+			probe_timeout: Self::DEFAULT_PROBE_TIMEOUT,
+			lag_timeout: Self::DEFAULT_LAG_TIMEOUT,
+		}
+	}
+
+	pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+		self.batch_size = Some(batch_size);
+		self
+	}
+
+	pub fn with_rest_client(mut self, rest_client: RestClient) -> Self {
+		self.rest_client = Some(rest_client);
+		self
+	}
+
+	pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+		self.probe_timeout = probe_timeout;
+		self
+	}
+
+	pub fn with_lag_timeout(mut self, lag_timeout: Duration) -> Self {
+		self.lag_timeout = lag_timeout;
+		self
+	}
+
+	/// How many versions the indexer is behind the latest ledger version of the node,
+	/// e.g., for a scheduler to pause consumption while the indexer catches up or is stalled.
+	///
+	/// The indexer's latest version is found by probing for versions back from the node's,
+	/// a version it doesn't serve within the probe timeout counts as not indexed yet.
+	/// An indexer serving no version at all lags by all versions of the node.
+	/// The probes of a search round run concurrently, so versions that aren't indexed cost one
+	/// probe timeout per round rather than each, see [latest_available_version].
+	///
+	/// Fails if the indexer doesn't answer a probe at all, or if the whole measurement takes
+	/// longer than the lag timeout, rather than reporting a stalled indexer as lagging.
+	pub async fn indexer_lag(&self) -> Result<u64, anyhow::Error> {
+		tokio::time::timeout(self.lag_timeout, self.measure_indexer_lag())
+			.await
+			.map_err(|_| {
+				anyhow::anyhow!("Measuring the indexer lag timed out after {:?}", self.lag_timeout)
+			})?
+	}
+
+	async fn measure_indexer_lag(&self) -> Result<u64, anyhow::Error> {
+		let rest_client = self
+			.rest_client
+			.as_ref()
+			.ok_or_else(|| anyhow::anyhow!("No node to compare the indexer with"))?;
+		let node_version = rest_client.get_ledger_information().await?.into_inner().version;
+
+		let client = RawDataClient::new(self.channel.connect().await?);
+		let latest = latest_available_version(node_version, |versions| {
+			future::try_join_all(
+				versions.into_iter().map(|version| self.serves_version(client.clone(), version)),
+			)
+		})
+		.await?;
+		Ok(match latest {
+			Some(latest) => node_version.saturating_sub(latest),
+			None => node_version + 1,
+		})
+	}
+
+	/// Whether the indexer serves `version` within the probe timeout.
+	///
+	/// The indexer answers the request right away and then holds the stream open until it has
+	/// the version, so only a timeout waiting for the transaction means the version isn't indexed.
+	async fn serves_version(
+		&self,
+		mut client: RawDataClient<Channel>,
+		version: u64,
+	) -> Result<bool, anyhow::Error> {
+		let request = GetTransactionsRequest {
+			starting_version: Some(version),
+			transactions_count: Some(1),
+			batch_size: None,
+		};
+		let mut stream = tokio::time::timeout(self.probe_timeout, client.get_transactions(request))
+			.await
+			.map_err(|_| {
+				anyhow::anyhow!(
+					"Indexer didn't answer the probe for version {} within {:?}",
+					version,
+					self.probe_timeout
+				)
+			})??
+			.into_inner();
+		let transaction = async {
+			while let Some(response) = stream.message().await? {
+				if !response.transactions.is_empty() {
+					return Ok(true);
+				}
+			}
+			Ok::<_, anyhow::Error>(false)
+		};
+		tokio::time::timeout(self.probe_timeout, transaction).await.unwrap_or(Ok(false))
+	}
+
+	/// Streams the transactions from `from_version` on that pass the filter.
+	///
+	/// Batches are flattened and the stream reconnects on failure, see [IndexerStream].
+	pub fn transactions(
+		&self,
+		from_version: u64,
+		filter: TransactionFilter,
+	) -> impl Stream<Item = Result<DecodedTransaction, anyhow::Error>> {
+		let mut stream = IndexerStream::with_channel(self.channel.clone(), from_version);
+		stream.batch_size = self.batch_size;
+		stream
+			.into_stream()
+			.map_ok(DecodedTransaction::from)
+			.try_filter(move |transaction| future::ready(filter.matches(transaction)))
+	}
+}
+
+/// How many versions [latest_available_version] probes per bisecting round.
+const PROBES_PER_ROUND: u64 = 16;
+
+/// Finds the latest version up to `node_version` the indexer serves, `None` if none.
+///
+/// `serve_versions` probes a round of versions at once, answering for each whether it is served.
+/// The first round gallops back from the node's version, probing it and the versions at powers
+/// of two below it. The following rounds split the gap left evenly, shrinking it by a factor of
+/// [PROBES_PER_ROUND] + 1, so a lag of a thousand versions takes four rounds.
+async fn latest_available_version<F, Fut>(
+	node_version: u64,
+	serve_versions: F,
+) -> Result<Option<u64>, anyhow::Error>
+where
+	F: Fn(Vec<u64>) -> Fut,
+	Fut: Future<Output = Result<Vec<bool>, anyhow::Error>>,
+{
+	let steps = (0..u64::BITS).map(|exponent| 1 << exponent);
+	let mut versions = vec![node_version];
+	versions.extend(steps.take_while(|step| *step <= node_version).map(|step| node_version - step));
+	if versions.last() != Some(&0) {
+		versions.push(0);
+	}
+	let served = serve_versions(versions.clone()).await?;
+	let (mut available, mut unavailable) = match served.iter().position(|served| *served) {
+		None => return Ok(None),
+		Some(0) => return Ok(Some(node_version)),
+		Some(index) => (versions[index], versions[index - 1]),
+	};
+
+	while unavailable - available > 1 {
+		let gap = unavailable - available;
+		let probes = PROBES_PER_ROUND.min(gap - 1);
+		// evenly spaced and distinct, as the gap is larger than the probes
+		let versions = (1..=probes)
+			.map(|probe| {
+				available + (u128::from(gap) * u128::from(probe) / u128::from(probes + 1)) as u64
+			})
+			.collect::<Vec<_>>();
+		let served = serve_versions(versions.clone()).await?;
+		for (version, served) in versions.into_iter().zip(served) {
+			if served {
+				available = version;
+			} else {
+				unavailable = version;
+				break;
+			}
+		}
+	}
+	Ok(Some(available))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_protos::transaction::v1::{
+		EventKey, TransactionInfo, UserTransaction, UserTransactionRequest,
+	};
+
+	fn user_transaction(version: u64, sender: &str, event_account: &str) -> Transaction {
+		Transaction {
+			version,
+			info: Some(TransactionInfo { success: true, ..Default::default() }),
+			txn_data: Some(TxnData::User(UserTransaction {
+				request: Some(UserTransactionRequest {
+					sender: sender.to_string(),
+					..Default::default()
+				}),
+				events: vec![Event {
+					key: Some(EventKey {
+						account_address: event_account.to_string(),
+						..Default::default()
+					}),
+					..Default::default()
+				}],
+			})),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_touches_account_filter() {
+		let account = AccountAddress::from_str("0xa").unwrap();
+		let filter = TransactionFilter::TouchesAccount(account);
+
+		let sent = DecodedTransaction::from(user_transaction(1, "0xa", "0xb"));
+		assert_eq!(sent.version, 1);
+		assert!(sent.success);
+		assert!(filter.matches(&sent));
+
+		let emitted = DecodedTransaction::from(user_transaction(
+			2,
+			"0xb",
+			"0x000000000000000000000000000000000000000000000000000000000000000a",
+		));
+		assert!(filter.matches(&emitted));
+
+		let unrelated = DecodedTransaction::from(user_transaction(3, "0xb", "0xc"));
+		assert!(!filter.matches(&unrelated));
+		assert!(TransactionFilter::All.matches(&unrelated));
+	}
+
+	#[tokio::test]
+	async fn test_latest_available_version() -> Result<(), anyhow::Error> {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		for (node_version, latest, max_rounds) in [
+			(100, Some(100), 1),
+			(100, Some(99), 1),
+			(100, Some(37), 3),
+			(100, Some(0), 3),
+			(100, None, 1),
+			(0, None, 1),
+			(0, Some(0), 1),
+			// a badly lagging indexer, each round costs a probe timeout
+			(1_000_000, Some(999_000), 4),
+			(u64::MAX, Some(u64::MAX / 3), 18),
+		] {
+			let rounds = AtomicUsize::new(0);
+			let found = latest_available_version(node_version, |versions| {
+				rounds.fetch_add(1, Ordering::SeqCst);
+				assert!(versions.len() <= 66);
+				let served = versions
+					.into_iter()
+					.map(|version| latest.is_some_and(|latest| version <= latest))
+					.collect();
+				future::ready(Ok(served))
+			})
+			.await?;
+			assert_eq!(found, latest);
+			assert!(rounds.load(Ordering::SeqCst) <= max_rounds, "{:?}", (node_version, latest));
+		}
+
+		let failed = latest_available_version(100, |_| future::ready(Err(anyhow::anyhow!("down"))));
+		assert!(failed.await.is_err());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,327 @@
+use crate::indexer_client::{DecodedTransaction, SuzukaIndexerClient, TransactionFilter};
+use aptos_protos::transaction::v1::Event;
+use aptos_sdk::rest_client::aptos_api_types::{HexEncodedBytes, U64};
+use aptos_sdk::types::account_address::AccountAddress;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// The bridge module emitting an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeModule {
+	Initiator,
+	Counterparty,
+}
+
+impl BridgeModule {
+	pub fn module_name(&self) -> &'static str {
+		match self {
+			BridgeModule::Initiator => "atomic_bridge_initiator",
+			BridgeModule::Counterparty => "atomic_bridge_counterparty",
+		}
+	}
+
+	fn from_module_name(name: &str) -> Option<Self> {
+		[BridgeModule::Initiator, BridgeModule::Counterparty]
+			.into_iter()
+			.find(|module| module.module_name() == name)
+	}
+}
+
+/// A transfer initiated on the initiator module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeTransferInitiated {
+	pub bridge_transfer_id: [u8; 32],
+	pub originator: AccountAddress,
+	pub recipient: Vec<u8>,
+	pub amount: u64,
+	pub hash_lock: [u8; 32],
+	pub time_lock: u64,
+}
+
+/// A transfer locked on the counterparty module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeTransferLocked {
+	pub bridge_transfer_id: [u8; 32],
+	pub originator: Vec<u8>,
+	pub recipient: AccountAddress,
+	pub amount: u64,
+	pub hash_lock: [u8; 32],
+	pub time_lock: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeTransferCompleted {
+	pub bridge_transfer_id: [u8; 32],
+	pub pre_image: Vec<u8>,
+}
+
+/// A transfer cancelled on the counterparty or refunded on the initiator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeTransferAborted {
+	pub bridge_transfer_id: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeEventKind {
+	Initiated(BridgeTransferInitiated),
+	Locked(BridgeTransferLocked),
+	Completed(BridgeTransferCompleted),
+	Aborted(BridgeTransferAborted),
+}
+
+/// A bridge event with the transaction it was emitted in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEvent {
+	pub version: u64,
+	pub module: BridgeModule,
+	pub kind: BridgeEventKind,
+}
+
+#[derive(Deserialize)]
+struct InitiatedData {
+	bridge_transfer_id: HexEncodedBytes,
+	originator: AccountAddress,
+	recipient: HexEncodedBytes,
+	amount: U64,
+	hash_lock: HexEncodedBytes,
+	time_lock: U64,
+}
+
+#[derive(Deserialize)]
+struct LockedData {
+	bridge_transfer_id: HexEncodedBytes,
+	originator: HexEncodedBytes,
+	recipient: AccountAddress,
+	amount: U64,
+	hash_lock: HexEncodedBytes,
+	time_lock: U64,
+}
+
+#[derive(Deserialize)]
+struct CompletedData {
+	bridge_transfer_id: HexEncodedBytes,
+	pre_image: HexEncodedBytes,
+}
+
+#[derive(Deserialize)]
+struct AbortedData {
+	bridge_transfer_id: HexEncodedBytes,
+}
+
+/// Decodes the events of the bridge modules published at `address` from indexer transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEventDecoder {
+	pub address: AccountAddress,
+}
+
+impl BridgeEventDecoder {
+	pub fn new(address: AccountAddress) -> Self {
+		Self { address }
+	}
+
+	/// Decodes the bridge events of a transaction, in emission order.
+	/// Failed transactions emit no events, events of other modules are skipped.
+	pub fn decode(
+		&self,
+		transaction: &DecodedTransaction,
+	) -> Result<Vec<BridgeEvent>, anyhow::Error> {
+		if !transaction.success {
+			return Ok(Vec::new());
+		}
+		transaction
+			.events
+			.iter()
+			.filter_map(|event| self.decode_event(transaction.version, event).transpose())
+			.collect()
+	}
+
+	/// Decodes an event, `None` if it isn't one of a bridge module at the address.
+	/// Bridge events with malformed data are errors.
+	pub fn decode_event(
+		&self,
+		version: u64,
+		event: &Event,
+	) -> Result<Option<BridgeEvent>, anyhow::Error> {
+		let mut parts = event.type_str.splitn(3, "::");
+		let (Some(address), Some(module), Some(name)) = (parts.next(), parts.next(), parts.next())
+		else {
+			return Ok(None);
+		};
+		if AccountAddress::from_str(address).ok() != Some(self.address) {
+			return Ok(None);
+		}
+		let Some(module) = BridgeModule::from_module_name(module) else {
+			return Ok(None);
+		};
+
+		let kind = match (module, name) {
+			(BridgeModule::Initiator, "BridgeTransferInitiatedEvent") => {
+				let data: InitiatedData = event_data(event)?;
+				BridgeEventKind::Initiated(BridgeTransferInitiated {
+					bridge_transfer_id: hash(data.bridge_transfer_id)?,
+					originator: data.originator,
+					recipient: data.recipient.0,
+					amount: data.amount.0,
+					hash_lock: hash(data.hash_lock)?,
+					time_lock: data.time_lock.0,
+				})
+			}
+			(BridgeModule::Counterparty, "BridgeTransferLockedEvent") => {
+				let data: LockedData = event_data(event)?;
+				BridgeEventKind::Locked(BridgeTransferLocked {
+					bridge_transfer_id: hash(data.bridge_transfer_id)?,
+					originator: data.originator.0,
+					recipient: data.recipient,
+					amount: data.amount.0,
+					hash_lock: hash(data.hash_lock)?,
+					time_lock: data.time_lock.0,
+				})
+			}
+			(_, "BridgeTransferCompletedEvent") => {
+				let data: CompletedData = event_data(event)?;
+				BridgeEventKind::Completed(BridgeTransferCompleted {
+					bridge_transfer_id: hash(data.bridge_transfer_id)?,
+					pre_image: data.pre_image.0,
+				})
+			}
+			(BridgeModule::Initiator, "BridgeTransferRefundedEvent")
+			| (BridgeModule::Counterparty, "BridgeTransferCancelledEvent") => {
+				let data: AbortedData = event_data(event)?;
+				BridgeEventKind::Aborted(BridgeTransferAborted {
+					bridge_transfer_id: hash(data.bridge_transfer_id)?,
+				})
+			}
+			_ => return Ok(None),
+		};
+		Ok(Some(BridgeEvent { version, module, kind }))
+	}
+}
+
+fn event_data<T: DeserializeOwned>(event: &Event) -> Result<T, anyhow::Error> {
+	serde_json::from_str(&event.data)
+		.map_err(|e| anyhow::anyhow!("Invalid data of {}: {}", event.type_str, e))
+}
+
+fn hash(bytes: HexEncodedBytes) -> Result<[u8; 32], anyhow::Error> {
+	bytes
+		.0
+		.try_into()
+		.map_err(|bytes: Vec<u8>| anyhow::anyhow!("Expected 32 bytes, got {}", bytes.len()))
+}
+
+impl SuzukaIndexerClient {
+	/// Streams the bridge events from `from_version` on, so a relayer can follow transfers
+	/// without polling the bridge modules.
+	pub fn bridge_events(
+		&self,
+		from_version: u64,
+		decoder: BridgeEventDecoder,
+	) -> impl Stream<Item = Result<BridgeEvent, anyhow::Error>> {
+		self.transactions(from_version, TransactionFilter::All)
+			.and_then(move |transaction| futures::future::ready(decoder.decode(&transaction)))
+			.map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+			.try_flatten()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_protos::transaction::v1::{
+		transaction::TxnData, Transaction, TransactionInfo, UserTransaction,
+	};
+
+	const BRIDGE: &str = "0xcafe";
+
+	fn event(type_str: &str, data: serde_json::Value) -> Event {
+		Event { type_str: type_str.to_string(), data: data.to_string(), ..Default::default() }
+	}
+
+	fn transaction(success: bool, events: Vec<Event>) -> DecodedTransaction {
+		DecodedTransaction::from(Transaction {
+			version: 9,
+			info: Some(TransactionInfo { success, ..Default::default() }),
+			txn_data: Some(TxnData::User(UserTransaction { request: None, events })),
+			..Default::default()
+		})
+	}
+
+	fn hex(byte: u8) -> String {
+		format!("0x{}", format!("{:02x}", byte).repeat(32))
+	}
+
+	#[test]
+	fn test_decode_bridge_events() -> Result<(), anyhow::Error> {
+		let decoder = BridgeEventDecoder::new(AccountAddress::from_str(BRIDGE)?);
+		let transaction = transaction(
+			true,
+			vec![
+				event("0x1::coin::DepositEvent", serde_json::json!({ "amount": "100" })),
+				event(
+					"0xcafe::atomic_bridge_counterparty::BridgeTransferLockedEvent",
+					serde_json::json!({
+						"bridge_transfer_id": hex(1),
+						"originator": "0x1234",
+						"recipient": "0xa",
+						"amount": "100",
+						"hash_lock": hex(2),
+						"time_lock": "3600",
+					}),
+				),
+				// the same module at another address
+				event(
+					"0xbeef::atomic_bridge_counterparty::BridgeTransferCancelledEvent",
+					serde_json::json!({ "bridge_transfer_id": hex(1) }),
+				),
+				event(
+					"0xcafe::atomic_bridge_initiator::BridgeTransferRefundedEvent",
+					serde_json::json!({ "bridge_transfer_id": hex(3) }),
+				),
+			],
+		);
+
+		let events = decoder.decode(&transaction)?;
+		assert_eq!(
+			events,
+			vec![
+				BridgeEvent {
+					version: 9,
+					module: BridgeModule::Counterparty,
+					kind: BridgeEventKind::Locked(BridgeTransferLocked {
+						bridge_transfer_id: [1; 32],
+						originator: vec![0x12, 0x34],
+						recipient: AccountAddress::from_str("0xa")?,
+						amount: 100,
+						hash_lock: [2; 32],
+						time_lock: 3600,
+					}),
+				},
+				BridgeEvent {
+					version: 9,
+					module: BridgeModule::Initiator,
+					kind: BridgeEventKind::Aborted(BridgeTransferAborted {
+						bridge_transfer_id: [3; 32],
+					}),
+				},
+			]
+		);
+
+		// failed transactions emit nothing
+		let failed = self::transaction(false, transaction.events.clone());
+		assert_eq!(decoder.decode(&failed)?, vec![]);
+
+		// a bridge event with malformed data is an error
+		let malformed = self::transaction(
+			true,
+			vec![event(
+				"0xcafe::atomic_bridge_counterparty::BridgeTransferCompletedEvent",
+				serde_json::json!({ "bridge_transfer_id": "0x01", "pre_image": "0x" }),
+			)],
+		);
+		assert!(decoder.decode(&malformed).is_err());
+
+		Ok(())
+	}
+}
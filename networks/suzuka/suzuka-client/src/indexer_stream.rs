@@ -0,0 +1,133 @@
+use crate::indexer_channel::IndexerChannel;
+use crate::indexer_checkpoint::{checkpointed, CheckpointSink};
+use aptos_protos::indexer::v1::{
+	raw_data_client::RawDataClient, GetTransactionsRequest, TransactionsResponse,
+};
+use aptos_protos::transaction::v1::Transaction;
+use futures::{Stream, StreamExt};
+use movement_algs::backoff::Backoff;
+use tonic::Streaming;
+
+/// A transaction stream from the indexer gRPC service which survives disconnects.
+///
+/// The version of the last yielded transaction is tracked, and on a stream error the
+/// connection is reopened from the following version after a backoff delay.
+#[derive(Debug, Clone)]
+pub struct IndexerStream {
+	pub channel: IndexerChannel,
+	pub batch_size: Option<u64>,
+	/// Gives up after this many consecutive failed reconnects, retries forever if `None`.
+	pub max_reconnects: Option<u32>,
+	backoff: Backoff,
+	next_version: u64,
+}
+
+impl IndexerStream {
+	pub fn new(url: impl Into<String>, starting_version: u64) -> Self {
+		Self::with_channel(IndexerChannel::new(url), starting_version)
+	}
+
+	/// Connects through a configured channel, e.g. one with TLS.
+	pub fn with_channel(channel: IndexerChannel, starting_version: u64) -> Self {
+		Self {
+			channel,
+			batch_size: None,
+			max_reconnects: None,
+			backoff: Backoff::default(),
+			next_version: starting_version,
+		}
+	}
+
+	pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+		self.batch_size = Some(batch_size);
+		self
+	}
+
+	pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+		self.max_reconnects = Some(max_reconnects);
+		self
+	}
+
+	pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+		self.backoff = backoff;
+		self
+	}
+
+	/// The version the stream will (re)connect from.
+	pub fn next_version(&self) -> u64 {
+		self.next_version
+	}
+
+	async fn connect(&self) -> Result<Streaming<TransactionsResponse>, anyhow::Error> {
+		let mut client = RawDataClient::new(self.channel.connect().await?);
+		let request = GetTransactionsRequest {
+			starting_version: Some(self.next_version),
+			transactions_count: None,
+			batch_size: self.batch_size,
+		};
+		Ok(client.get_transactions(request).await?.into_inner())
+	}
+
+	/// Turns the helper into a stream of transactions, starting at the configured version.
+	pub fn into_stream(mut self) -> impl Stream<Item = Result<Transaction, anyhow::Error>> {
+		async_stream::try_stream! {
+			loop {
+				match self.connect().await {
+					Ok(mut stream) => {
+						while let Some(response) = stream.next().await {
+							match response {
+								Ok(response) => {
+									self.backoff.reset();
+									for transaction in response.transactions {
+										self.next_version = transaction.version + 1;
+										yield transaction;
+									}
+								}
+								Err(status) => {
+									tracing::warn!(
+										"Indexer stream failed at version {}: {}",
+										self.next_version,
+										status
+									);
+									break;
+								}
+							}
+						}
+					}
+					Err(e) => {
+						tracing::warn!("Failed to connect to indexer {}: {}", self.channel.url, e);
+					}
+				}
+
+				if let Some(max_reconnects) = self.max_reconnects {
+					if self.backoff.attempt() >= max_reconnects {
+						Err(anyhow::anyhow!(
+							"Indexer stream gave up after {} reconnects at version {}",
+							max_reconnects,
+							self.next_version
+						))?;
+					}
+				}
+				tokio::time::sleep(self.backoff.next_delay()).await;
+			}
+		}
+	}
+
+	/// Like [IndexerStream::into_stream], but resumes from the version stored in `sink` if there
+	/// is one, and stores progress every `interval` transactions, see [checkpointed].
+	pub fn into_checkpointed_stream<C: CheckpointSink>(
+		mut self,
+		sink: C,
+		interval: u64,
+	) -> impl Stream<Item = Result<Transaction, anyhow::Error>> {
+		async_stream::try_stream! {
+			if let Some(next_version) = sink.load().await? {
+				self.next_version = next_version;
+			}
+			let mut stream = std::pin::pin!(checkpointed(self.into_stream(), sink, interval));
+			while let Some(transaction) = stream.next().await {
+				yield transaction?;
+			}
+		}
+	}
+}
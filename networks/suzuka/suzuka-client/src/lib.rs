@@ -1,3 +1,8 @@
+pub mod indexer_channel;
+pub mod indexer_checkpoint;
+pub mod indexer_client;
+pub mod indexer_events;
+pub mod indexer_stream;
 pub mod load_soak_testing;
 #[cfg(test)]
 pub mod tests;
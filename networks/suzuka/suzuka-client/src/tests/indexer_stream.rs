@@ -2,6 +2,9 @@
 // pub mod indexer_stream;
 // use std::str::FromStr;
 // use url::Url;
+use crate::indexer_channel::{indexer_url, IndexerChannel};
+use crate::indexer_checkpoint::{CheckpointSink, FileCheckpoint};
+use crate::indexer_stream::IndexerStream;
 use aptos_protos::indexer::v1::{raw_data_client::RawDataClient, GetTransactionsRequest};
 use futures::StreamExt;
 use once_cell::sync::Lazy;
@@ -26,10 +29,7 @@ static INDEXER_URL: Lazy<String> = Lazy::new(|| {
 		.maptos_indexer_grpc_connection_port
 		.clone();
 
-	let indexer_connection_url =
-		format!("http://{}:{}", indexer_connection_hostname, indexer_connection_port);
-
-	indexer_connection_url
+	indexer_url(&indexer_connection_hostname, indexer_connection_port)
 });
 
 #[tokio::test]
@@ -57,3 +57,45 @@ async fn test_example_indexer_stream() -> Result<(), anyhow::Error> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_indexer_stream_versions_are_contiguous() -> Result<(), anyhow::Error> {
+	let channel = IndexerChannel::from_config(&SUZUKA_CONFIG.execution_config.maptos_config.client);
+	let stream = IndexerStream::with_channel(channel, 1).with_batch_size(100);
+	let transactions: Vec<_> = stream.into_stream().take(10).collect().await;
+
+	let mut expected_version = 1;
+	for transaction in transactions {
+		assert_eq!(transaction?.version, expected_version);
+		expected_version += 1;
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_indexer_stream_resumes_from_checkpoint() -> Result<(), anyhow::Error> {
+	let path = std::env::temp_dir()
+		.join(format!("suzuka-client-indexer-stream-checkpoint-{}", std::process::id()));
+	let checkpoint = FileCheckpoint::new(&path);
+	checkpoint.store(3).await?;
+
+	let channel = IndexerChannel::from_config(&SUZUKA_CONFIG.execution_config.maptos_config.client);
+	let stream = IndexerStream::with_channel(channel, 1)
+		.with_batch_size(100)
+		.into_checkpointed_stream(checkpoint.clone(), 2);
+	let versions = stream
+		.take(4)
+		.map(|transaction| transaction.map(|transaction| transaction.version))
+		.collect::<Vec<_>>()
+		.await
+		.into_iter()
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(versions, vec![3, 4, 5, 6]);
+
+	// the last transaction wasn't asked past, so it counts as unprocessed
+	assert_eq!(checkpoint.load().await?, Some(5));
+	tokio::fs::remove_file(&path).await?;
+
+	Ok(())
+}
@@ -5,10 +5,11 @@ use maptos_dof_execution::{v1::Executor, DynOptFinExecutor};
 use mcr_settlement_client::McrSettlementClient;
 use mcr_settlement_manager::CommitmentEventStream;
 use mcr_settlement_manager::McrSettlementManager;
-use movement_rest::MovementRest;
+use movement_rest::{CommitmentEvents, MovementRest};
 use suzuka_config::Config;
 
 use anyhow::Context;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::try_join;
 use tracing::debug;
@@ -37,12 +38,18 @@ where
 		let services = context.services();
 		let mut movement_rest = self.movement_rest;
 		movement_rest.set_context(services.opt_api_context());
+		let commitment_feed = self.commitment_events.as_ref().map(|_| {
+			let feed = Arc::new(CommitmentEvents::default());
+			movement_rest.commitment_events = Some(feed.clone());
+			feed
+		});
 		let exec_settle_task = tasks::execute_settle::Task::new(
 			self.executor,
 			self.settlement_manager,
 			self.da_db,
 			self.light_node_client.clone(),
 			self.commitment_events,
+			commitment_feed,
 			self.config.execution_extension.clone(),
 		);
 		let transaction_ingress_task = tasks::transaction_ingress::Task::new(
@@ -11,10 +11,12 @@ use maptos_dof_execution::{
 	SignatureVerifiedTransaction, SignedTransaction, Transaction,
 };
 use mcr_settlement_manager::{CommitmentEventStream, McrSettlementManagerOperations};
+use movement_rest::CommitmentEvents;
 use movement_types::block::{Block, BlockCommitment, BlockCommitmentEvent};
 
 use anyhow::Context;
 use futures::{future::Either, stream};
+use std::sync::Arc;
 use suzuka_config::execution_extension;
 use tokio::select;
 use tokio_stream::{Stream, StreamExt};
@@ -28,6 +30,8 @@ pub struct Task<E, S> {
 	// Stream receiving commitment events, conditionally enabled
 	commitment_events:
 		Either<CommitmentEventStream, stream::Pending<<CommitmentEventStream as Stream>::Item>>,
+	// Republishes the commitment events to the REST service subscribers
+	commitment_feed: Option<Arc<CommitmentEvents>>,
 	execution_extension: execution_extension::Config,
 }
 
@@ -38,6 +42,7 @@ impl<E, S> Task<E, S> {
 		da_db: DaDB,
 		da_light_node_client: LightNodeServiceClient<tonic::transport::Channel>,
 		commitment_events: Option<CommitmentEventStream>,
+		commitment_feed: Option<Arc<CommitmentEvents>>,
 		execution_extension: execution_extension::Config,
 	) -> Self {
 		let commitment_events = match commitment_events {
@@ -50,6 +55,7 @@ impl<E, S> Task<E, S> {
 			da_db,
 			da_light_node_client,
 			commitment_events,
+			commitment_feed,
 			execution_extension,
 		}
 	}
@@ -243,6 +249,9 @@ where
 		&mut self,
 		event: BlockCommitmentEvent,
 	) -> anyhow::Result<()> {
+		if let Some(feed) = &self.commitment_feed {
+			feed.publish(event.clone());
+		}
 		match event {
 			BlockCommitmentEvent::Accepted(commitment) => {
 				debug!("Commitment accepted: {:?}", commitment);
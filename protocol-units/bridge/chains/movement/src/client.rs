@@ -0,0 +1,168 @@
+use crate::error::MovementBridgeError;
+use aptos_sdk::rest_client::{error::RestError, Client};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+use url::Url;
+
+/// Errors after which another node may serve the request, e.g., a refused connection.
+pub trait ConnectionError: fmt::Display {
+	fn is_connection_error(&self) -> bool;
+}
+
+impl ConnectionError for RestError {
+	fn is_connection_error(&self) -> bool {
+		// API errors come from a node that is up, so another node would answer the same
+		matches!(self, RestError::Http(..) | RestError::Timeout(_) | RestError::Unknown(_))
+	}
+}
+
+impl ConnectionError for MovementBridgeError {
+	fn is_connection_error(&self) -> bool {
+		match self {
+			MovementBridgeError::RpcError(e) => e.is_connection_error(),
+			_ => false,
+		}
+	}
+}
+
+/// Rest clients of a pool of nodes, failing over to the next node on connection errors.
+///
+/// Reads go round robin over the nodes and are retried on the next node.
+/// Writes go to the current node and aren't retried, since the transaction may have reached
+/// the failed node, but the next write goes to the next node.
+#[derive(Clone)]
+pub struct FailoverClient {
+	clients: Arc<[Client]>,
+	current: Arc<AtomicUsize>,
+	next_read: Arc<AtomicUsize>,
+}
+
+impl FailoverClient {
+	pub fn new(urls: Vec<Url>) -> Result<Self, MovementBridgeError> {
+		if urls.is_empty() {
+			return Err(MovementBridgeError::ConfigInvalid("No RPC URL configured".to_string()));
+		}
+		Ok(Self {
+			clients: urls.into_iter().map(Client::new).collect(),
+			current: Arc::new(AtomicUsize::new(0)),
+			next_read: Arc::new(AtomicUsize::new(0)),
+		})
+	}
+
+	/// The client of the node writes currently go to.
+	pub fn current(&self) -> &Client {
+		&self.clients[self.current.load(Ordering::Acquire)]
+	}
+
+	/// Moves writes on from the failed node, unless another caller already did.
+	fn fail_over(&self, failed: usize) {
+		let next = (failed + 1) % self.clients.len();
+		let _ = self.current.compare_exchange(failed, next, Ordering::AcqRel, Ordering::Acquire);
+	}
+
+	/// Runs a read on the next node in turn, trying the other nodes on connection errors.
+	pub async fn read<T, E, F, Fut>(&self, f: F) -> Result<T, E>
+	where
+		E: ConnectionError,
+		F: Fn(Client) -> Fut,
+		Fut: Future<Output = Result<T, E>>,
+	{
+		let nodes = self.clients.len();
+		let start = self.next_read.fetch_add(1, Ordering::Relaxed);
+		let mut attempt = 0;
+		loop {
+			let index = (start + attempt) % nodes;
+			match f(self.clients[index].clone()).await {
+				Err(e) if e.is_connection_error() && attempt + 1 < nodes => {
+					warn!("Movement node {} unreachable, trying the next one: {}", index, e);
+					self.fail_over(index);
+					attempt += 1;
+				}
+				result => return result,
+			}
+		}
+	}
+
+	/// Runs a write on the current node, failing over for later writes on connection errors.
+	pub async fn write<T, E, F, Fut>(&self, f: F) -> Result<T, E>
+	where
+		E: ConnectionError,
+		F: FnOnce(Client) -> Fut,
+		Fut: Future<Output = Result<T, E>>,
+	{
+		let index = self.current.load(Ordering::Acquire);
+		let result = f(self.clients[index].clone()).await;
+		if let Err(e) = &result {
+			if e.is_connection_error() {
+				warn!("Movement node {} unreachable, failing over: {}", index, e);
+				self.fail_over(index);
+			}
+		}
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn failover_client(nodes: usize) -> FailoverClient {
+		let urls = (0..nodes)
+			.map(|port| Url::parse(&format!("http://127.0.0.1:{}", 8080 + port)).unwrap())
+			.collect();
+		FailoverClient::new(urls).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_read_fails_over() {
+		let client = failover_client(3);
+		let tried = std::sync::Mutex::new(Vec::new());
+
+		let result = client
+			.read(|client| {
+				let port = client.path_prefix_string();
+				tried.lock().unwrap().push(port.clone());
+				async move {
+					if port.contains("8080") {
+						Err(MovementBridgeError::RpcError(RestError::Timeout("refused")))
+					} else {
+						Ok(port)
+					}
+				}
+			})
+			.await;
+		assert!(result.unwrap().contains("8081"));
+		assert_eq!(tried.lock().unwrap().len(), 2);
+		// writes moved on from the failed node
+		assert!(client.current().path_prefix_string().contains("8081"));
+
+		// the next read starts at the next node in turn
+		let result = client.read(|client| async move { Ok::<_, RestError>(client) }).await;
+		assert!(result.unwrap().path_prefix_string().contains("8081"));
+	}
+
+	#[tokio::test]
+	async fn test_write_is_not_retried() {
+		let client = failover_client(2);
+
+		let result = client
+			.write(|_| async {
+				Err::<(), _>(MovementBridgeError::RpcError(RestError::Timeout("refused")))
+			})
+			.await;
+		assert!(result.is_err());
+		assert!(client.current().path_prefix_string().contains("8081"));
+
+		// errors from a live node keep the current node
+		let result = client
+			.write(|_| async { Err::<(), _>(MovementBridgeError::AbortCode(1)) })
+			.await;
+		assert!(result.is_err());
+		assert!(client.current().path_prefix_string().contains("8081"));
+
+		assert!(FailoverClient::new(Vec::new()).is_err());
+	}
+}
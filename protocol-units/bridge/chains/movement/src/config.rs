@@ -0,0 +1,319 @@
+use crate::error::MovementBridgeError;
+use crate::types::{CounterpartyFunctions, MoveAmountType};
+use crate::utils::{GasParams, MovementAddress, GAS_UNIT_PRICE};
+use crate::Config;
+use aptos_sdk::{
+	move_types::{identifier::Identifier, language_storage::TypeTag},
+	types::LocalAccount,
+};
+use dot_movement::DotMovement;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use url::Url;
+
+/// The file form of [Config], as operators running the bridge as a service configure it.
+///
+/// The signer private key isn't inline but read from `signer_key_file`,
+/// which is resolved relative to the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+	pub rpc_url: Option<String>,
+	#[serde(default)]
+	pub rpc_urls: Vec<String>,
+	pub ws_url: Option<String>,
+	#[serde(default)]
+	pub faucet_url: Option<String>,
+	#[serde(default)]
+	pub movement_rest_url: Option<String>,
+	#[serde(default)]
+	pub indexer_url: Option<String>,
+	pub chain_id: String,
+	/// A file holding the hex encoded Ed25519 private key of the signer
+	pub signer_key_file: PathBuf,
+	pub initiator_contract: Option<MovementAddress>,
+	#[serde(default)]
+	pub counterparty_contract: Option<MovementAddress>,
+	pub gas_limit: u64,
+	#[serde(default = "ConfigFile::default_gas_unit_price")]
+	pub gas_unit_price: u64,
+	#[serde(default = "ConfigFile::default_expiration_secs")]
+	pub expiration_secs: u64,
+	#[serde(default = "ConfigFile::default_request_timeout_ms")]
+	pub request_timeout_ms: u64,
+	#[serde(default = "ConfigFile::default_poll_interval_ms")]
+	pub poll_interval_ms: u64,
+	#[serde(default = "ConfigFile::default_max_poll_interval_ms")]
+	pub max_poll_interval_ms: u64,
+	#[serde(default)]
+	pub dry_run: bool,
+	#[serde(default = "ConfigFile::default_check_completed")]
+	pub check_completed: bool,
+	#[serde(default)]
+	pub amount_type: MoveAmountType,
+	/// The coin locked on the counterparty, e.g., `0x1::aptos_coin::AptosCoin`, which needs a
+	/// counterparty module generic over it. Only its syntax is checked here, the client checks
+	/// the module takes it before locking, see [Config::coin_type]
+	#[serde(default)]
+	pub coin_type: Option<String>,
+	#[serde(default)]
+	pub counterparty_functions: CounterpartyFunctions,
+}
+
+impl ConfigFile {
+	/// The name of the bridge config file in the `.movement` directory.
+	pub const FILE_NAME: &'static str = "bridge.json";
+
+	fn default_gas_unit_price() -> u64 {
+		GAS_UNIT_PRICE
+	}
+
+	fn default_expiration_secs() -> u64 {
+		GasParams::DEFAULT_EXPIRATION_SECS
+	}
+
+	fn default_request_timeout_ms() -> u64 {
+		Config::DEFAULT_REQUEST_TIMEOUT.as_millis() as u64
+	}
+
+	fn default_poll_interval_ms() -> u64 {
+		Config::DEFAULT_POLL_INTERVAL.as_millis() as u64
+	}
+
+	fn default_max_poll_interval_ms() -> u64 {
+		Config::DEFAULT_MAX_POLL_INTERVAL.as_millis() as u64
+	}
+
+	fn default_check_completed() -> bool {
+		true
+	}
+
+	/// Reads the signer key from `signer_key_file`, relative to `base_dir`, and validates the config.
+	pub fn into_config(self, base_dir: &Path) -> Result<Config, MovementBridgeError> {
+		let key_path = base_dir.join(&self.signer_key_file);
+		let key = std::fs::read_to_string(&key_path).map_err(|e| {
+			MovementBridgeError::ConfigInvalid(format!(
+				"Failed to read the signer key file {}: {}",
+				key_path.display(),
+				e
+			))
+		})?;
+		let signer = LocalAccount::from_private_key(key.trim(), 0).map_err(|e| {
+			MovementBridgeError::ConfigInvalid(format!(
+				"Invalid signer key in {}: {}",
+				key_path.display(),
+				e
+			))
+		})?;
+		let coin_type = self
+			.coin_type
+			.map(|coin_type| {
+				TypeTag::from_str(&coin_type).map_err(|e| {
+					MovementBridgeError::ConfigInvalid(format!(
+						"Invalid coin type {}: {}",
+						coin_type, e
+					))
+				})
+			})
+			.transpose()?;
+
+		let config = Config {
+			rpc_url: self.rpc_url,
+			rpc_urls: self.rpc_urls,
+			ws_url: self.ws_url,
+			faucet_url: self.faucet_url,
+			movement_rest_url: self.movement_rest_url,
+			indexer_url: self.indexer_url,
+			chain_id: self.chain_id,
+			signer_private_key: Arc::new(RwLock::new(signer)),
+			initiator_contract: self.initiator_contract,
+			counterparty_contract: self.counterparty_contract,
+			gas_limit: self.gas_limit,
+			gas_unit_price: self.gas_unit_price,
+			expiration_secs: self.expiration_secs,
+			request_timeout: Duration::from_millis(self.request_timeout_ms),
+			poll_interval: Duration::from_millis(self.poll_interval_ms),
+			max_poll_interval: Duration::from_millis(self.max_poll_interval_ms),
+			dry_run: self.dry_run,
+			check_completed: self.check_completed,
+			amount_type: self.amount_type,
+			coin_type,
+			counterparty_functions: self.counterparty_functions,
+		};
+		config.validate()?;
+		Ok(config)
+	}
+}
+
+impl Config {
+	/// Loads the config from a JSON [ConfigFile].
+	pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MovementBridgeError> {
+		let path = path.as_ref();
+		let contents = std::fs::read_to_string(path).map_err(|e| {
+			MovementBridgeError::ConfigInvalid(format!("Failed to read {}: {}", path.display(), e))
+		})?;
+		let file: ConfigFile = serde_json::from_str(&contents).map_err(|e| {
+			MovementBridgeError::ConfigInvalid(format!("Failed to parse {}: {}", path.display(), e))
+		})?;
+		file.into_config(path.parent().unwrap_or(Path::new(".")))
+	}
+
+	/// Loads the config from the [ConfigFile::FILE_NAME] file in the `.movement` directory.
+	pub fn from_dot_movement(dot_movement: &DotMovement) -> Result<Self, MovementBridgeError> {
+		Self::from_file(dot_movement.get_path().join(ConfigFile::FILE_NAME))
+	}
+
+	/// Checks the URLs parse, the function names are identifiers and the limits are usable.
+	pub fn validate(&self) -> Result<(), MovementBridgeError> {
+		self.rpc_endpoints()?;
+		self.movement_rest_endpoint()?;
+		for url in self.ws_url.iter().chain(&self.faucet_url).chain(&self.indexer_url) {
+			Url::parse(url)
+				.map_err(|e| MovementBridgeError::ConfigInvalid(format!("{}: {}", url, e)))?;
+		}
+		for function in self.counterparty_functions.names() {
+			if !Identifier::is_valid(function) {
+				return Err(MovementBridgeError::InvalidIdentifier(function.to_string()));
+			}
+		}
+		if self.chain_id.is_empty() {
+			return Err(MovementBridgeError::ConfigInvalid("Empty chain id".to_string()));
+		}
+		if self.gas_limit == 0 || self.expiration_secs == 0 {
+			return Err(MovementBridgeError::ConfigInvalid(
+				"Gas limit and expiration have to be positive".to_string(),
+			));
+		}
+		if self.gas_unit_price < GAS_UNIT_PRICE {
+			return Err(MovementBridgeError::ConfigInvalid(format!(
+				"Gas unit price {} is below the minimum {}",
+				self.gas_unit_price, GAS_UNIT_PRICE
+			)));
+		}
+		if self.request_timeout.is_zero()
+			|| self.poll_interval.is_zero()
+			|| self.max_poll_interval.is_zero()
+		{
+			return Err(MovementBridgeError::ConfigInvalid(
+				"Request timeout and poll intervals have to be positive".to_string(),
+			));
+		}
+		if self.poll_interval > self.max_poll_interval {
+			return Err(MovementBridgeError::ConfigInvalid(format!(
+				"Poll interval {:?} exceeds the max poll interval {:?}",
+				self.poll_interval, self.max_poll_interval
+			)));
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	const KEY: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+	fn write_config(dir: &Path, config: serde_json::Value) -> PathBuf {
+		let path = dir.join(ConfigFile::FILE_NAME);
+		std::fs::write(&path, config.to_string()).unwrap();
+		path
+	}
+
+	#[test]
+	fn test_config_from_file() -> Result<(), MovementBridgeError> {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(dir.path().join("signer.key"), format!("{}\n", KEY)).unwrap();
+		let config = json!({
+			"rpc_url": "http://localhost:8080",
+			"ws_url": null,
+			"chain_id": "4",
+			"signer_key_file": "signer.key",
+			"initiator_contract": null,
+			"gas_limit": 10_000_000,
+			"poll_interval_ms": 500,
+		});
+		let path = write_config(dir.path(), config.clone());
+
+		let loaded = Config::from_file(&path)?;
+		assert_eq!(loaded.rpc_endpoints()?.len(), 1);
+		assert_eq!(loaded.poll_interval, Duration::from_millis(500));
+		assert_eq!(loaded.request_timeout, Config::DEFAULT_REQUEST_TIMEOUT);
+		assert!(loaded.check_completed);
+		assert_eq!(loaded.coin_type, None);
+		assert_eq!(
+			loaded.gas_params(),
+			GasParams {
+				max_gas_amount: 10_000_000,
+				gas_unit_price: GAS_UNIT_PRICE,
+				expiration_secs: GasParams::DEFAULT_EXPIRATION_SECS,
+			}
+		);
+		let expected = LocalAccount::from_private_key(KEY, 0).unwrap();
+		assert_eq!(loaded.signer_private_key.read().unwrap().address(), expected.address());
+
+		let loaded = Config::from_dot_movement(&DotMovement::new(dir.path().to_str().unwrap()))?;
+		assert_eq!(loaded.chain_id, "4");
+
+		// validated on load
+		let mut invalid = config.clone();
+		invalid["poll_interval_ms"] = json!(60_000);
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		for interval in ["poll_interval_ms", "max_poll_interval_ms"] {
+			let mut invalid = config.clone();
+			invalid[interval] = json!(0);
+			assert!(matches!(
+				Config::from_file(write_config(dir.path(), invalid)),
+				Err(MovementBridgeError::ConfigInvalid(message)) if message.contains("positive")
+			));
+		}
+		let mut invalid = config.clone();
+		invalid["rpc_url"] = json!("not a url");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["faucet_url"] = json!("not a url");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["indexer_url"] = json!("not a url");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["movement_rest_url"] = json!("not a url");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["signer_key_file"] = json!("missing.key");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["gas_unit_price"] = json!(GAS_UNIT_PRICE - 1);
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["expiration_secs"] = json!(0);
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+		let mut invalid = config.clone();
+		invalid["coin_type"] = json!("0x1::aptos_coin");
+		assert!(Config::from_file(write_config(dir.path(), invalid)).is_err());
+
+		let mut invalid = config.clone();
+		invalid["counterparty_functions"] = json!({ "complete": "complete-v2" });
+		assert!(matches!(
+			Config::from_file(write_config(dir.path(), invalid)),
+			Err(MovementBridgeError::InvalidIdentifier(_))
+		));
+
+		let mut config = config;
+		config["counterparty_functions"] = json!({ "complete": "complete_bridge_transfer_v2" });
+		config["coin_type"] = json!("0x1::aptos_coin::AptosCoin");
+		let loaded = Config::from_file(write_config(dir.path(), config))?;
+		assert_eq!(
+			loaded.coin_type.map(|coin_type| coin_type.to_string()),
+			Some("0x1::aptos_coin::AptosCoin".to_string())
+		);
+		assert_eq!(loaded.counterparty_functions.complete, "complete_bridge_transfer_v2");
+		assert_eq!(loaded.counterparty_functions.lock, "lock_bridge_transfer");
+
+		Ok(())
+	}
+}
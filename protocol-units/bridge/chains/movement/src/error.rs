@@ -0,0 +1,238 @@
+use crate::types::{MoveAmountType, TransferContext};
+use alloy::primitives::Uint;
+use aptos_sdk::{move_types::vm_status::StatusCode, rest_client::error::RestError};
+use bridge_shared::bridge_contracts::{BridgeContractCounterpartyError, ChainError};
+use thiserror::Error;
+
+/// Errors of the Movement chain client, categorized so callers can branch on them.
+#[derive(Debug, Error)]
+pub enum MovementBridgeError {
+	#[error("RPC request failed: {0}")]
+	RpcError(#[source] RestError),
+	#[error("Failed to serialize or deserialize: {0}")]
+	SerializationError(#[source] bcs::Error),
+	#[error("Request timed out")]
+	Timeout,
+	#[error("Move abort with code {0:#x}")]
+	AbortCode(u64),
+	#[error("Transaction failed with status: {0}")]
+	TransactionFailed(String),
+	#[error("Expected a user transaction")]
+	NotUserTransaction,
+	#[error("Sequence number out of date: {0}")]
+	SequenceNumber(#[source] RestError),
+	#[error("Transaction expired before it was committed")]
+	Expired,
+	#[error("Not submitted after an earlier failure")]
+	NotSubmitted,
+	#[error("Transaction rejected: {0}")]
+	Rejected(String),
+	#[error("Not executable before an earlier rejected transaction")]
+	Blocked,
+	#[error("Not submitted, the client is draining")]
+	Draining,
+	#[error("Invalid config: {0}")]
+	ConfigInvalid(String),
+	#[error("Faucet not configured, set the faucet_url of the config")]
+	FaucetNotConfigured,
+	#[error("Invalid Move identifier: {0}")]
+	InvalidIdentifier(String),
+	#[error("Signing failed: {0}")]
+	SigningError(String),
+	#[error("The counterparty {function} isn't generic over a coin, it can't lock {coin_type}")]
+	CoinTypeUnsupported { function: String, coin_type: String },
+	#[error("Amount {amount} overflows the Move {amount_type:?} argument")]
+	AmountOverflow { amount: Uint<256, 4>, amount_type: MoveAmountType },
+	#[error("{source} ({context})")]
+	InContext {
+		context: TransferContext,
+		#[source]
+		source: Box<MovementBridgeError>,
+	},
+}
+
+impl MovementBridgeError {
+	/// The error without the [TransferContext] it may be wrapped in, to branch on.
+	pub fn without_context(&self) -> &Self {
+		match self {
+			MovementBridgeError::InContext { source, .. } => source.without_context(),
+			e => e,
+		}
+	}
+
+	pub fn context(&self) -> Option<&TransferContext> {
+		match self {
+			MovementBridgeError::InContext { context, .. } => Some(context),
+			_ => None,
+		}
+	}
+
+	/// Categorizes the `vm_status` of a failed transaction.
+	pub fn from_vm_status(vm_status: &str) -> Self {
+		match parse_abort_code(vm_status) {
+			Some(code) => MovementBridgeError::AbortCode(code),
+			None => MovementBridgeError::TransactionFailed(vm_status.to_string()),
+		}
+	}
+
+	/// Categorizes the error of a view call, which reports aborts as API errors.
+	pub fn from_view_error(e: RestError) -> Self {
+		match &e {
+			RestError::Api(response) => match parse_abort_code(&response.error.message) {
+				Some(code) => MovementBridgeError::AbortCode(code),
+				None => e.into(),
+			},
+			_ => e.into(),
+		}
+	}
+}
+
+/// Categorizes by the VM status a transaction was rejected on submission with.
+impl From<RestError> for MovementBridgeError {
+	fn from(e: RestError) -> Self {
+		let vm_status = match &e {
+			RestError::Api(response) => {
+				response.error.vm_error_code.and_then(|code| StatusCode::try_from(code).ok())
+			}
+			_ => None,
+		};
+		match vm_status {
+			Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD | StatusCode::SEQUENCE_NUMBER_TOO_NEW) => {
+				MovementBridgeError::SequenceNumber(e)
+			}
+			Some(StatusCode::TRANSACTION_EXPIRED) => MovementBridgeError::Expired,
+			_ => MovementBridgeError::RpcError(e),
+		}
+	}
+}
+
+impl From<bcs::Error> for MovementBridgeError {
+	fn from(e: bcs::Error) -> Self {
+		MovementBridgeError::SerializationError(e)
+	}
+}
+
+impl From<MovementBridgeError> for BridgeContractCounterpartyError {
+	fn from(e: MovementBridgeError) -> Self {
+		match e.without_context() {
+			MovementBridgeError::Timeout => BridgeContractCounterpartyError::Timeout,
+			_ => BridgeContractCounterpartyError::ChainError(ChainError::new(e)),
+		}
+	}
+}
+
+/// Reads the code of a `vm_status` like `Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): ..`,
+/// which may also be embedded in an API error message.
+fn parse_abort_code(vm_status: &str) -> Option<u64> {
+	let (_, reason) = vm_status.split_once("Move abort")?.1.split_once(": ")?;
+	let code = &reason[reason.find("0x")? + 2..];
+	let end = code.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(code.len());
+	u64::from_str_radix(&code[..end], 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_sdk::rest_client::aptos_api_types::{AptosError, AptosErrorCode, AptosErrorResponse};
+	use std::error::Error;
+
+	#[test]
+	fn test_abort_code_from_vm_status() {
+		let error = MovementBridgeError::from_vm_status(
+			"Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins",
+		);
+		assert!(matches!(error, MovementBridgeError::AbortCode(0x10006)));
+		assert!(matches!(
+			MovementBridgeError::from_vm_status("Move abort: code 0x2a"),
+			MovementBridgeError::AbortCode(0x2a)
+		));
+		assert!(matches!(
+			MovementBridgeError::from_vm_status(
+				"Error: Move abort in 0xcafe::atomic_bridge_counterparty: 0x1"
+			),
+			MovementBridgeError::AbortCode(0x1)
+		));
+		assert!(matches!(
+			MovementBridgeError::from_vm_status("OUT_OF_GAS"),
+			MovementBridgeError::TransactionFailed(_)
+		));
+	}
+
+	fn rejected(vm_status: StatusCode) -> RestError {
+		RestError::Api(AptosErrorResponse {
+			error: AptosError::new_with_vm_status(
+				format!("Invalid transaction: Type: Validation Code: {:?}", vm_status),
+				AptosErrorCode::VmError,
+				vm_status,
+			),
+			state: None,
+		})
+	}
+
+	#[test]
+	fn test_rejection_from_rest_error() {
+		let error = MovementBridgeError::from(rejected(StatusCode::TRANSACTION_EXPIRED));
+		assert!(matches!(error, MovementBridgeError::Expired));
+		let error = MovementBridgeError::from(rejected(StatusCode::SEQUENCE_NUMBER_TOO_OLD));
+		assert!(matches!(error, MovementBridgeError::SequenceNumber(_)));
+		let error = MovementBridgeError::from(rejected(StatusCode::INVALID_SIGNATURE));
+		assert!(matches!(error, MovementBridgeError::RpcError(_)));
+		// the text alone doesn't make an error an expiry
+		let error = MovementBridgeError::from(RestError::Unknown(anyhow::anyhow!(
+			"Transaction expired. It is guaranteed it will not be committed on chain."
+		)));
+		assert!(matches!(error, MovementBridgeError::RpcError(_)));
+	}
+
+	#[test]
+	fn test_counterparty_error_keeps_source() {
+		let error = BridgeContractCounterpartyError::from(MovementBridgeError::AbortCode(7));
+		let source = error.source().expect("Expected the chain error as source");
+		assert_eq!(source.to_string(), "Move abort with code 0x7");
+
+		let BridgeContractCounterpartyError::ChainError(chain_error) = error else {
+			panic!("Expected a chain error, got {:?}", error);
+		};
+		assert!(matches!(
+			chain_error.downcast_ref::<MovementBridgeError>(),
+			Some(MovementBridgeError::AbortCode(7))
+		));
+
+		assert_eq!(
+			BridgeContractCounterpartyError::from(MovementBridgeError::Timeout),
+			BridgeContractCounterpartyError::Timeout
+		);
+	}
+
+	#[test]
+	fn test_error_in_context() {
+		let context = TransferContext::new("7f3a").with_tag("source", "eth");
+		let error = MovementBridgeError::InContext {
+			context: context.clone(),
+			source: Box::new(MovementBridgeError::AbortCode(7)),
+		};
+		assert_eq!(error.to_string(), "Move abort with code 0x7 (request 7f3a source=eth)");
+		assert!(matches!(error.without_context(), MovementBridgeError::AbortCode(7)));
+		assert_eq!(error.context(), Some(&context));
+
+		let error = BridgeContractCounterpartyError::from(error);
+		let BridgeContractCounterpartyError::ChainError(chain_error) = error else {
+			panic!("Expected a chain error, got {:?}", error);
+		};
+		assert_eq!(
+			chain_error
+				.downcast_ref::<MovementBridgeError>()
+				.and_then(MovementBridgeError::context),
+			Some(&context)
+		);
+
+		let timeout = MovementBridgeError::InContext {
+			context,
+			source: Box::new(MovementBridgeError::Timeout),
+		};
+		assert_eq!(
+			BridgeContractCounterpartyError::from(timeout),
+			BridgeContractCounterpartyError::Timeout
+		);
+	}
+}
@@ -0,0 +1,156 @@
+use crate::error::MovementBridgeError;
+use crate::types::TransferContext;
+use aptos_sdk::crypto::HashValue;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// A transaction the client was still sending when it drained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransaction {
+	/// The `module::function` called by the transaction.
+	pub function: String,
+	pub context: Option<TransferContext>,
+	/// Set once the transaction is signed, a resubmission replaces it.
+	pub hash: Option<HashValue>,
+}
+
+#[derive(Debug, Default)]
+struct InFlightState {
+	draining: bool,
+	next_id: u64,
+	pending: BTreeMap<u64, PendingTransaction>,
+}
+
+/// Tracks the transactions a client and its clones are sending, so they can be drained
+/// before shutting down.
+#[derive(Debug, Default)]
+pub struct InFlight {
+	state: Mutex<InFlightState>,
+	finished: Notify,
+}
+
+impl InFlight {
+	/// Registers a transaction until the returned guard is dropped.
+	/// Fails with [MovementBridgeError::Draining] once draining started.
+	pub fn start(
+		self: &Arc<Self>,
+		function: String,
+		context: Option<TransferContext>,
+	) -> Result<InFlightGuard, MovementBridgeError> {
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		if state.draining {
+			return Err(MovementBridgeError::Draining);
+		}
+		let id = state.next_id;
+		state.next_id += 1;
+		state.pending.insert(id, PendingTransaction { function, context, hash: None });
+		Ok(InFlightGuard { in_flight: self.clone(), id })
+	}
+
+	pub fn is_draining(&self) -> bool {
+		self.state.lock().unwrap_or_else(|e| e.into_inner()).draining
+	}
+
+	/// Stops registering transactions and waits up to `timeout` for the registered ones
+	/// to finish. Returns those still pending, in the order they started.
+	pub async fn drain(&self, timeout: Duration) -> Vec<PendingTransaction> {
+		let deadline = Instant::now() + timeout;
+		self.state.lock().unwrap_or_else(|e| e.into_inner()).draining = true;
+		loop {
+			// registered before checking, so a transaction finishing in between isn't missed
+			let finished = self.finished.notified();
+			tokio::pin!(finished);
+			finished.as_mut().enable();
+
+			let pending = self.pending();
+			if pending.is_empty() {
+				return pending;
+			}
+			if tokio::time::timeout_at(deadline, finished).await.is_err() {
+				return self.pending();
+			}
+		}
+	}
+
+	fn pending(&self) -> Vec<PendingTransaction> {
+		self.state
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.pending
+			.values()
+			.cloned()
+			.collect()
+	}
+}
+
+/// Keeps a transaction registered in [InFlight], also when the send is cancelled.
+#[derive(Debug)]
+pub struct InFlightGuard {
+	in_flight: Arc<InFlight>,
+	id: u64,
+}
+
+impl InFlightGuard {
+	pub fn set_hash(&self, hash: HashValue) {
+		let mut state = self.in_flight.state.lock().unwrap_or_else(|e| e.into_inner());
+		if let Some(transaction) = state.pending.get_mut(&self.id) {
+			transaction.hash = Some(hash);
+		}
+	}
+}
+
+impl Drop for InFlightGuard {
+	fn drop(&mut self) {
+		self.in_flight
+			.state
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.pending
+			.remove(&self.id);
+		self.in_flight.finished.notify_waiters();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_drain_waits_for_transactions() -> Result<(), MovementBridgeError> {
+		let in_flight = Arc::new(InFlight::default());
+		let first = in_flight.start("counterparty::lock".to_string(), None)?;
+		let second = in_flight
+			.start("counterparty::complete".to_string(), Some(TransferContext::new("7f3a")))?;
+		second.set_hash(HashValue::zero());
+
+		let finish = tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(20)).await;
+			drop(first);
+		});
+		let pending = in_flight.drain(Duration::from_millis(200)).await;
+		finish.await.unwrap();
+
+		// the first finished in time, the second is reported
+		assert_eq!(
+			pending,
+			vec![PendingTransaction {
+				function: "counterparty::complete".to_string(),
+				context: Some(TransferContext::new("7f3a")),
+				hash: Some(HashValue::zero()),
+			}]
+		);
+		assert!(in_flight.is_draining());
+		assert!(matches!(
+			in_flight.start("counterparty::abort".to_string(), None),
+			Err(MovementBridgeError::Draining)
+		));
+
+		drop(second);
+		assert_eq!(in_flight.drain(Duration::ZERO).await, vec![]);
+
+		Ok(())
+	}
+}
@@ -1,14 +1,22 @@
-use crate::utils::MovementAddress;
+use crate::client::FailoverClient;
+use crate::error::MovementBridgeError;
+use crate::in_flight::{InFlight, PendingTransaction};
+use crate::signer::Signer;
+use crate::utils::{GasParams, MovementAddress};
+use alloy::primitives::Uint;
 use anyhow::Result;
 use aptos_api::accounts::Account;
-use aptos_api_types::{EntryFunctionId, MoveModuleId, ViewFunction, ViewRequest};
 use aptos_sdk::{
-	move_types::{
-		identifier::Identifier,
-		language_storage::{ModuleId, TypeTag},
+	move_types::language_storage::TypeTag,
+	rest_client::{
+		aptos_api_types::{AptosErrorCode, Transaction as AptosTransaction},
+		error::RestError,
+		Client, FaucetClient,
+	},
+	types::{
+		transaction::{SignedTransaction, TransactionPayload},
+		LocalAccount,
 	},
-	rest_client::{Client, FaucetClient, Response},
-	types::LocalAccount,
 };
 use aptos_types::account_address::AccountAddress;
 use bridge_shared::{
@@ -21,28 +29,48 @@ use bridge_shared::{
 	},
 };
 use hex::{decode, FromHex};
+use movement_algs::backoff::Backoff;
+use movement_types::proof::StateValueProofResponse;
 use rand::prelude::*;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::Value;
 use std::{env, fs, io::{Read, Write}, path::{Path, PathBuf}, process::{Command, Stdio}};
 use std::str::FromStr;
-use std::sync::{Arc, RwLock};
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+#[cfg(feature = "indexer")]
+use suzuka_client::{indexer_channel::IndexerChannel, indexer_client::SuzukaIndexerClient};
 use tokio::{
 	io::{AsyncBufReadExt, BufReader},
 	process::Command as TokioCommand,
 	sync::oneshot,
 	task,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+use types::{CounterpartyFunctions, MoveAmountType, NodeHealth, TransferContext, TransferState};
 
 use url::Url;
 
-mod types;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod in_flight;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod signer;
+#[cfg(feature = "test-node")]
+pub mod test_node;
+pub mod types;
 pub mod utils;
 
 const DUMMY_ADDRESS: AccountAddress = AccountAddress::new([0; 32]);
 const COUNTERPARTY_MODULE_NAME: &str = "atomic_bridge_counterparty";
+/// The address of the counterparty module when the config sets none
+const DEFAULT_COUNTERPARTY_ADDRESS: &str = "0xcafe";
+/// The abort code of the `bridge_transfers` view for an unknown transfer
+const ETRANSFER_NOT_FOUND: u64 = 0x1;
 
 #[allow(dead_code)]
 enum Call {
@@ -54,27 +82,134 @@ enum Call {
 
 pub struct Config {
 	pub rpc_url: Option<String>,
+	/// Further nodes to fail over to, after `rpc_url`
+	pub rpc_urls: Vec<String>,
 	pub ws_url: Option<String>,
+	/// The faucet funding accounts, on networks that have one
+	pub faucet_url: Option<String>,
+	/// The movement REST service of the node, serving the proofs of
+	/// [MovementClient::get_transfer_with_state_value_proof]
+	pub movement_rest_url: Option<String>,
+	/// The indexer gRPC service following the node, for `MovementClient::indexer_lag` of the
+	/// `indexer` feature
+	pub indexer_url: Option<String>,
 	pub chain_id: String,
 	pub signer_private_key: Arc<RwLock<LocalAccount>>,
 	pub initiator_contract: Option<MovementAddress>,
+	/// The address the counterparty module is published at, `0xcafe` if unset
+	pub counterparty_contract: Option<MovementAddress>,
+	/// The max gas amount of the sent transactions
 	pub gas_limit: u64,
+	/// The price of a gas unit of the sent transactions, at least [utils::GAS_UNIT_PRICE]
+	pub gas_unit_price: u64,
+	/// Seconds until the sent transactions expire, see [GasParams::expiration_secs]
+	pub expiration_secs: u64,
+	/// Deadline of each node call, so a hung node doesn't stall the caller
+	pub request_timeout: Duration,
+	/// The first interval when polling a transfer state, doubling up to `max_poll_interval`
+	pub poll_interval: Duration,
+	pub max_poll_interval: Duration,
+	/// Log the counterparty payloads instead of submitting them
+	pub dry_run: bool,
+	/// Check the state of a transfer before completing it, so a replayed completion isn't submitted
+	pub check_completed: bool,
+	/// The Move integer type the bridge modules take amounts as
+	pub amount_type: MoveAmountType,
+	/// The coin the counterparty locks, passed as the type argument of `lock_bridge_transfer`.
+	/// Needs a counterparty module generic over the coin, see
+	/// [MovementClient::check_lock_coin_type]. None for a module locking its own asset, which
+	/// takes no type argument, like the `atomic_bridge_counterparty` of this repository
+	pub coin_type: Option<TypeTag>,
+	/// The names of the counterparty module functions
+	pub counterparty_functions: CounterpartyFunctions,
 }
 
 impl Config {
+	pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+	pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+	pub const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
 	pub fn build_for_test() -> Self {
 		let seed = [3u8; 32];
 		let mut rng = rand::rngs::StdRng::from_seed(seed);
 
 		Config {
 			rpc_url: Some("http://localhost:8080".parse().unwrap()),
+			rpc_urls: Vec::new(),
 			ws_url: Some("ws://localhost:8080".parse().unwrap()),
+			faucet_url: None,
+			movement_rest_url: Some("http://localhost:30832".to_string()),
+			indexer_url: None,
 			chain_id: 4.to_string(),
 			signer_private_key: Arc::new(RwLock::new(LocalAccount::generate(&mut rng))),
 			initiator_contract: None,
-			gas_limit: 10_000_000_000,
+			counterparty_contract: None,
+			gas_limit: GasParams::default().max_gas_amount,
+			gas_unit_price: utils::GAS_UNIT_PRICE,
+			expiration_secs: GasParams::DEFAULT_EXPIRATION_SECS,
+			request_timeout: Config::DEFAULT_REQUEST_TIMEOUT,
+			poll_interval: Config::DEFAULT_POLL_INTERVAL,
+			max_poll_interval: Config::DEFAULT_MAX_POLL_INTERVAL,
+			dry_run: false,
+			check_completed: true,
+			amount_type: MoveAmountType::default(),
+			coin_type: None,
+			counterparty_functions: CounterpartyFunctions::default(),
+		}
+	}
+
+	/// The nodes to connect to, `rpc_url` first.
+	pub fn rpc_endpoints(&self) -> Result<Vec<Url>, MovementBridgeError> {
+		self.rpc_url
+			.iter()
+			.chain(&self.rpc_urls)
+			.map(|url| {
+				Url::parse(url)
+					.map_err(|e| MovementBridgeError::ConfigInvalid(format!("{}: {}", url, e)))
+			})
+			.collect()
+	}
+
+	/// A copy of the account of `signer_private_key`, for a client to sign with.
+	pub fn signer(&self) -> Result<LocalAccount, MovementBridgeError> {
+		let account = self.signer_private_key.read().map_err(|_| {
+			MovementBridgeError::ConfigInvalid("Signer private key lock poisoned".to_string())
+		})?;
+		Ok(LocalAccount::new(
+			account.address(),
+			account.private_key().clone(),
+			account.sequence_number(),
+		))
+	}
+
+	/// A client of the indexer comparing it with the node at `node_url`, if one is configured.
+	#[cfg(feature = "indexer")]
+	pub fn indexer_client(&self, node_url: &Url) -> Option<SuzukaIndexerClient> {
+		self.indexer_url.as_ref().map(|indexer_url| {
+			SuzukaIndexerClient::new(IndexerChannel::new(indexer_url.clone()))
+				.with_rest_client(Client::new(node_url.clone()))
+		})
+	}
+
+	/// The gas settings of the transactions the client sends.
+	pub fn gas_params(&self) -> GasParams {
+		GasParams {
+			max_gas_amount: self.gas_limit,
+			gas_unit_price: self.gas_unit_price,
+			expiration_secs: self.expiration_secs,
 		}
 	}
+
+	/// The movement REST service to fetch proofs from, if one is configured.
+	pub fn movement_rest_endpoint(&self) -> Result<Option<Url>, MovementBridgeError> {
+		self.movement_rest_url
+			.as_ref()
+			.map(|url| {
+				Url::parse(url)
+					.map_err(|e| MovementBridgeError::ConfigInvalid(format!("{}: {}", url, e)))
+			})
+			.transpose()
+	}
 }
 
 #[allow(dead_code)]
@@ -84,40 +219,107 @@ pub struct MovementClient {
 	pub counterparty_address: AccountAddress,
 	///Address of the initiator module
 	initiator_address: Vec<u8>,
+	///The Apotos Rest Clients of the node pool
+	pub rest_client: FailoverClient,
 	///The Apotos Rest Client
-	pub rest_client: Client,
-	///The Apotos Rest Client
-	pub faucet_client: Option<Arc<RwLock<FaucetClient>>>,
-	///The signer account
-	signer: Arc<LocalAccount>,
+	pub faucet_client: Option<Arc<FaucetClient>>,
+	///The movement REST service serving transaction proofs
+	movement_rest_url: Option<Url>,
+	///The HTTP client of the movement REST service
+	http_client: reqwest::Client,
+	///The indexer following the node, compared with the node for its lag
+	#[cfg(feature = "indexer")]
+	indexer_client: Option<SuzukaIndexerClient>,
+	///The signer of the client's transactions
+	signer: Arc<dyn Signer>,
+	///Deadline of each node call
+	request_timeout: Duration,
+	///Bounds of the transfer state polling interval
+	poll_interval: Duration,
+	max_poll_interval: Duration,
+	///Skip submitting counterparty payloads
+	dry_run: bool,
+	///Check transfers aren't completed before completing them
+	check_completed: bool,
+	///The last payload built in dry-run mode
+	last_payload: Arc<RwLock<Option<TransactionPayload>>>,
+	///The error of the last failed transaction
+	last_error: Arc<Mutex<Option<String>>>,
+	///The Move integer type of amount arguments
+	amount_type: MoveAmountType,
+	///Gas settings of the sent transactions
+	gas_params: GasParams,
+	///The coin locked by default
+	coin_type: Option<TypeTag>,
+	///The counterparty and coin the lock function was last checked to take, shared with the clones
+	checked_coin_type: Arc<Mutex<Option<(AccountAddress, TypeTag)>>>,
+	///The names of the counterparty module functions
+	counterparty_functions: CounterpartyFunctions,
+	///Recorded in the spans and transaction errors of the calls
+	context: Option<TransferContext>,
+	///The transactions being sent, shared with the clones
+	in_flight: Arc<InFlight>,
 }
 
 impl MovementClient {
-	pub async fn new(_config: Config) -> Result<Self, anyhow::Error> {
-		let node_connection_url = "http://127.0.0.1:8080".to_string();
-		let node_connection_url = Url::from_str(node_connection_url.as_str())
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-
-		let rest_client = Client::new(node_connection_url.clone());
+	pub async fn new(config: Config) -> Result<Self, anyhow::Error> {
+		let mut rpc_endpoints = config.rpc_endpoints()?;
+		if rpc_endpoints.is_empty() {
+			let node_connection_url = "http://127.0.0.1:8080".to_string();
+			rpc_endpoints.push(
+				Url::from_str(node_connection_url.as_str())
+					.map_err(|e| MovementBridgeError::ConfigInvalid(e.to_string()))?,
+			);
+		}
+		let faucet_client = match &config.faucet_url {
+			Some(faucet_url) => {
+				let faucet_url = Url::parse(faucet_url).map_err(|e| {
+					MovementBridgeError::ConfigInvalid(format!("{}: {}", faucet_url, e))
+				})?;
+				Some(Arc::new(FaucetClient::new(faucet_url, rpc_endpoints[0].clone())))
+			}
+			None => None,
+		};
+		#[cfg(feature = "indexer")]
+		let indexer_client = config.indexer_client(&rpc_endpoints[0]);
+		let rest_client = FailoverClient::new(rpc_endpoints)?;
+		let movement_rest_url = config.movement_rest_endpoint()?;
 
-		let seed = [3u8; 32];
-		let mut rng = rand::rngs::StdRng::from_seed(seed);
-		let signer = LocalAccount::generate(&mut rng);
+		let signer = config.signer()?;
 
-		let mut address_bytes = [0u8; AccountAddress::LENGTH];
-		address_bytes[0..2].copy_from_slice(&[0xca, 0xfe]);
-		let counterparty_address = AccountAddress::new(address_bytes);
+		let counterparty_address = match &config.counterparty_contract {
+			Some(address) => address.0,
+			None => AccountAddress::from_hex_literal(DEFAULT_COUNTERPARTY_ADDRESS)?,
+		};
 		Ok(MovementClient {
 			counterparty_address,
 			initiator_address: Vec::new(), //dummy for now
 			rest_client,
-			faucet_client: None,
+			faucet_client,
+			movement_rest_url,
+			http_client: reqwest::Client::new(),
+			#[cfg(feature = "indexer")]
+			indexer_client,
 			signer: Arc::new(signer),
+			request_timeout: config.request_timeout,
+			poll_interval: config.poll_interval,
+			max_poll_interval: config.max_poll_interval,
+			dry_run: config.dry_run,
+			check_completed: config.check_completed,
+			last_payload: Arc::new(RwLock::new(None)),
+			last_error: Arc::new(Mutex::new(None)),
+			amount_type: config.amount_type,
+			gas_params: config.gas_params(),
+			coin_type: config.coin_type,
+			checked_coin_type: Arc::default(),
+			counterparty_functions: config.counterparty_functions,
+			context: None,
+			in_flight: Arc::default(),
 		})
 	}
 
 	pub async fn new_for_test(
-		_config: Config,
+		config: Config,
 	) -> Result<(Self, tokio::process::Child), anyhow::Error> {
 
 		let kill_cmd = TokioCommand::new("sh")
@@ -206,24 +408,39 @@ impl MovementClient {
 		let node_connection_url = "http://127.0.0.1:8080".to_string();
 		let node_connection_url = Url::from_str(node_connection_url.as_str())
 			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let rest_client = Client::new(node_connection_url.clone());
+		let rest_client = FailoverClient::new(vec![node_connection_url.clone()])?;
 
 		let faucet_url = "http://127.0.0.1:8081".to_string();
 		let faucet_url = Url::from_str(faucet_url.as_str())
 			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let faucet_client = Arc::new(RwLock::new(FaucetClient::new(
-			faucet_url.clone(),
-			node_connection_url.clone(),
-		)));
+		let faucet_client = Arc::new(FaucetClient::new(faucet_url, node_connection_url.clone()));
 
-		let mut rng = ::rand::rngs::StdRng::from_seed([3u8; 32]);
+		let signer = config.signer()?;
 		Ok((
 			MovementClient {
 				counterparty_address: DUMMY_ADDRESS,
 				initiator_address: Vec::new(), // dummy for now
 				rest_client,
 				faucet_client: Some(faucet_client),
-				signer: Arc::new(LocalAccount::generate(&mut rng)),
+				movement_rest_url: config.movement_rest_endpoint()?,
+				http_client: reqwest::Client::new(),
+				#[cfg(feature = "indexer")]
+				indexer_client: config.indexer_client(&node_connection_url),
+				signer: Arc::new(signer),
+				request_timeout: config.request_timeout,
+				poll_interval: config.poll_interval,
+				max_poll_interval: config.max_poll_interval,
+				dry_run: config.dry_run,
+				check_completed: config.check_completed,
+				last_payload: Arc::new(RwLock::new(None)),
+				last_error: Arc::new(Mutex::new(None)),
+				amount_type: config.amount_type,
+				gas_params: config.gas_params(),
+				coin_type: config.coin_type,
+				checked_coin_type: Arc::default(),
+				counterparty_functions: config.counterparty_functions,
+				context: None,
+				in_flight: Arc::default(),
 			},
 			child,
 		))
@@ -240,7 +457,11 @@ impl MovementClient {
 			.spawn()
 			.expect("Failed to execute command");
 
-		let private_key_hex = hex::encode(self.signer.private_key().to_bytes());
+		let local_account = self
+			.signer
+			.local_account()
+			.ok_or_else(|| anyhow::anyhow!("Publishing needs the private key of a local signer"))?;
+		let private_key_hex = hex::encode(local_account.private_key().to_bytes());
 
 		let stdin: &mut std::process::ChildStdin =
 			process.stdin.as_mut().expect("Failed to open stdin");
@@ -455,20 +676,215 @@ impl MovementClient {
 		Ok(())
 	}
 
+	/// The client of the node transactions are currently submitted to.
 	pub fn rest_client(&self) -> &Client {
-		&self.rest_client
+		self.rest_client.current()
 	}
 
-	pub fn signer(&self) -> &LocalAccount {
-		&self.signer
+	/// Bounds a node call by the configured request timeout.
+	async fn with_timeout<F: Future>(
+		&self,
+		future: F,
+	) -> Result<F::Output, tokio::time::error::Elapsed> {
+		tokio::time::timeout(self.request_timeout, future).await
 	}
 
-	pub fn faucet_client(&self) -> Result<&Arc<RwLock<FaucetClient>>> {
-		if let Some(faucet_client) = &self.faucet_client {
-			Ok(faucet_client)
-		} else {
-			Err(anyhow::anyhow!("Faucet client not initialized"))
+	/// The last counterparty payload built in dry-run mode.
+	pub fn last_payload(&self) -> Option<TransactionPayload> {
+		self.last_payload.read().ok().and_then(|payload| payload.clone())
+	}
+
+	/// The most recent error of a transaction the client sent, for diagnostics when the error
+	/// returned to the caller is coarse.
+	pub fn last_error(&self) -> Option<String> {
+		self.last_error.lock().ok().and_then(|last_error| last_error.clone())
+	}
+
+	/// Stops sending transactions, on this client and its clones, and waits up to `timeout`
+	/// for the ones in flight to be confirmed, e.g., before a restart.
+	///
+	/// Returns the transactions still pending, later sends fail with
+	/// [MovementBridgeError::Draining].
+	pub async fn drain(&self, timeout: Duration) -> Vec<PendingTransaction> {
+		let pending = self.in_flight.drain(timeout).await;
+		for transaction in &pending {
+			warn!(
+				"Transaction {} still pending after draining for {:?}: {:?}",
+				transaction.function, timeout, transaction
+			);
 		}
+		pending
+	}
+
+	pub fn is_draining(&self) -> bool {
+		self.in_flight.is_draining()
+	}
+
+	/// Sends and confirms a transaction through the node pool, each node request within the
+	/// request timeout, recording a failure as the [MovementClient::last_error].
+	async fn send_aptos_transaction(
+		&self,
+		payload: TransactionPayload,
+	) -> Result<(), MovementBridgeError> {
+		self.send_and_confirm_aptos_transaction(payload).await.map(|_| ())
+	}
+
+	/// Sends the transaction like [MovementClient::send_aptos_transaction], returning it as
+	/// committed, e.g., to read its events.
+	async fn send_and_confirm_aptos_transaction(
+		&self,
+		payload: TransactionPayload,
+	) -> Result<AptosTransaction, MovementBridgeError> {
+		let signer = self.signer.as_ref();
+		let gas_params = self.gas_params;
+		let result =
+			match self.in_flight.start(utils::payload_function(&payload), self.context.clone()) {
+				Ok(guard) => {
+					let on_signed = |hash| guard.set_hash(hash);
+					let on_signed = &on_signed;
+					let request_timeout = self.request_timeout;
+					self.rest_client
+						.write(|client| async move {
+							utils::send_and_confirm_aptos_transaction_with(
+								&client,
+								signer,
+								payload,
+								gas_params,
+								request_timeout,
+								on_signed,
+							)
+							.await
+						})
+						.await
+				}
+				Err(e) => Err(e),
+			};
+		let result = result.map_err(|e| match &self.context {
+			Some(context) => {
+				MovementBridgeError::InContext { context: context.clone(), source: Box::new(e) }
+			}
+			None => e,
+		});
+		match &result {
+			Ok(txn) => utils::record_transaction(txn),
+			Err(e) => self.record_error(e),
+		}
+		result
+	}
+
+	/// Calls a view function through the nodes of the client, see [utils::send_view_function].
+	pub async fn view_function(
+		&self,
+		address: AccountAddress,
+		module: &str,
+		function: &str,
+		type_args: Vec<TypeTag>,
+		args: Vec<Vec<u8>>,
+		version: Option<u64>,
+	) -> Result<Vec<Value>, MovementBridgeError> {
+		let (type_args, args) = (&type_args, &args);
+		self.with_timeout(self.rest_client.read(|client| async move {
+			utils::send_view_function(
+				&client,
+				address,
+				module,
+				function,
+				type_args.clone(),
+				args.clone(),
+				version,
+			)
+			.await
+		}))
+		.await
+		.map_err(|_| MovementBridgeError::Timeout)?
+	}
+
+	fn record_error(&self, error: &MovementBridgeError) {
+		if let Ok(mut last_error) = self.last_error.lock() {
+			*last_error = Some(error.to_string());
+		}
+	}
+
+	/// In dry-run mode, logs and records the payload and returns true so it isn't submitted.
+	fn dry_run_payload(&self, payload: &TransactionPayload) -> bool {
+		if !self.dry_run {
+			return false;
+		}
+		if let TransactionPayload::EntryFunction(entry_function) = payload {
+			info!(
+				"Dry run {}::{} args: {:?}",
+				entry_function.module(),
+				entry_function.function(),
+				entry_function.args().iter().map(hex::encode).collect::<Vec<_>>()
+			);
+		}
+		if let Ok(mut last_payload) = self.last_payload.write() {
+			*last_payload = Some(payload.clone());
+		}
+		true
+	}
+
+	pub fn signer(&self) -> &dyn Signer {
+		self.signer.as_ref()
+	}
+
+	/// Signs with `signer` instead of the configured signer key, e.g., a remote signer.
+	pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+		self.signer = signer;
+		self
+	}
+
+	pub fn gas_params(&self) -> GasParams {
+		self.gas_params
+	}
+
+	/// Sends transactions with `gas_params` instead of the defaults.
+	pub fn with_gas_params(mut self, gas_params: GasParams) -> Self {
+		self.gas_params = gas_params;
+		self
+	}
+
+	/// The coin [BridgeContractCounterparty::lock_bridge_transfer] locks.
+	pub fn coin_type(&self) -> Option<&TypeTag> {
+		self.coin_type.as_ref()
+	}
+
+	/// Locks `coin_type` instead of the configured coin, see [Config::coin_type].
+	pub fn with_coin_type(mut self, coin_type: TypeTag) -> Self {
+		self.coin_type = Some(coin_type);
+		self
+	}
+
+	pub fn context(&self) -> Option<&TransferContext> {
+		self.context.as_ref()
+	}
+
+	/// Attaches `context` to the calls of the client, e.g., on a clone per transfer.
+	pub fn with_context(mut self, context: TransferContext) -> Self {
+		self.context = Some(context);
+		self
+	}
+
+	/// Turns the completion pre-check off, e.g., when the caller already verified the state.
+	pub fn with_check_completed(mut self, check_completed: bool) -> Self {
+		self.check_completed = check_completed;
+		self
+	}
+
+	/// The faucet client, shared without a lock since funding only takes `&self`.
+	///
+	/// Only clients created with a `faucet_url` in their config, or by
+	/// [MovementClient::new_for_test], have one.
+	pub fn faucet_client(&self) -> Result<&Arc<FaucetClient>, MovementBridgeError> {
+		self.faucet_client.as_ref().ok_or(MovementBridgeError::FaucetNotConfigured)
+	}
+
+	/// Funds `address` with `amount` octas from the faucet.
+	pub async fn fund(&self, address: AccountAddress, amount: u64) -> Result<()> {
+		self.faucet_client()?
+			.fund(address, amount)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to fund {} from the faucet: {}", address, e))
 	}
 }
 
@@ -477,98 +893,96 @@ impl BridgeContractCounterparty for MovementClient {
 	type Address = MovementAddress;
 	type Hash = [u8; 32];
 
+	#[tracing::instrument(
+		skip_all,
+		fields(
+			bridge_transfer_id = %hex::encode(bridge_transfer_id.0),
+			context = self.context.as_ref().map(tracing::field::display),
+			tx_hash = tracing::field::Empty,
+			gas_used = tracing::field::Empty
+		)
+	)]
 	async fn lock_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId<Self::Hash>,
 		hash_lock: HashLock<Self::Hash>,
-		time_lock: TimeLock,
+		// the counterparty module sets its own time lock
+		_time_lock: TimeLock,
 		initiator: InitiatorAddress<Vec<u8>>,
 		recipient: RecipientAddress<Self::Address>,
 		amount: Amount,
 	) -> BridgeContractCounterpartyResult<()> {
-		let amount_value = match amount.0 {
-			AssetType::Moveth(value) => value,
-			_ => return Err(BridgeContractCounterpartyError::SerializationError),
-		};
-
-		let args = vec![
-			utils::serialize_vec(&initiator.0)?,
-			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec(&hash_lock.0[..])?,
-			utils::serialize_u64(&time_lock.0)?,
-			utils::serialize_vec(&recipient.0 .0)?,
-			utils::serialize_u64(&amount_value)?,
-		];
-
-		let payload = utils::make_aptos_payload(
-			self.counterparty_address,
-			COUNTERPARTY_MODULE_NAME,
-			"lock_bridge_transfer",
-			Vec::new(),
-			args,
-		);
-
-		let result = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractCounterpartyError::LockTransferError);
+		let payload = self.lock_bridge_transfer_payload(
+			bridge_transfer_id,
+			hash_lock,
+			initiator,
+			recipient,
+			amount,
+			self.coin_type.clone(),
+		)?;
+
+		if self.dry_run_payload(&payload) {
+			return Ok(());
+		}
+		self.check_configured_coin_type().await?;
 
+		self.send_aptos_transaction(payload).await?;
 		Ok(())
 	}
 
+	#[tracing::instrument(
+		skip_all,
+		fields(
+			bridge_transfer_id = %hex::encode(bridge_transfer_id.0),
+			context = self.context.as_ref().map(tracing::field::display),
+			tx_hash = tracing::field::Empty,
+			gas_used = tracing::field::Empty
+		)
+	)]
 	async fn complete_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId<Self::Hash>,
 		preimage: HashLockPreImage,
 	) -> BridgeContractCounterpartyResult<()> {
-		let args2 = vec![
-			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec(&preimage.0)?,
-		];
+		let payload = self.complete_bridge_transfer_payload(bridge_transfer_id.clone(), preimage)?;
 
-		let payload = utils::make_aptos_payload(
-			self.counterparty_address,
-			COUNTERPARTY_MODULE_NAME,
-			"complete_bridge_transfer",
-			Vec::new(),
-			args2,
-		);
-
-		let result = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractCounterpartyError::CompleteTransferError);
+		if self.dry_run_payload(&payload) {
+			return Ok(());
+		}
+		self.check_not_completed(bridge_transfer_id).await?;
 
+		self.send_aptos_transaction(payload).await?;
 		Ok(())
 	}
 
+	#[tracing::instrument(
+		skip_all,
+		fields(
+			bridge_transfer_id = %hex::encode(bridge_transfer_id.0),
+			context = self.context.as_ref().map(tracing::field::display),
+			tx_hash = tracing::field::Empty,
+			gas_used = tracing::field::Empty
+		)
+	)]
 	async fn abort_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId<Self::Hash>,
 	) -> BridgeContractCounterpartyResult<()> {
 		let args3 = vec![utils::serialize_vec(&bridge_transfer_id.0[..])?];
-		let payload = utils::make_aptos_payload(
+		let payload = utils::try_make_aptos_payload(
 			self.counterparty_address,
 			COUNTERPARTY_MODULE_NAME,
-			"abort_bridge_transfer",
+			self.counterparty_functions.function(Call::Abort),
 			Vec::new(),
 			args3,
-		);
-		let result = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractCounterpartyError::AbortTransferError);
+		)?;
+		if self.dry_run_payload(&payload) {
+			return Ok(());
+		}
 
-		println!("Abort bridge transfer result: {:?}", &result);
+		let result = self.send_aptos_transaction(payload).await;
+		debug!("Abort bridge transfer result: {:?}", &result);
+		result?;
 		Ok(())
 	}
 
@@ -579,74 +993,570 @@ impl BridgeContractCounterparty for MovementClient {
 		Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>,
 		BridgeContractCounterpartyError,
 	> {
-		// Convert the bridge_transfer_id to a hex string
-		let bridge_transfer_id_hex = format!("0x{}", hex::encode(bridge_transfer_id.0));
-
-		// Construct the ViewRequest
-		let view_request = ViewRequest {
-			function: EntryFunctionId {
-				module: MoveModuleId {
-					address: self.counterparty_address.clone().into(),
-					name: aptos_api_types::IdentifierWrapper(
-						Identifier::new("atomic_bridge_counterparty")
-							.map_err(|_| BridgeContractCounterpartyError::FunctionViewError)?,
-					),
-				},
-				name: aptos_api_types::IdentifierWrapper(
-					Identifier::new("bridge_transfers")
-						.map_err(|_| BridgeContractCounterpartyError::FunctionViewError)?,
-				),
-			},
-			type_arguments: vec![],
-			arguments: vec![serde_json::json!(bridge_transfer_id_hex)],
+		self.counterparty_transfer_details(bridge_transfer_id, None).await
+	}
+}
+
+impl MovementClient {
+	/// Builds the counterparty `lock_bridge_transfer` payload, with the arguments in the order
+	/// of the Move entry function. A `coin_type` becomes its type argument, which only a module
+	/// generic over the coin takes, see [MovementClient::check_lock_coin_type].
+	///
+	/// The entry function takes no time lock, the module derives it from the current time.
+	pub fn lock_bridge_transfer_payload(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+		hash_lock: HashLock<[u8; 32]>,
+		initiator: InitiatorAddress<Vec<u8>>,
+		recipient: RecipientAddress<MovementAddress>,
+		amount: Amount,
+		coin_type: Option<TypeTag>,
+	) -> BridgeContractCounterpartyResult<TransactionPayload> {
+		let amount_value = match amount.0 {
+			AssetType::Moveth(value) => Uint::from(value),
+			AssetType::Token(value) => value,
+			AssetType::EthAndWeth(_) => {
+				return Err(BridgeContractCounterpartyError::SerializationError)
+			}
+		};
+
+		let args = vec![
+			utils::serialize_vec(&initiator.0)?,
+			utils::serialize_vec(&bridge_transfer_id.0[..])?,
+			utils::serialize_vec(&hash_lock.0[..])?,
+			utils::serialize_vec(&recipient.0 .0)?,
+			utils::serialize_amount(amount_value, self.amount_type)?,
+		];
+
+		Ok(utils::try_make_aptos_payload(
+			self.counterparty_address,
+			COUNTERPARTY_MODULE_NAME,
+			self.counterparty_functions.function(Call::Lock),
+			coin_type.into_iter().collect(),
+			args,
+		)?)
+	}
+
+	/// Checks the counterparty lock function takes a coin type argument by its ABI on chain,
+	/// if a `coin_type` is given, e.g., at startup. A coin passed to a module locking its own
+	/// asset, like the `atomic_bridge_counterparty` of this repository, aborts every lock.
+	pub async fn check_lock_coin_type(
+		&self,
+		coin_type: Option<&TypeTag>,
+	) -> Result<(), MovementBridgeError> {
+		let Some(coin_type) = coin_type else {
+			return Ok(());
+		};
+		let address = self.counterparty_address;
+		let module = self
+			.with_timeout(self.rest_client.read(|client| async move {
+				client.get_account_module(address, COUNTERPARTY_MODULE_NAME).await
+			}))
+			.await
+			.map_err(|_| MovementBridgeError::Timeout)??
+			.into_inner();
+		let function = self.counterparty_functions.function(Call::Lock);
+		let type_params = module.abi.as_ref().and_then(|abi| {
+			abi.exposed_functions
+				.iter()
+				.find(|exposed| exposed.name.as_str() == function)
+				.map(|exposed| exposed.generic_type_params.len())
+		});
+		match type_params {
+			Some(1) => Ok(()),
+			_ => Err(MovementBridgeError::CoinTypeUnsupported {
+				function: function.to_string(),
+				coin_type: coin_type.to_string(),
+			}),
+		}
+	}
+
+	/// Checks the configured coin type with [MovementClient::check_lock_coin_type] once per
+	/// counterparty address and coin, rather than fetching the module ABI on every lock.
+	async fn check_configured_coin_type(&self) -> Result<(), MovementBridgeError> {
+		let Some(coin_type) = &self.coin_type else {
+			return Ok(());
 		};
+		let checked = (self.counterparty_address, coin_type.clone());
+		if self.checked_coin_type.lock().is_ok_and(|last| last.as_ref() == Some(&checked)) {
+			return Ok(());
+		}
+		self.check_lock_coin_type(Some(coin_type)).await?;
+		if let Ok(mut last) = self.checked_coin_type.lock() {
+			*last = Some(checked);
+		}
+		Ok(())
+	}
 
-		// Send the request to the "/view" endpoint using JSON
-		let response: Response<Vec<serde_json::Value>> = self
+	/// Builds the counterparty `complete_bridge_transfer` payload.
+	pub fn complete_bridge_transfer_payload(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+		preimage: HashLockPreImage,
+	) -> BridgeContractCounterpartyResult<TransactionPayload> {
+		let args = vec![
+			utils::serialize_vec(&bridge_transfer_id.0[..])?,
+			utils::serialize_vec(&preimage.0)?,
+		];
+
+		Ok(utils::try_make_aptos_payload(
+			self.counterparty_address,
+			COUNTERPARTY_MODULE_NAME,
+			self.counterparty_functions.function(Call::Complete),
+			Vec::new(),
+			args,
+		)?)
+	}
+
+	/// Submits signed transactions to the current node in one round trip, without waiting for
+	/// them. Returns whether each was accepted, in order.
+	pub async fn submit_batch(
+		&self,
+		txns: Vec<SignedTransaction>,
+	) -> Vec<Result<(), MovementBridgeError>> {
+		match self
+			.with_timeout(utils::submit_aptos_transactions(self.rest_client.current(), &txns))
+			.await
+		{
+			Ok(results) => results,
+			Err(_) => txns.iter().map(|_| Err(MovementBridgeError::Timeout)).collect(),
+		}
+	}
+
+	/// Completes many counterparty transfers at once, submitting them through the node pool under
+	/// consecutive sequence numbers before waiting for any. Returns one result per item, in order.
+	///
+	/// The pre-checks of the items run concurrently, and the transactions are tracked as in
+	/// flight. An item that timed out is checked again, so a transfer that was completed anyway
+	/// isn't reported as failed.
+	pub async fn complete_bridge_transfers_batch(
+		&mut self,
+		items: Vec<(BridgeTransferId<[u8; 32]>, HashLockPreImage)>,
+	) -> Vec<BridgeContractCounterpartyResult<()>> {
+		let mut results = Vec::with_capacity(items.len());
+		let mut checked = Vec::with_capacity(items.len());
+		for (bridge_transfer_id, preimage) in items {
+			match self.complete_bridge_transfer_payload(bridge_transfer_id.clone(), preimage) {
+				Ok(payload) if self.dry_run_payload(&payload) => results.push(Ok(())),
+				Ok(payload) => {
+					checked.push((results.len(), bridge_transfer_id, payload));
+					results.push(Ok(()));
+				}
+				Err(e) => results.push(Err(e)),
+			}
+		}
+		let checks = futures::future::join_all(checked.iter().map(|(_, bridge_transfer_id, _)| {
+			self.check_not_completed(bridge_transfer_id.clone())
+		}))
+		.await;
+		let mut positions = Vec::with_capacity(checked.len());
+		let mut bridge_transfer_ids = Vec::with_capacity(checked.len());
+		let mut payloads = Vec::with_capacity(checked.len());
+		for ((position, bridge_transfer_id, payload), check) in checked.into_iter().zip(checks) {
+			match check {
+				Ok(()) => {
+					positions.push(position);
+					bridge_transfer_ids.push(bridge_transfer_id);
+					payloads.push(payload);
+				}
+				Err(e) => results[position] = Err(e),
+			}
+		}
+		if payloads.is_empty() {
+			return results;
+		}
+		let guards = match payloads
+			.iter()
+			.map(|payload| {
+				self.in_flight.start(utils::payload_function(payload), self.context.clone())
+			})
+			.collect::<Result<Vec<_>, _>>()
+		{
+			Ok(guards) => guards,
+			Err(_) => {
+				for position in positions {
+					results[position] = Err(MovementBridgeError::Draining.into());
+				}
+				return results;
+			}
+		};
+
+		let on_signed = |index: usize, hash| guards[index].set_hash(hash);
+		let on_signed = &on_signed;
+		let signer = self.signer.as_ref();
+		let gas_params = self.gas_params;
+		let request_timeout = self.request_timeout;
+		let submitted = self
 			.rest_client
-			.view(&view_request, None)
+			.write(|client| async move {
+				utils::send_and_confirm_aptos_transactions(
+					&client,
+					signer,
+					payloads,
+					gas_params,
+					request_timeout,
+					on_signed,
+				)
+				.await
+			})
+			.await
+			.unwrap_or_else(|e| {
+				let mut submitted = vec![Err(e)];
+				submitted.resize_with(positions.len(), || Err(MovementBridgeError::NotSubmitted));
+				submitted
+			});
+		for ((position, bridge_transfer_id), result) in
+			positions.into_iter().zip(bridge_transfer_ids).zip(submitted)
+		{
+			let result = match result {
+				// the transaction may have been committed after all
+				Err(MovementBridgeError::Timeout) => {
+					match self.transfer_state(bridge_transfer_id).await {
+						Ok(TransferState::Completed) => Ok(()),
+						_ => Err(MovementBridgeError::Timeout),
+					}
+				}
+				result => result.map(|_| ()),
+			};
+			results[position] = result.map_err(|e| {
+				tracing::warn!("Failed to complete bridge transfer: {}", e);
+				self.record_error(&e);
+				e.into()
+			});
+		}
+		results
+	}
+
+	/// Reads the counterparty bridge transfer details, optionally at a given ledger version.
+	async fn counterparty_transfer_details(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+		version: Option<u64>,
+	) -> BridgeContractCounterpartyResult<Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>>
+	{
+		let args = vec![utils::serialize_vec(&bridge_transfer_id.0[..])?];
+		let values = match self
+			.view_function(
+				self.counterparty_address,
+				COUNTERPARTY_MODULE_NAME,
+				self.counterparty_functions.function(Call::GetDetails),
+				Vec::new(),
+				args,
+				version,
+			)
 			.await
-			.map_err(|_| BridgeContractCounterpartyError::CallError)?;
+		{
+			Ok(values) => values,
+			Err(MovementBridgeError::AbortCode(ETRANSFER_NOT_FOUND)) => return Ok(None),
+			Err(MovementBridgeError::Timeout) => {
+				return Err(BridgeContractCounterpartyError::Timeout)
+			}
+			Err(_) => return Err(BridgeContractCounterpartyError::CallError),
+		};
 
-		// Extract and parse the response
-		let values = response.inner();
+		let details = utils::decode_transfer_details(
+			bridge_transfer_id,
+			Value::Array(values),
+			self.amount_type,
+		)?;
+		Ok(Some(details))
+	}
 
-		if values.len() != 6 {
-			return Err(BridgeContractCounterpartyError::InvalidResponseLength);
+	/// Reads the state of a counterparty bridge transfer, e.g., to skip completing or aborting
+	/// a transfer that already was.
+	pub async fn transfer_state(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	) -> BridgeContractCounterpartyResult<TransferState> {
+		let details = self.counterparty_transfer_details(bridge_transfer_id, None).await?;
+		Self::state_of(details.as_ref())
+	}
+
+	/// With the pre-check on, fails with [BridgeContractCounterpartyError::AlreadyCompleted] for
+	/// a completed transfer, so completing it again doesn't waste gas on a certain abort.
+	async fn check_not_completed(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	) -> BridgeContractCounterpartyResult<()> {
+		if self.check_completed
+			&& self.transfer_state(bridge_transfer_id).await? == TransferState::Completed
+		{
+			return Err(BridgeContractCounterpartyError::AlreadyCompleted);
 		}
+		Ok(())
+	}
 
-		let originator = utils::val_as_str(values.get(0))?;
-		let recipient = utils::val_as_str(values.get(1))?;
-		let amount = utils::val_as_str(values.get(2))?
-			.parse::<u64>()
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let hash_lock = utils::val_as_str(values.get(3))?;
-		let time_lock = utils::val_as_str(values.get(4))?
-			.parse::<u64>()
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let state = utils::val_as_u64(values.get(5))? as u8;
+	/// Polls the state of a counterparty bridge transfer until it reaches `target`, e.g., to wait
+	/// for the initiator side to complete a locked transfer. The interval is a [Backoff] from the
+	/// configured poll interval up to the max poll interval. Errors of a poll are returned.
+	///
+	/// Returns the details of the transfer in the target state, `None` for [TransferState::NotFound].
+	pub async fn wait_for_transfer_state(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+		target: TransferState,
+		timeout: Duration,
+	) -> BridgeContractCounterpartyResult<Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>>
+	{
+		let deadline = tokio::time::Instant::now() + timeout;
+		let mut backoff = Backoff::new(self.poll_interval, self.max_poll_interval);
+		loop {
+			let details =
+				self.counterparty_transfer_details(bridge_transfer_id.clone(), None).await?;
+			if Self::state_of(details.as_ref())? == target {
+				return Ok(details);
+			}
 
-		// Convert the originator, recipient, and hash_lock
-		let originator_address = AccountAddress::from_hex_literal(originator)
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let recipient_address_bytes = hex::decode(&recipient[2..])
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
-		let hash_lock_array: [u8; 32] = hex::decode(&hash_lock[2..])
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?
-			.try_into()
-			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
+			let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+			if remaining.is_zero() {
+				return Err(BridgeContractCounterpartyError::Timeout);
+			}
+			tokio::time::sleep(backoff.next_delay().min(remaining)).await;
+		}
+	}
 
-		// Create the BridgeTransferDetails struct
-		let details: BridgeTransferDetails<MovementAddress, [u8; 32]> = BridgeTransferDetails {
+	fn state_of(
+		details: Option<&BridgeTransferDetails<MovementAddress, [u8; 32]>>,
+	) -> BridgeContractCounterpartyResult<TransferState> {
+		match details {
+			Some(details) => TransferState::from_state(details.state)
+				.ok_or(BridgeContractCounterpartyError::SerializationError),
+			None => Ok(TransferState::NotFound),
+		}
+	}
+
+	/// Reads a counterparty bridge transfer together with a proof anchoring it to the ledger state.
+	///
+	/// The transfer is read at the end of the latest block, whose transaction info carries the
+	/// state root. The bucket of the `transfers` table holding it is proven in that state, see
+	/// [utils::verify_state_value_proof], and the details are decoded from the proven bucket.
+	/// The ledger info the proof leads to is checked against the accumulator root the node
+	/// committed, see [utils::verify_committed_ledger_info].
+	///
+	/// The node is trusted: the ledger info is checked against the node's own transactions, not
+	/// the validator signatures, so a counterpart chain has to verify those itself.
+	pub async fn get_transfer_with_state_value_proof(
+		&self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	) -> Result<(BridgeTransferDetails<MovementAddress, [u8; 32]>, StateValueProofResponse)> {
+		let movement_rest_url = self
+			.movement_rest_url
+			.as_ref()
+			.ok_or_else(|| anyhow::anyhow!("No movement REST service configured to read proofs"))?;
+		let version = self
+			.with_timeout(
+				self.rest_client.read(|client| async move { client.get_ledger_information().await }),
+			)
+			.await??
+			.into_inner()
+			.version;
+
+		let block = self
+			.with_timeout(self.rest_client.read(|client| async move {
+				client.get_block_by_version(version, false).await
+			}))
+			.await??
+			.into_inner();
+		let block_end_version = block.last_version.0;
+
+		let counterparty_address = self.counterparty_address;
+		let config_type = format!(
+			"{}::atomic_bridge_counterparty::BridgeConfig",
+			counterparty_address.to_hex_literal()
+		);
+		let config = self.resource_at(counterparty_address, &config_type, block_end_version).await?;
+		let store_address = config["bridge_module_deployer"]
+			.as_str()
+			.and_then(|address| AccountAddress::from_hex_literal(address).ok())
+			.ok_or_else(|| anyhow::anyhow!("Invalid bridge config {}", config))?;
+		let store_type = format!(
+			"{}::atomic_bridge_counterparty::BridgeTransferStore",
+			counterparty_address.to_hex_literal()
+		);
+		let store = self.resource_at(store_address, &store_type, block_end_version).await?;
+		let location = utils::locate_bridge_transfer(&store, bridge_transfer_id.clone())?;
+
+		let proof = self
+			.with_timeout(utils::fetch_state_value_proof(
+				&self.http_client,
+				movement_rest_url,
+				block_end_version,
+				&location.state_key,
+			))
+			.await??;
+
+		let ledger_info = proof.proof.state_proof.latest_ledger_info();
+		let ledger_version = ledger_info.version();
+		let transaction = self
+			.with_timeout(self.rest_client.read(|client| async move {
+				client.get_transaction_by_version(ledger_version).await
+			}))
+			.await??
+			.into_inner();
+		utils::verify_committed_ledger_info(ledger_info, &transaction)?;
+
+		let details = utils::proven_transfer_details(
 			bridge_transfer_id,
-			initiator_address: InitiatorAddress(MovementAddress(originator_address)),
-			recipient_address: RecipientAddress(recipient_address_bytes),
-			amount: Amount(AssetType::Moveth(amount)),
-			hash_lock: HashLock(hash_lock_array),
-			time_lock: TimeLock(time_lock),
-			state,
+			&location,
+			proof.state_value.as_ref(),
+			self.amount_type,
+		)?
+		.ok_or_else(|| {
+			anyhow::anyhow!("Bridge transfer not found at version {}", block_end_version)
+		})?;
+
+		Ok((details, proof))
+	}
+
+	/// The JSON data of a resource at a version.
+	async fn resource_at(
+		&self,
+		address: AccountAddress,
+		resource_type: &str,
+		version: u64,
+	) -> Result<Value> {
+		let resource = self
+			.with_timeout(self.rest_client.read(|client| async move {
+				client.get_account_resource_at_version(address, resource_type, version).await
+			}))
+			.await??
+			.into_inner()
+			.ok_or_else(|| {
+				anyhow::anyhow!("No {} at {} at version {}", resource_type, address, version)
+			})?;
+		Ok(resource.data)
+	}
+
+	/// The time left before the time lock of the transfer elapses by the ledger time of the node,
+	/// zero once it did, e.g., for a relayer to decide between completing and aborting it.
+	///
+	/// Warns if the time lock elapses within `warn_within`.
+	pub async fn time_lock_remaining(
+		&self,
+		details: &BridgeTransferDetails<MovementAddress, [u8; 32]>,
+		warn_within: Duration,
+	) -> Result<Duration> {
+		let now = self.health().await?.ledger_timestamp_usecs / 1_000_000;
+		let remaining = Duration::from_secs(details.time_lock.remaining(now));
+		if details.time_lock.is_expired(now) {
+			warn!(
+				"Time lock of bridge transfer {} elapsed",
+				hex::encode(details.bridge_transfer_id.0)
+			);
+		} else if remaining <= warn_within {
+			warn!(
+				"Time lock of bridge transfer {} elapses in {:?}",
+				hex::encode(details.bridge_transfer_id.0),
+				remaining
+			);
+		}
+		Ok(remaining)
+	}
+
+	/// Checks that the node is reachable and reports its chain id, latest version and timestamp.
+	pub async fn health(&self) -> Result<NodeHealth> {
+		let state = self
+			.with_timeout(
+				self.rest_client.read(|client| async move { client.get_ledger_information().await }),
+			)
+			.await
+			.map_err(|_| anyhow::anyhow!("Movement node timed out"))?
+			.map_err(|e| anyhow::anyhow!("Movement node unreachable: {}", e))?
+			.into_inner();
+
+		Ok(NodeHealth {
+			chain_id: state.chain_id,
+			ledger_version: state.version,
+			block_height: state.block_height,
+			ledger_timestamp_usecs: state.timestamp_usecs,
+		})
+	}
+
+	/// How many versions the indexer is behind the node, e.g., for a relayer consuming the
+	/// indexer to pause while it catches up. Fails if no indexer is configured or it's stalled,
+	/// see [SuzukaIndexerClient::indexer_lag].
+	#[cfg(feature = "indexer")]
+	pub async fn indexer_lag(&self) -> Result<u64> {
+		let Some(indexer_client) = &self.indexer_client else {
+			let error = MovementBridgeError::ConfigInvalid("No indexer configured".to_string());
+			return Err(error.into());
 		};
-		Ok(Some(details))
+		indexer_client.indexer_lag().await
+	}
+
+	/// Like [MovementClient::health], but fails if the node lags the wall clock by more than
+	/// `max_lag`, i.e., it isn't synced enough to submit against.
+	pub async fn ensure_ready(&self, max_lag: Duration) -> Result<NodeHealth> {
+		let health = self.health().await?;
+		if health.is_stale(max_lag) {
+			return Err(anyhow::anyhow!(
+				"Movement node is {:?} behind at version {}",
+				health.lag(),
+				health.ledger_version
+			));
+		}
+		Ok(health)
+	}
+
+	/// Reads the balance of `coin_type` held by `address`, e.g., to check the counterparty can
+	/// cover a lock before submitting it. An account without a coin store holds 0.
+	pub async fn coin_balance(&self, address: AccountAddress, coin_type: TypeTag) -> Result<u64> {
+		let resource_type = format!("0x1::coin::CoinStore<{}>", coin_type);
+		let resource_type = resource_type.as_str();
+		let resource = match self
+			.with_timeout(self.rest_client.read(|client| async move {
+				client.get_account_resource(address, resource_type).await
+			}))
+			.await
+			.map_err(|_| MovementBridgeError::Timeout)?
+		{
+			Ok(response) => response.into_inner(),
+			Err(RestError::Api(e))
+				if matches!(
+					e.error.error_code,
+					AptosErrorCode::ResourceNotFound | AptosErrorCode::AccountNotFound
+				) =>
+			{
+				None
+			}
+			Err(e) => return Err(MovementBridgeError::from(e).into()),
+		};
+
+		match resource {
+			Some(resource) => Ok(utils::val_as_str(resource.data["coin"].get("value"))?.parse()?),
+			None => Ok(0),
+		}
+	}
+
+	/// Proposes a transaction to a multisig account, e.g., for bridge governance operations.
+	/// Returns the sequence number of the proposal, which the other owners use to approve it,
+	/// as the committed proposal reports it, see [utils::proposed_sequence_number].
+	pub async fn propose_multisig(
+		&self,
+		multisig_address: AccountAddress,
+		payload: TransactionPayload,
+	) -> Result<u64> {
+		let payload = utils::make_multisig_propose_payload(multisig_address, payload)?;
+		let transaction = self
+			.send_and_confirm_aptos_transaction(payload)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to propose multisig transaction: {}", e))?;
+
+		utils::proposed_sequence_number(&transaction, multisig_address)
+	}
+
+	/// Approves a pending multisig transaction on behalf of the client's signer.
+	pub async fn approve_multisig(
+		&self,
+		multisig_address: AccountAddress,
+		sequence_number: u64,
+	) -> Result<()> {
+		let payload = utils::make_multisig_approve_payload(multisig_address, sequence_number)?;
+		self.send_aptos_transaction(payload)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to approve multisig transaction: {}", e))?;
+
+		Ok(())
 	}
 }
 
@@ -664,8 +1574,9 @@ impl BridgeContractInitiator for MovementClient {
 		amount: Amount,
         ) -> BridgeContractInitiatorResult<()> {
                 let amount_value = match amount.0 {
-                        AssetType::Moveth(value) => value,
-                        _ => return Err(BridgeContractInitiatorError::ConversionError),
+                        AssetType::Moveth(value) => Uint::from(value),
+                        AssetType::Token(value) => value,
+                        AssetType::EthAndWeth(_) => return Err(BridgeContractInitiatorError::ConversionError),
                 };
 		debug!("Amount value: {:?}", amount_value);
 	
@@ -673,7 +1584,8 @@ impl BridgeContractInitiator for MovementClient {
 			utils::serialize_vec_initiator(&recipient.0)?,					
 			utils::serialize_vec_initiator(&hash_lock.0[..])?,			
 			utils::serialize_u64_initiator(&time_lock.0)?,			
-			utils::serialize_u64_initiator(&amount_value)?
+			utils::serialize_amount(amount_value, self.amount_type)
+				.map_err(|_| BridgeContractInitiatorError::ConversionError)?
 		];
 
                 let payload = utils::make_aptos_payload(
@@ -684,9 +1596,12 @@ impl BridgeContractInitiator for MovementClient {
                         args,
                 );
 
-                let response = utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
-                        .await
-                        .map_err(|_| BridgeContractInitiatorError::InitiateTransferError)?;
+		self.send_aptos_transaction(payload)
+			.await
+			.map_err(|e| match e.without_context() {
+				MovementBridgeError::Timeout => BridgeContractInitiatorError::Timeout,
+				_ => BridgeContractInitiatorError::InitiateTransferError,
+			})?;
 
                 Ok(())
         }
@@ -709,9 +1624,12 @@ impl BridgeContractInitiator for MovementClient {
                         args,
                 );
 
-                utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
-                        .await
-                        .map_err(|_| BridgeContractInitiatorError::CompleteTransferError)?;
+		self.send_aptos_transaction(payload)
+			.await
+			.map_err(|e| match e.without_context() {
+				MovementBridgeError::Timeout => BridgeContractInitiatorError::Timeout,
+				_ => BridgeContractInitiatorError::CompleteTransferError,
+			})?;
 
                 Ok(())
         }
@@ -732,9 +1650,12 @@ impl BridgeContractInitiator for MovementClient {
                         args,
                 );
 
-                utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
-                        .await
-                        .map_err(|_| BridgeContractInitiatorError::ConversionError)?;
+		self.send_aptos_transaction(payload)
+			.await
+			.map_err(|e| match e.without_context() {
+				MovementBridgeError::Timeout => BridgeContractInitiatorError::Timeout,
+				_ => BridgeContractInitiatorError::ConversionError,
+			})?;
 
                 Ok(())
         }
@@ -743,34 +1664,22 @@ impl BridgeContractInitiator for MovementClient {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId<<MovementClient as BridgeContractCounterparty>::Hash>,
 	) -> BridgeContractInitiatorResult<Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>> {
-		let bridge_transfer_id_hex = format!("0x{}", hex::encode(bridge_transfer_id.0));
-	
-		let view_request = ViewRequest {
-			function: EntryFunctionId {
-				module: MoveModuleId {
-					address: self.counterparty_address.clone().into(),
-					name: aptos_api_types::IdentifierWrapper(
-						Identifier::new("atomic_bridge_initiator")
-						.map_err(|_| BridgeContractInitiatorError::FunctionViewError)?,
-					),
-				},
-				name: aptos_api_types::IdentifierWrapper(
-					Identifier::new("bridge_transfers")
-						.map_err(|_| BridgeContractInitiatorError::FunctionViewError)?,
-					),
-			},
-			type_arguments: vec![],
-			arguments: vec![serde_json::json!(bridge_transfer_id_hex)],
-		};
-	
-		let response: Response<Vec<serde_json::Value>> = self
-			.rest_client
-			.view(&view_request, None)
+		let args = vec![utils::serialize_vec_initiator(&bridge_transfer_id.0[..])?];
+		let values = self
+			.view_function(
+				self.counterparty_address,
+				"atomic_bridge_initiator",
+				"bridge_transfers",
+				Vec::new(),
+				args,
+				None,
+			)
 			.await
-			.map_err(|_| BridgeContractInitiatorError::CallError)?;
-	
-		let values = response.inner();
-	
+			.map_err(|e| match e {
+				MovementBridgeError::Timeout => BridgeContractInitiatorError::Timeout,
+				_ => BridgeContractInitiatorError::CallError,
+			})?;
+
 		if values.len() != 6 {
 		return Err(BridgeContractInitiatorError::InvalidResponseLength);
 		}
@@ -819,3 +1728,294 @@ impl MovementClient {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_faucet_client_is_shareable() {
+		// concurrent funding only needs the Arc
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<FaucetClient>();
+	}
+
+	#[tokio::test]
+	async fn test_counterparty_address_from_config() -> Result<()> {
+		let client = MovementClient::new(Config::build_for_test()).await?;
+		assert_eq!(client.counterparty_address, AccountAddress::from_hex_literal("0xcafe")?);
+
+		let address = MovementAddress(AccountAddress::new([7; 32]));
+		let config =
+			Config { counterparty_contract: Some(address.clone()), ..Config::build_for_test() };
+		let client = MovementClient::new(config).await?;
+		assert_eq!(client.counterparty_address, address.0);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_signer_from_config() -> Result<()> {
+		let key = "0x0101010101010101010101010101010101010101010101010101010101010101";
+		let account = LocalAccount::from_private_key(key, 0)?;
+		let address = account.address();
+		let config = Config {
+			signer_private_key: Arc::new(RwLock::new(account)),
+			..Config::build_for_test()
+		};
+
+		let client = MovementClient::new(config).await?;
+		assert_eq!(client.signer().address(), address);
+		assert_eq!(client.gas_params(), Config::build_for_test().gas_params());
+		assert!(client.signer().local_account().is_some());
+
+		let other = LocalAccount::generate(&mut rand::rngs::StdRng::from_seed([4; 32]));
+		let other_address = other.address();
+		let client = client.with_signer(Arc::new(other));
+		assert_eq!(client.signer().address(), other_address);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lock_bridge_transfer_payload_args() -> Result<()> {
+		let client = MovementClient::new(Config::build_for_test()).await?;
+		let recipient = MovementAddress(AccountAddress::new([7; 32]));
+		let payload = client.lock_bridge_transfer_payload(
+			BridgeTransferId([1; 32]),
+			HashLock([2; 32]),
+			InitiatorAddress(b"0x123".to_vec()),
+			RecipientAddress(recipient.clone()),
+			Amount(AssetType::Moveth(100)),
+			None,
+		)?;
+
+		let entry_function = match payload {
+			TransactionPayload::EntryFunction(entry_function) => entry_function,
+			_ => panic!("Expected an entry function payload"),
+		};
+		assert_eq!(entry_function.module().address(), &client.counterparty_address);
+		assert_eq!(entry_function.module().name().as_str(), COUNTERPARTY_MODULE_NAME);
+		assert_eq!(entry_function.function().as_str(), "lock_bridge_transfer");
+		assert!(entry_function.ty_args().is_empty());
+
+		// the Move entry function, without the signer account:
+		// lock_bridge_transfer(originator, bridge_transfer_id, hash_lock, recipient, amount)
+		let args = entry_function.args();
+		assert_eq!(args.len(), 5);
+		assert_eq!(bcs::from_bytes::<Vec<u8>>(&args[0])?, b"0x123".to_vec());
+		assert_eq!(bcs::from_bytes::<Vec<u8>>(&args[1])?, vec![1; 32]);
+		assert_eq!(bcs::from_bytes::<Vec<u8>>(&args[2])?, vec![2; 32]);
+		assert_eq!(bcs::from_bytes::<AccountAddress>(&args[3])?, recipient.0);
+		assert_eq!(bcs::from_bytes::<u64>(&args[4])?, 100);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lock_bridge_transfer_payload_wide_amount() -> Result<()> {
+		let amount = Amount(AssetType::Token(Uint::MAX - Uint::from(1)));
+		let lock = |client: &MovementClient| {
+			client.lock_bridge_transfer_payload(
+				BridgeTransferId([1; 32]),
+				HashLock([2; 32]),
+				InitiatorAddress(b"0x123".to_vec()),
+				RecipientAddress(MovementAddress(AccountAddress::new([7; 32]))),
+				amount,
+				None,
+			)
+		};
+
+		let config = Config { amount_type: MoveAmountType::U256, ..Config::build_for_test() };
+		let entry_function = match lock(&MovementClient::new(config).await?)? {
+			TransactionPayload::EntryFunction(entry_function) => entry_function,
+			_ => panic!("Expected an entry function payload"),
+		};
+		let mut expected = vec![0xff; 32];
+		expected[0] = 0xfe;
+		assert_eq!(entry_function.args()[4], expected);
+
+		// a u64 argument can't hold the amount
+		let error = lock(&MovementClient::new(Config::build_for_test()).await?).unwrap_err();
+		let BridgeContractCounterpartyError::ChainError(error) = error else {
+			panic!("Expected a chain error, got {:?}", error);
+		};
+		assert!(matches!(
+			error.downcast_ref::<MovementBridgeError>(),
+			Some(MovementBridgeError::AmountOverflow { amount_type: MoveAmountType::U64, .. })
+		));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lock_bridge_transfer_payload_coin_type() -> Result<()> {
+		let coin_type = TypeTag::from_str("0x1::aptos_coin::AptosCoin")?;
+		let config = Config { coin_type: Some(coin_type.clone()), ..Config::build_for_test() };
+		let client = MovementClient::new(config).await?;
+		assert_eq!(client.coin_type(), Some(&coin_type));
+
+		let lock = |coin_type: Option<TypeTag>| {
+			client.lock_bridge_transfer_payload(
+				BridgeTransferId([1; 32]),
+				HashLock([2; 32]),
+				InitiatorAddress(b"0x123".to_vec()),
+				RecipientAddress(MovementAddress(AccountAddress::new([7; 32]))),
+				Amount(AssetType::Moveth(100)),
+				coin_type,
+			)
+		};
+		let TransactionPayload::EntryFunction(entry_function) = lock(client.coin_type().cloned())?
+		else {
+			panic!("Expected an entry function payload");
+		};
+		assert_eq!(entry_function.ty_args(), &[coin_type]);
+
+		// a transfer may lock another coin
+		let other = TypeTag::from_str("0xcafe::moveth::MovETH")?;
+		let TransactionPayload::EntryFunction(entry_function) = lock(Some(other.clone()))? else {
+			panic!("Expected an entry function payload");
+		};
+		assert_eq!(entry_function.ty_args(), &[other]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_counterparty_function_names() -> Result<()> {
+		let counterparty_functions = CounterpartyFunctions {
+			complete: "complete_bridge_transfer_v2".to_string(),
+			..CounterpartyFunctions::default()
+		};
+		let config = Config { counterparty_functions, ..Config::build_for_test() };
+		let client = MovementClient::new(config).await?;
+
+		let payload = client.complete_bridge_transfer_payload(
+			BridgeTransferId([1; 32]),
+			HashLockPreImage(vec![2; 32]),
+		)?;
+		let TransactionPayload::EntryFunction(entry_function) = payload else {
+			panic!("Expected an entry function payload");
+		};
+		assert_eq!(entry_function.function().as_str(), "complete_bridge_transfer_v2");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_last_error() -> Result<()> {
+		// nothing listens on the port
+		let config = Config {
+			rpc_url: Some("http://127.0.0.1:1".to_string()),
+			request_timeout: Duration::from_secs(5),
+			..Config::build_for_test()
+		};
+		let mut client = MovementClient::new(config).await?;
+		assert_eq!(client.last_error(), None);
+
+		let error = BridgeContractCounterparty::abort_bridge_transfer(
+			&mut client,
+			BridgeTransferId([1; 32]),
+		)
+		.await
+		.unwrap_err();
+		assert!(matches!(error, BridgeContractCounterpartyError::ChainError(_)));
+		let last_error = client.last_error().expect("Expected the failure to be recorded");
+		assert!(last_error.starts_with("RPC request failed"), "{}", last_error);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_transfer_context() -> Result<()> {
+		// nothing listens on the port
+		let config = Config {
+			rpc_url: Some("http://127.0.0.1:1".to_string()),
+			request_timeout: Duration::from_secs(5),
+			..Config::build_for_test()
+		};
+		let context = TransferContext::new("7f3a").with_tag("source", "eth");
+		let mut client = MovementClient::new(config).await?.with_context(context.clone());
+		assert_eq!(client.context(), Some(&context));
+
+		let error = BridgeContractCounterparty::abort_bridge_transfer(
+			&mut client,
+			BridgeTransferId([1; 32]),
+		)
+		.await
+		.unwrap_err();
+		let BridgeContractCounterpartyError::ChainError(chain_error) = error else {
+			panic!("Expected a chain error, got {:?}", error);
+		};
+		let error = chain_error
+			.downcast_ref::<MovementBridgeError>()
+			.expect("Expected a Movement error");
+		assert_eq!(error.context(), Some(&context));
+		assert!(matches!(error.without_context(), MovementBridgeError::RpcError(_)));
+		let last_error = client.last_error().expect("Expected the failure to be recorded");
+		assert!(last_error.ends_with("(request 7f3a source=eth)"), "{}", last_error);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_drain() -> Result<()> {
+		// nothing is read from the node before the transactions
+		let mut client =
+			MovementClient::new(Config::build_for_test()).await?.with_check_completed(false);
+		assert!(!client.is_draining());
+		assert_eq!(client.clone().drain(Duration::from_secs(1)).await, vec![]);
+
+		// clones drain together
+		assert!(client.is_draining());
+		let error = BridgeContractCounterparty::abort_bridge_transfer(
+			&mut client,
+			BridgeTransferId([1; 32]),
+		)
+		.await
+		.unwrap_err();
+		let BridgeContractCounterpartyError::ChainError(chain_error) = error else {
+			panic!("Expected a chain error, got {:?}", error);
+		};
+		assert!(matches!(
+			chain_error.downcast_ref::<MovementBridgeError>(),
+			Some(MovementBridgeError::Draining)
+		));
+
+		let results = client
+			.complete_bridge_transfers_batch(vec![(
+				BridgeTransferId([2; 32]),
+				HashLockPreImage(b"secret".to_vec()),
+			)])
+			.await;
+		assert!(matches!(
+			&results[0],
+			Err(BridgeContractCounterpartyError::ChainError(chain_error))
+				if matches!(
+					chain_error.downcast_ref::<MovementBridgeError>(),
+					Some(MovementBridgeError::Draining)
+				)
+		));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_faucet_not_configured() -> Result<()> {
+		let client = MovementClient::new(Config::build_for_test()).await?;
+		assert!(matches!(client.faucet_client(), Err(MovementBridgeError::FaucetNotConfigured)));
+		let error = client.fund(AccountAddress::ONE, 1).await.unwrap_err();
+		assert!(matches!(
+			error.downcast_ref::<MovementBridgeError>(),
+			Some(MovementBridgeError::FaucetNotConfigured)
+		));
+
+		let config = Config {
+			faucet_url: Some("http://localhost:8081".to_string()),
+			..Config::build_for_test()
+		};
+		assert!(MovementClient::new(config).await?.faucet_client().is_ok());
+
+		Ok(())
+	}
+}
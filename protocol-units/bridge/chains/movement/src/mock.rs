@@ -0,0 +1,205 @@
+use crate::types::TransferState;
+use crate::utils::MovementAddress;
+use aptos_sdk::types::account_address::AccountAddress;
+use bridge_shared::{
+	bridge_contracts::{
+		BridgeContractCounterparty, BridgeContractCounterpartyError,
+		BridgeContractCounterpartyResult,
+	},
+	types::{
+		Amount, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage,
+		InitiatorAddress, RecipientAddress, TimeLock,
+	},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const LOCKED: u8 = 1;
+const COMPLETED: u8 = 2;
+const ABORTED: u8 = 3;
+
+type Details = BridgeTransferDetails<MovementAddress, [u8; 32]>;
+
+/// An in-memory counterparty contract, so code built on [BridgeContractCounterparty] can be tested
+/// without a Movement node.
+///
+/// It follows the checks of the `atomic_bridge_counterparty` module, except that time locks
+/// aren't enforced, so transfers can be aborted right away. Clones share the transfers.
+#[derive(Debug, Clone, Default)]
+pub struct MockMovementClient {
+	transfers: Arc<Mutex<HashMap<BridgeTransferId<[u8; 32]>, Details>>>,
+}
+
+impl MockMovementClient {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads the state of a transfer, like [crate::MovementClient::transfer_state].
+	pub fn transfer_state(&self, bridge_transfer_id: &BridgeTransferId<[u8; 32]>) -> TransferState {
+		let transfers = self.transfers.lock().expect("lock poisoned");
+		transfers
+			.get(bridge_transfer_id)
+			.and_then(|details| TransferState::from_state(details.state))
+			.unwrap_or(TransferState::NotFound)
+	}
+
+	/// Moves a locked transfer to `state`, failing with `error` otherwise.
+	fn settle(
+		&self,
+		bridge_transfer_id: &BridgeTransferId<[u8; 32]>,
+		state: u8,
+		error: BridgeContractCounterpartyError,
+		check: impl FnOnce(&Details) -> bool,
+	) -> BridgeContractCounterpartyResult<()> {
+		let mut transfers = self.transfers.lock().expect("lock poisoned");
+		match transfers.get_mut(bridge_transfer_id) {
+			Some(details) if details.state == LOCKED && check(details) => {
+				details.state = state;
+				Ok(())
+			}
+			_ => Err(error),
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl BridgeContractCounterparty for MockMovementClient {
+	type Address = MovementAddress;
+	type Hash = [u8; 32];
+
+	async fn lock_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId<Self::Hash>,
+		hash_lock: HashLock<Self::Hash>,
+		time_lock: TimeLock,
+		initiator: InitiatorAddress<Vec<u8>>,
+		recipient: RecipientAddress<Self::Address>,
+		amount: Amount,
+	) -> BridgeContractCounterpartyResult<()> {
+		// the originator is stored like the view function returns it, left padded to an address
+		let initiator = &initiator.0;
+		if initiator.len() > AccountAddress::LENGTH {
+			return Err(BridgeContractCounterpartyError::SerializationError);
+		}
+		let mut originator = [0u8; AccountAddress::LENGTH];
+		originator[AccountAddress::LENGTH - initiator.len()..].copy_from_slice(initiator);
+
+		let mut transfers = self.transfers.lock().expect("lock poisoned");
+		if transfers.contains_key(&bridge_transfer_id) {
+			return Err(BridgeContractCounterpartyError::LockTransferError);
+		}
+		let details = BridgeTransferDetails {
+			bridge_transfer_id: bridge_transfer_id.clone(),
+			initiator_address: InitiatorAddress(MovementAddress(AccountAddress::new(originator))),
+			recipient_address: RecipientAddress(recipient.0 .0.to_vec()),
+			hash_lock,
+			time_lock,
+			amount,
+			state: LOCKED,
+		};
+		transfers.insert(bridge_transfer_id, details);
+		Ok(())
+	}
+
+	async fn complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId<Self::Hash>,
+		secret: HashLockPreImage,
+	) -> BridgeContractCounterpartyResult<()> {
+		let computed_hash = keccak_hash::keccak(&secret.0).0;
+		self.settle(
+			&bridge_transfer_id,
+			COMPLETED,
+			BridgeContractCounterpartyError::CompleteTransferError,
+			|details| details.hash_lock.0 == computed_hash,
+		)
+	}
+
+	async fn abort_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId<Self::Hash>,
+	) -> BridgeContractCounterpartyResult<()> {
+		self.settle(
+			&bridge_transfer_id,
+			ABORTED,
+			BridgeContractCounterpartyError::AbortTransferError,
+			|_| true,
+		)
+	}
+
+	async fn get_bridge_transfer_details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId<Self::Hash>,
+	) -> BridgeContractCounterpartyResult<Option<BridgeTransferDetails<Self::Address, Self::Hash>>>
+	{
+		Ok(self.transfers.lock().expect("lock poisoned").get(&bridge_transfer_id).cloned())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bridge_shared::types::AssetType;
+
+	async fn lock(client: &mut MockMovementClient, id: u8) -> BridgeContractCounterpartyResult<()> {
+		client
+			.lock_bridge_transfer(
+				BridgeTransferId([id; 32]),
+				HashLock(keccak_hash::keccak(b"secret").0),
+				TimeLock(100),
+				InitiatorAddress(vec![0xee; 20]),
+				RecipientAddress(MovementAddress(AccountAddress::new([0xab; 32]))),
+				Amount(AssetType::Moveth(42)),
+			)
+			.await
+	}
+
+	#[tokio::test]
+	async fn test_mock_transfer_lifecycle() -> BridgeContractCounterpartyResult<()> {
+		let mut client = MockMovementClient::new();
+		lock(&mut client, 1).await?;
+		assert_eq!(
+			lock(&mut client, 1).await,
+			Err(BridgeContractCounterpartyError::LockTransferError)
+		);
+
+		let details = client.get_bridge_transfer_details(BridgeTransferId([1; 32])).await?.unwrap();
+		assert_eq!(details.state, LOCKED);
+		assert_eq!(&details.initiator_address.0 .0[12..], &[0xee; 20]);
+		assert_eq!(details.recipient_address.0, vec![0xab; 32]);
+
+		// a wrong secret keeps the transfer locked
+		assert_eq!(
+			client
+				.complete_bridge_transfer(
+					BridgeTransferId([1; 32]),
+					HashLockPreImage(b"wrong".to_vec())
+				)
+				.await,
+			Err(BridgeContractCounterpartyError::CompleteTransferError)
+		);
+		// clones share the transfers
+		client
+			.clone()
+			.complete_bridge_transfer(
+				BridgeTransferId([1; 32]),
+				HashLockPreImage(b"secret".to_vec()),
+			)
+			.await?;
+		assert_eq!(client.transfer_state(&BridgeTransferId([1; 32])), TransferState::Completed);
+		assert_eq!(
+			client.abort_bridge_transfer(BridgeTransferId([1; 32])).await,
+			Err(BridgeContractCounterpartyError::AbortTransferError)
+		);
+
+		lock(&mut client, 2).await?;
+		client.abort_bridge_transfer(BridgeTransferId([2; 32])).await?;
+		assert_eq!(client.transfer_state(&BridgeTransferId([2; 32])), TransferState::Aborted);
+
+		assert_eq!(client.get_bridge_transfer_details(BridgeTransferId([3; 32])).await?, None);
+		assert_eq!(client.transfer_state(&BridgeTransferId([3; 32])), TransferState::NotFound);
+
+		Ok(())
+	}
+}
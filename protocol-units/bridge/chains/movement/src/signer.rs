@@ -0,0 +1,94 @@
+use crate::error::MovementBridgeError;
+use aptos_sdk::{
+	crypto::{
+		ed25519::{Ed25519PublicKey, Ed25519Signature},
+		traits::signing_message,
+		SigningKey,
+	},
+	types::{
+		account_address::AccountAddress,
+		transaction::{RawTransaction, SignedTransaction},
+		LocalAccount,
+	},
+};
+
+/// Signs the transactions of a [crate::MovementClient], e.g., with a key held in a KMS or HSM
+/// rather than in process memory.
+#[async_trait::async_trait]
+pub trait Signer: Send + Sync {
+	/// The account transactions are sent from.
+	fn address(&self) -> AccountAddress;
+
+	fn public_key(&self) -> Ed25519PublicKey;
+
+	/// Signs the message with the key of the account.
+	async fn sign_message(&self, message: &[u8]) -> Result<Ed25519Signature, MovementBridgeError>;
+
+	/// The local account, if the key is held in process, e.g., to publish the test modules.
+	fn local_account(&self) -> Option<&LocalAccount> {
+		None
+	}
+}
+
+#[async_trait::async_trait]
+impl Signer for LocalAccount {
+	fn address(&self) -> AccountAddress {
+		LocalAccount::address(self)
+	}
+
+	fn public_key(&self) -> Ed25519PublicKey {
+		LocalAccount::public_key(self).clone()
+	}
+
+	async fn sign_message(&self, message: &[u8]) -> Result<Ed25519Signature, MovementBridgeError> {
+		Ok(self.private_key().sign_arbitrary_message(message))
+	}
+
+	fn local_account(&self) -> Option<&LocalAccount> {
+		Some(self)
+	}
+}
+
+/// Signs a raw transaction, like [LocalAccount::sign_transaction] does with a local key.
+pub async fn sign_transaction(
+	signer: &dyn Signer,
+	raw_tx: RawTransaction,
+) -> Result<SignedTransaction, MovementBridgeError> {
+	let message =
+		signing_message(&raw_tx).map_err(|e| MovementBridgeError::SigningError(e.to_string()))?;
+	let signature = signer.sign_message(&message).await?;
+	Ok(SignedTransaction::new(raw_tx, signer.public_key(), signature))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::make_aptos_payload;
+	use aptos_sdk::{transaction_builder::TransactionFactory, types::chain_id::ChainId};
+	use rand::prelude::*;
+
+	#[tokio::test]
+	async fn test_sign_transaction_like_local_account() -> Result<(), MovementBridgeError> {
+		let mut rng = rand::rngs::StdRng::from_seed([3u8; 32]);
+		let account = LocalAccount::generate(&mut rng);
+		let raw_tx = TransactionFactory::new(ChainId::new(4))
+			.payload(make_aptos_payload(
+				AccountAddress::ONE,
+				"coin",
+				"transfer",
+				Vec::new(),
+				Vec::new(),
+			))
+			.sender(account.address())
+			.sequence_number(0)
+			.build();
+
+		let signer: &dyn Signer = &account;
+		let signed_tx = sign_transaction(signer, raw_tx.clone()).await?;
+		assert!(signed_tx.verify_signature().is_ok());
+		assert_eq!(signed_tx, account.sign_transaction(raw_tx));
+		assert!(signer.local_account().is_some());
+
+		Ok(())
+	}
+}
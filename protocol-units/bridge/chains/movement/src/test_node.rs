@@ -0,0 +1,318 @@
+use crate::utils::{self, GasParams};
+use crate::{Config, MovementClient};
+use anyhow::Context as _;
+use aptos_framework::{BuildOptions, BuiltPackage};
+use aptos_sdk::{
+	crypto::HashValue,
+	rest_client::Client,
+	types::{
+		account_address::{create_resource_address, AccountAddress},
+		account_config::aptos_test_root_address,
+		AccountKey, LocalAccount,
+	},
+};
+use aptos_types::{
+	block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
+	block_metadata::BlockMetadata,
+	transaction::{
+		signature_verified_transaction::SignatureVerifiedTransaction, SignedTransaction,
+		Transaction,
+	},
+};
+use maptos_execution_util::config::Config as MaptosConfig;
+use maptos_opt_executor::{Executor, Service};
+use movement_algs::backoff::Backoff;
+use movement_rest::MovementRest;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// The directory of the bridge Move package.
+pub const BRIDGE_MODULES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../move-modules");
+
+/// Transactions the mempool can hand to the block producer before it applies backpressure.
+const TRANSACTION_CHANNEL_SIZE: usize = 1024;
+
+/// How long the node has to start serving its REST APIs.
+const SERVING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The octas the test client's signer is funded with.
+const SIGNER_FUNDING: u64 = 100_000_000_000;
+
+/// The seed of the resource account the bridge modules are published to.
+const RESOURCE_ACCOUNT_SEED: &[u8] = b"movement-bridge";
+
+/// A Movement node running in process, so the bridge can be tested without the `movement` CLI.
+///
+/// It serves the REST API and the movement REST service of the proofs on free local ports, and
+/// executes every batch of transactions the mempool hands over as a block. There's no faucet; accounts are funded from the root account
+/// with [TestNode::fund]. The node stops when dropped.
+pub struct TestNode {
+	rest_url: Url,
+	movement_rest_url: Url,
+	root_account: Mutex<LocalAccount>,
+	tasks: Vec<JoinHandle<Result<(), anyhow::Error>>>,
+	_db_dir: TempDir,
+}
+
+impl TestNode {
+	pub async fn start() -> Result<Self, anyhow::Error> {
+		let db_dir = tempfile::tempdir()?;
+		let port = free_port()?;
+		let movement_rest_port = free_port()?;
+		let mut maptos_config = MaptosConfig::default();
+		maptos_config.chain.maptos_db_path.replace(db_dir.path().to_path_buf());
+		maptos_config.chain.maptos_rest_listen_hostname = "127.0.0.1".to_string();
+		maptos_config.chain.maptos_rest_listen_port = port;
+
+		let executor = Executor::try_from_config(&maptos_config)?;
+		// transactions expire relative to the ledger timestamp, so it has to be current
+		executor.rollover_genesis_now().await?;
+		let (transaction_sender, transaction_receiver) = mpsc::channel(TRANSACTION_CHANNEL_SIZE);
+		let (context, transaction_pipe) =
+			executor.background(transaction_sender, &maptos_config)?;
+		let service = Service::new(&context);
+		let movement_rest =
+			MovementRest::new(([127, 0, 0, 1], movement_rest_port).into(), service.api_context());
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(maptos_config.chain.maptos_private_key.clone()),
+			0,
+		);
+
+		let tasks = vec![
+			tokio::spawn(async move { transaction_pipe.run().await.map_err(anyhow::Error::from) }),
+			tokio::spawn(service.run()),
+			tokio::spawn(movement_rest.run_service()),
+			tokio::spawn(async move {
+				// the context has to outlive the transaction pipe
+				let _context = context;
+				produce_blocks(executor, transaction_receiver).await
+			}),
+		];
+		let node = Self {
+			rest_url: Url::parse(&format!("http://127.0.0.1:{}", port))?,
+			movement_rest_url: Url::parse(&format!("http://127.0.0.1:{}", movement_rest_port))?,
+			root_account: Mutex::new(root_account),
+			tasks,
+			_db_dir: db_dir,
+		};
+		node.wait_until_serving().await?;
+		Ok(node)
+	}
+
+	pub fn rest_url(&self) -> &Url {
+		&self.rest_url
+	}
+
+	pub fn movement_rest_url(&self) -> &Url {
+		&self.movement_rest_url
+	}
+
+	async fn wait_until_serving(&self) -> Result<(), anyhow::Error> {
+		let client = Client::new(self.rest_url.clone());
+		let health = self.movement_rest_url.join("health")?;
+		let deadline = tokio::time::Instant::now() + SERVING_TIMEOUT;
+		let mut backoff = Backoff::new(Duration::from_millis(50), Duration::from_secs(1));
+		loop {
+			if client.get_ledger_information().await.is_ok()
+				&& reqwest::get(health.clone()).await.is_ok()
+			{
+				return Ok(());
+			}
+			if tokio::time::Instant::now() >= deadline {
+				return Err(anyhow::anyhow!(
+					"Test node isn't serving at {} and {}",
+					self.rest_url,
+					self.movement_rest_url
+				));
+			}
+			tokio::time::sleep(backoff.next_delay()).await;
+		}
+	}
+
+	/// Transfers `amount` octas from the root account, creating the account if needed.
+	pub async fn fund(&self, address: AccountAddress, amount: u64) -> Result<(), anyhow::Error> {
+		let payload = utils::make_aptos_payload(
+			AccountAddress::ONE,
+			"aptos_account",
+			"transfer",
+			Vec::new(),
+			vec![bcs::to_bytes(&address)?, bcs::to_bytes(&amount)?],
+		);
+		// funding calls share the sequence numbers of the root account
+		let root_account = self.root_account.lock().await;
+		utils::send_and_confirm_aptos_transaction(
+			&Client::new(self.rest_url.clone()),
+			&*root_account,
+			payload,
+			GasParams::default(),
+		)
+		.await?;
+		Ok(())
+	}
+
+	/// Compiles the bridge modules in process and publishes them to a resource account of the
+	/// client's signer, like [MovementClient::publish_for_test] does with the CLI.
+	///
+	/// Points the client's counterparty address at the resource account and returns it.
+	pub async fn publish_bridge_modules(
+		&self,
+		client: &mut MovementClient,
+		package_dir: impl AsRef<Path>,
+	) -> Result<AccountAddress, anyhow::Error> {
+		let origin = client.signer().address();
+		let resource_address = create_resource_address(origin, RESOURCE_ACCOUNT_SEED);
+		let package_dir = package_dir.as_ref().to_path_buf();
+		let (metadata, code) = tokio::task::spawn_blocking(move || {
+			build_package(package_dir, origin, resource_address)
+		})
+		.await??;
+
+		let payload = utils::make_aptos_payload(
+			AccountAddress::ONE,
+			"resource_account",
+			"create_resource_account_and_publish_package",
+			Vec::new(),
+			vec![
+				bcs::to_bytes(&RESOURCE_ACCOUNT_SEED)?,
+				bcs::to_bytes(&metadata)?,
+				bcs::to_bytes(&code)?,
+			],
+		);
+		utils::send_and_confirm_aptos_transaction(
+			client.rest_client(),
+			client.signer(),
+			payload,
+			client.gas_params(),
+		)
+		.await?;
+
+		client.counterparty_address = resource_address;
+		Ok(resource_address)
+	}
+}
+
+impl Drop for TestNode {
+	fn drop(&mut self) {
+		for task in &self.tasks {
+			task.abort();
+		}
+	}
+}
+
+fn free_port() -> Result<u16, anyhow::Error> {
+	Ok(std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// Builds a copy of the package with the named addresses [MovementClient::publish_for_test]
+/// writes into the `Move.toml`, leaving the package itself untouched.
+///
+/// Returns the serialized package metadata and the module bytecode.
+fn build_package(
+	package_dir: PathBuf,
+	origin: AccountAddress,
+	resource_address: AccountAddress,
+) -> Result<(Vec<u8>, Vec<Vec<u8>>), anyhow::Error> {
+	let mut named_addresses = BTreeMap::new();
+	for name in ["resource_addr", "atomic_bridge", "moveth", "master_minter", "minter", "admin"] {
+		named_addresses.insert(name, resource_address.to_hex_literal());
+	}
+	named_addresses.insert("origin_addr", origin.to_hex_literal());
+	named_addresses.insert("pauser", "0xdafe".to_string());
+	named_addresses.insert("denylister", "0xcade".to_string());
+
+	let build_dir = tempfile::tempdir()?;
+	copy_dir(&package_dir, build_dir.path())?;
+	let manifest_path = build_dir.path().join("Move.toml");
+	let manifest = fs::read_to_string(&manifest_path)?
+		.lines()
+		.map(|line| match line.split_once(" = ") {
+			Some((name, _)) if named_addresses.contains_key(name) => {
+				format!(r#"{} = "{}""#, name, named_addresses[name])
+			}
+			_ => line.to_string(),
+		})
+		.collect::<Vec<_>>()
+		.join("\n");
+	fs::write(&manifest_path, manifest)?;
+
+	let package = BuiltPackage::build(build_dir.path().to_path_buf(), BuildOptions::default())
+		.context("Failed to build the bridge modules")?;
+	Ok((bcs::to_bytes(&package.extract_metadata()?)?, package.extract_code()))
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+	fs::create_dir_all(to)?;
+	for entry in fs::read_dir(from)? {
+		let entry = entry?;
+		// skip earlier build output
+		if entry.file_name() == "build" {
+			continue;
+		}
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+		} else {
+			fs::copy(entry.path(), to.join(entry.file_name()))?;
+		}
+	}
+	Ok(())
+}
+
+/// Executes each batch of transactions received from the mempool as a block.
+async fn produce_blocks(
+	executor: Executor,
+	mut transaction_receiver: mpsc::Receiver<SignedTransaction>,
+) -> Result<(), anyhow::Error> {
+	while let Some(transaction) = transaction_receiver.recv().await {
+		let mut transactions = vec![transaction];
+		while let Ok(transaction) = transaction_receiver.try_recv() {
+			transactions.push(transaction);
+		}
+		let count = transactions.len() as u64;
+
+		let (epoch, round) = executor.get_next_epoch_and_round()?;
+		let block_id = HashValue::random();
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+		let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+			block_id,
+			epoch,
+			round,
+			executor.signer.author(),
+			vec![],
+			vec![],
+			timestamp,
+		));
+		let transactions = ExecutableTransactions::Unsharded(
+			std::iter::once(block_metadata)
+				.chain(transactions.into_iter().map(Transaction::UserTransaction))
+				.map(SignatureVerifiedTransaction::Valid)
+				.collect(),
+		);
+		executor.execute_block(ExecutableBlock::new(block_id, transactions)).await?;
+		executor.decrement_transactions_in_flight(count);
+	}
+	Ok(())
+}
+
+impl MovementClient {
+	/// Like [MovementClient::new_for_test], but against a [TestNode] rather than a local testnet
+	/// of the `movement` CLI. The signer is funded.
+	pub async fn new_with_test_node(config: Config) -> Result<(Self, TestNode), anyhow::Error> {
+		let node = TestNode::start().await?;
+		let config = Config {
+			rpc_url: Some(node.rest_url().to_string()),
+			rpc_urls: Vec::new(),
+			movement_rest_url: Some(node.movement_rest_url().to_string()),
+			..config
+		};
+		let client = MovementClient::new(config).await?;
+		node.fund(client.signer().address(), SIGNER_FUNDING).await?;
+		Ok((client, node))
+	}
+}
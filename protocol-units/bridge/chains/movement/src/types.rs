@@ -1 +1,197 @@
+use crate::Call;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 pub type MovementValue = u64;
+
+/// The Move integer type of the amount argument of the bridge entry functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MoveAmountType {
+	#[default]
+	U64,
+	U128,
+	U256,
+}
+
+impl MoveAmountType {
+	/// The size of the BCS encoding, which is the little endian integer.
+	pub fn byte_len(&self) -> usize {
+		match self {
+			MoveAmountType::U64 => 8,
+			MoveAmountType::U128 => 16,
+			MoveAmountType::U256 => 32,
+		}
+	}
+}
+
+/// The names of the counterparty module functions, for deployments whose entry functions are
+/// named differently, e.g., versioned modules. Unset names keep the defaults.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CounterpartyFunctions {
+	pub lock: String,
+	pub complete: String,
+	pub abort: String,
+	/// The view function reading the transfer details
+	pub get_details: String,
+}
+
+impl CounterpartyFunctions {
+	/// The name of the function making `call`.
+	pub(crate) fn function(&self, call: Call) -> &str {
+		match call {
+			Call::Lock => &self.lock,
+			Call::Complete => &self.complete,
+			Call::Abort => &self.abort,
+			Call::GetDetails => &self.get_details,
+		}
+	}
+
+	pub fn names(&self) -> [&str; 4] {
+		[&self.lock, &self.complete, &self.abort, &self.get_details]
+	}
+}
+
+impl Default for CounterpartyFunctions {
+	fn default() -> Self {
+		Self {
+			lock: "lock_bridge_transfer".to_string(),
+			complete: "complete_bridge_transfer".to_string(),
+			abort: "abort_bridge_transfer".to_string(),
+			get_details: "bridge_transfers".to_string(),
+		}
+	}
+}
+
+/// The state of a counterparty bridge transfer, as the `state` of the transfer details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TransferState {
+	Locked,
+	Completed,
+	Aborted,
+	NotFound,
+}
+
+impl TransferState {
+	/// Maps the `state` constant of the Move module, `None` if unknown.
+	pub fn from_state(state: u8) -> Option<Self> {
+		match state {
+			1 => Some(TransferState::Locked),
+			2 => Some(TransferState::Completed),
+			3 => Some(TransferState::Aborted),
+			_ => None,
+		}
+	}
+}
+
+/// The state of the node the client talks to, for readiness gating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NodeHealth {
+	pub chain_id: u8,
+	/// The latest ledger version of the node.
+	pub ledger_version: u64,
+	pub block_height: u64,
+	/// The timestamp of the latest ledger version, in microseconds since the unix epoch.
+	pub ledger_timestamp_usecs: u64,
+}
+
+impl NodeHealth {
+	/// How far the latest ledger timestamp is behind the given wall-clock time.
+	pub fn lag_at(&self, now: SystemTime) -> Duration {
+		let ledger_time = UNIX_EPOCH + Duration::from_micros(self.ledger_timestamp_usecs);
+		now.duration_since(ledger_time).unwrap_or(Duration::ZERO)
+	}
+
+	/// How far the latest ledger timestamp is behind the wall clock.
+	pub fn lag(&self) -> Duration {
+		self.lag_at(SystemTime::now())
+	}
+
+	/// Whether the node has fallen more than `max_lag` behind the wall clock.
+	pub fn is_stale(&self, max_lag: Duration) -> bool {
+		self.lag() > max_lag
+	}
+}
+
+/// Identifies the operation of a relayer a call belongs to, e.g., to correlate the logs
+/// of a transfer across chains.
+///
+/// Recorded in the spans of the counterparty calls and attached to their transaction errors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferContext {
+	pub request_id: String,
+	pub tags: BTreeMap<String, String>,
+}
+
+impl TransferContext {
+	pub fn new(request_id: impl Into<String>) -> Self {
+		Self { request_id: request_id.into(), tags: BTreeMap::new() }
+	}
+
+	pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.tags.insert(key.into(), value.into());
+		self
+	}
+}
+
+impl fmt::Display for TransferContext {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "request {}", self.request_id)?;
+		for (key, value) in &self.tags {
+			write!(f, " {}={}", key, value)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_node_health_lag() {
+		let health = NodeHealth {
+			chain_id: 4,
+			ledger_version: 10,
+			block_height: 5,
+			ledger_timestamp_usecs: 1_000_000,
+		};
+
+		assert_eq!(health.lag_at(UNIX_EPOCH + Duration::from_secs(3)), Duration::from_secs(2));
+		// a ledger ahead of the local clock is not lagging
+		assert_eq!(health.lag_at(UNIX_EPOCH), Duration::ZERO);
+		assert!(health.is_stale(Duration::from_secs(60)));
+	}
+
+	#[test]
+	fn test_counterparty_functions_defaults() -> Result<(), serde_json::Error> {
+		let functions: CounterpartyFunctions = serde_json::from_value(
+			serde_json::json!({ "complete": "complete_bridge_transfer_v2" }),
+		)?;
+		assert_eq!(functions.function(Call::Complete), "complete_bridge_transfer_v2");
+		assert_eq!(functions.function(Call::Lock), "lock_bridge_transfer");
+		assert_eq!(functions.function(Call::GetDetails), "bridge_transfers");
+		assert!(serde_json::from_value::<CounterpartyFunctions>(
+			serde_json::json!({ "refund": "refund_bridge_transfer" })
+		)
+		.is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_transfer_context_display() {
+		let context =
+			TransferContext::new("7f3a").with_tag("source", "eth").with_tag("attempt", "2");
+		assert_eq!(context.to_string(), "request 7f3a attempt=2 source=eth");
+		assert_eq!(TransferContext::new("7f3a").to_string(), "request 7f3a");
+	}
+
+	#[test]
+	fn test_transfer_state_from_state() {
+		assert_eq!(TransferState::from_state(1), Some(TransferState::Locked));
+		assert_eq!(TransferState::from_state(2), Some(TransferState::Completed));
+		assert_eq!(TransferState::from_state(3), Some(TransferState::Aborted));
+		assert_eq!(TransferState::from_state(0), None);
+	}
+}
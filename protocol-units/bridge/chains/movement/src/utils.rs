@@ -1,34 +1,55 @@
-use crate::MovementClient;
+use crate::error::MovementBridgeError;
+use crate::signer::{sign_transaction, Signer};
+use crate::types::MoveAmountType;
+use crate::{Config, MovementClient};
+use alloy::primitives::Uint;
 use anyhow::{Context, Result};
 use aptos_sdk::{
-	crypto::ed25519::Ed25519Signature,
+	crypto::{ed25519::Ed25519Signature, HashValue},
 	move_types::{
 		account_address::AccountAddressParseError,
 		ident_str,
+		identifier::Identifier,
 		language_storage::{ModuleId, TypeTag},
 	},
 	rest_client::{
 		aptos_api_types::{
-			EntryFunctionId, MoveType, Transaction as AptosTransaction, TransactionInfo,
-			ViewRequest,
+			AptosErrorCode, MoveType, Transaction as AptosTransaction, TransactionInfo,
+			ViewFunction,
 		},
+		error::RestError,
 		Client as RestClient, Transaction,
 	},
 	transaction_builder::TransactionFactory,
 	types::{
 		account_address::AccountAddress,
 		chain_id::ChainId,
-		transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+		transaction::{
+			EntryFunction, MultisigTransactionPayload, SignedTransaction, TransactionPayload,
+		},
 		LocalAccount,
 	},
 };
+use aptos_types::{
+	ledger_info::LedgerInfo,
+	state_store::{state_key::StateKey, state_value::StateValue},
+};
 use bridge_shared::bridge_contracts::{BridgeContractCounterpartyError, BridgeContractInitiatorError};
+use bridge_shared::types::{
+	Amount, AssetType, BridgeTransferDetails, BridgeTransferId, HashLock, InitiatorAddress,
+	RecipientAddress, TimeLock,
+};
 use derive_new::new;
+use movement_algs::backoff::Backoff;
+use movement_types::proof::{StateProofResponse, StateValueProofResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::future::Future;
+use std::hash::Hasher;
 use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
-use tracing::log::{debug, info, error};
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Error)]
 pub enum MovementAddressError {
@@ -40,7 +61,9 @@ pub enum MovementAddressError {
 	AccountParseError(#[from] AccountAddressParseError),
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+/// Displays and serializes to human-readable formats as a `0x` prefixed hex literal,
+/// binary formats like BCS get the bytes of the [AccountAddress].
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct MovementAddress(pub AccountAddress);
 
 impl From<&MovementAddress> for Vec<u8> {
@@ -63,22 +86,64 @@ impl std::fmt::Display for MovementAddress {
 	}
 }
 
-impl From<Vec<u8>> for MovementAddress {
-	fn from(vec: Vec<u8>) -> Self {
-		// Ensure the vector has the correct length
-		assert_eq!(vec.len(), AccountAddress::LENGTH);
+impl Serialize for MovementAddress {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if serializer.is_human_readable() {
+			serializer.collect_str(self)
+		} else {
+			self.0.serialize(serializer)
+		}
+	}
+}
+
+/// Accepts the literals [MovementAddress::from_str] does, with or without `0x` and leading zeros.
+impl<'de> Deserialize<'de> for MovementAddress {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		if deserializer.is_human_readable() {
+			let literal = String::deserialize(deserializer)?;
+			MovementAddress::from_str(&literal).map_err(serde::de::Error::custom)
+		} else {
+			AccountAddress::deserialize(deserializer).map(MovementAddress)
+		}
+	}
+}
+
+impl From<AccountAddress> for MovementAddress {
+	fn from(address: AccountAddress) -> Self {
+		MovementAddress(address)
+	}
+}
+
+impl From<MovementAddress> for AccountAddress {
+	fn from(address: MovementAddress) -> Self {
+		address.0
+	}
+}
+
+impl TryFrom<&[u8]> for MovementAddress {
+	type Error = MovementAddressError;
 
-		let account_address =
-			AccountAddress::from_bytes(vec).expect("Invalid byte length for AccountAddress");
-		MovementAddress(account_address)
+	fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+		if bytes.len() != AccountAddress::LENGTH {
+			return Err(MovementAddressError::InvalidByteLength);
+		}
+		Ok(MovementAddress(AccountAddress::from_bytes(bytes)?))
 	}
 }
 
-impl From<&str> for MovementAddress {
-	fn from(s: &str) -> Self {
-		let s = s.trim_start_matches("0x");
-		let bytes = hex::decode(s).expect("Invalid hex string");
-		bytes.into()
+impl TryFrom<Vec<u8>> for MovementAddress {
+	type Error = MovementAddressError;
+
+	fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+		MovementAddress::try_from(bytes.as_slice())
+	}
+}
+
+/// Deprecated, only kept for the bound of [bridge_shared::types::BridgeAddressType].
+/// Panics on bytes that aren't an address, use the `TryFrom<Vec<u8>>` conversion instead.
+impl From<Vec<u8>> for MovementAddress {
+	fn from(bytes: Vec<u8>) -> Self {
+		MovementAddress::try_from(bytes).expect("Invalid byte length for AccountAddress")
 	}
 }
 
@@ -87,6 +152,43 @@ const GAS_UNIT_LIMIT: u64 = 100000;
 /// minimum price of gas unit of aptos chains
 pub const GAS_UNIT_PRICE: u64 = 100;
 
+/// Gas and expiration settings of the transactions the bridge sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasParams {
+	pub max_gas_amount: u64,
+	pub gas_unit_price: u64,
+	/// Seconds from the node's ledger timestamp until the transaction expires,
+	/// which keeps the expiration independent of the local clock
+	pub expiration_secs: u64,
+}
+
+impl GasParams {
+	pub const DEFAULT_EXPIRATION_SECS: u64 = 30;
+
+	/// A transaction factory building transactions with these settings.
+	pub fn transaction_factory(&self, chain_id: u8) -> TransactionFactory {
+		TransactionFactory::new(ChainId::new(chain_id))
+			.with_gas_unit_price(self.gas_unit_price)
+			.with_max_gas_amount(self.max_gas_amount)
+			.with_transaction_expiration_time(self.expiration_secs)
+	}
+
+	/// The expiration of a transaction built at the given ledger timestamp.
+	pub fn expiration_timestamp_secs(&self, ledger_timestamp_usecs: u64) -> u64 {
+		ledger_timestamp_usecs / 1_000_000 + self.expiration_secs
+	}
+}
+
+impl Default for GasParams {
+	fn default() -> Self {
+		Self {
+			max_gas_amount: GAS_UNIT_LIMIT,
+			gas_unit_price: GAS_UNIT_PRICE,
+			expiration_secs: Self::DEFAULT_EXPIRATION_SECS,
+		}
+	}
+}
+
 /// Wrapper struct that adds indexing information to a type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, new)]
 pub struct Indexed<T> {
@@ -96,27 +198,115 @@ pub struct Indexed<T> {
 	pub sequence: Option<u32>,
 }
 
+/// Records the hash and gas used of a submitted transaction on the current span.
+pub fn record_transaction(txn: &AptosTransaction) {
+	if let Transaction::UserTransaction(user_txn) = txn {
+		let span = tracing::Span::current();
+		span.record("tx_hash", tracing::field::display(&user_txn.info.hash));
+		span.record("gas_used", user_txn.info.gas_used.0);
+	}
+}
+
+/// The `module::function` called by a payload, to identify its transaction in reports.
+pub fn payload_function(payload: &TransactionPayload) -> String {
+	match payload {
+		TransactionPayload::EntryFunction(entry_function) => {
+			format!("{}::{}", entry_function.module().name(), entry_function.function())
+		}
+		TransactionPayload::Multisig(_) => "multisig".to_string(),
+		_ => "script".to_string(),
+	}
+}
+
+/// The first interval when polling a submitted transaction, doubling up to the max.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Send Aptos Transaction
+///
+/// A transaction that expires before it's committed is rebuilt with a fresh expiration and
+/// resubmitted once. Each node request is bounded by [Config::DEFAULT_REQUEST_TIMEOUT].
 pub async fn send_and_confirm_aptos_transaction(
 	rest_client: &RestClient,
-	signer: &LocalAccount,
+	signer: &dyn Signer,
 	payload: TransactionPayload,
-) -> Result<AptosTransaction, String> {
+	gas_params: GasParams,
+) -> Result<AptosTransaction, MovementBridgeError> {
+	send_and_confirm_aptos_transaction_with(
+		rest_client,
+		signer,
+		payload,
+		gas_params,
+		Config::DEFAULT_REQUEST_TIMEOUT,
+		&|_| (),
+	)
+	.await
+}
+
+/// Like [send_and_confirm_aptos_transaction], bounding each node request by `request_timeout`
+/// and calling `on_signed` with the hash of each transaction before it's submitted.
+#[tracing::instrument(
+	skip_all,
+	fields(sender = %signer.address(), tx_hash = tracing::field::Empty, gas_used = tracing::field::Empty)
+)]
+pub async fn send_and_confirm_aptos_transaction_with(
+	rest_client: &RestClient,
+	signer: &dyn Signer,
+	payload: TransactionPayload,
+	gas_params: GasParams,
+	request_timeout: Duration,
+	on_signed: &(dyn Fn(HashValue) + Send + Sync),
+) -> Result<AptosTransaction, MovementBridgeError> {
 	info!("Starting send_aptos_transaction");
-	let state = rest_client
-		.get_ledger_information()
+	let submit = |payload| {
+		submit_aptos_transaction(
+			rest_client,
+			signer,
+			payload,
+			gas_params,
+			request_timeout,
+			on_signed,
+		)
+	};
+	let txn = match submit(payload.clone()).await {
+		Err(MovementBridgeError::Expired) => {
+			warn!("Transaction expired before it was committed, resubmitting");
+			submit(payload).await?
+		}
+		result => result?,
+	};
+	debug!("Response: {:?}", txn);
+	record_transaction(&txn);
+
+	confirm_user_transaction(txn)
+}
+
+/// Builds, signs and submits the transaction, expiring relative to the ledger timestamp,
+/// and waits for it.
+async fn submit_aptos_transaction(
+	rest_client: &RestClient,
+	signer: &dyn Signer,
+	payload: TransactionPayload,
+	gas_params: GasParams,
+	request_timeout: Duration,
+	on_signed: &(dyn Fn(HashValue) + Send + Sync),
+) -> Result<AptosTransaction, MovementBridgeError> {
+	let state = within(request_timeout, rest_client.get_ledger_information())
 		.await
-		.map_err(|e| format!("Failed in getting chain id: {}", e))?
+		.map_err(|e| {
+			error!("Failed in getting chain id: {}", e);
+			e
+		})?
 		.into_inner();
 	info!("Ledger information retrieved: chain_id = {}", state.chain_id);
 
-	let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
-		.with_gas_unit_price(100)
-		.with_max_gas_amount(GAS_UNIT_LIMIT);
-	let latest_account_info = rest_client
-		.get_account(signer.address())
+	let transaction_factory = gas_params.transaction_factory(state.chain_id);
+	let latest_account_info = within(request_timeout, rest_client.get_account(signer.address()))
 		.await
-		.map_err(|e| format!("Failed to get account information: {}", e))?;
+		.map_err(|e| {
+			error!("Failed to get account information: {}", e);
+			e
+		})?;
 	let account = latest_account_info.into_inner();
 	let latest_sequence_number = account.sequence_number;
 
@@ -124,38 +314,209 @@ pub async fn send_and_confirm_aptos_transaction(
 		.payload(payload)
 		.sender(signer.address())
 		.sequence_number(latest_sequence_number)
+		.expiration_timestamp_secs(gas_params.expiration_timestamp_secs(state.timestamp_usecs))
 		.build();
 
-	let signed_tx = signer.sign_transaction(raw_tx);
+	let signed_tx = sign_transaction(signer, raw_tx).await?;
 
 	debug!("Signed TX: {:?}", signed_tx);
+	on_signed(signed_tx.committed_hash());
 
-	let response = rest_client
-		.submit_and_wait(&signed_tx)
-		.await
+	within(request_timeout, rest_client.submit(&signed_tx)).await.map_err(|e| {
+		error!("Transaction submission error: {}", e); // Log the error in detail
+		e
+	})?;
 
-		.map_err(|e| {
-			let err_msg = format!("Transaction submission error: {}", e.to_string());
-			error!("{}", err_msg); // Log the error in detail
-			err_msg
-		})?;
+	wait_for_transaction(rest_client, &signed_tx, gas_params, request_timeout).await
+}
 
-	let txn = response.into_inner();
-	debug!("Response: {:?}", txn);
+/// Polls the submitted transaction until it's committed. One still pending once the ledger
+/// timestamp reached its expiration can't be committed anymore and is
+/// [MovementBridgeError::Expired]. In case the ledger stalls, the wait is also bounded by the
+/// local clock, to the expiration plus the request timeout.
+async fn wait_for_transaction(
+	rest_client: &RestClient,
+	signed_tx: &SignedTransaction,
+	gas_params: GasParams,
+	request_timeout: Duration,
+) -> Result<AptosTransaction, MovementBridgeError> {
+	let hash = signed_tx.committed_hash();
+	let deadline = tokio::time::Instant::now()
+		+ Duration::from_secs(gas_params.expiration_secs)
+		+ request_timeout;
+	let mut backoff = Backoff::new(WAIT_POLL_INTERVAL, MAX_WAIT_POLL_INTERVAL);
+	loop {
+		// read before the transaction, so one pending after it is known to be expired
+		let state = within(request_timeout, rest_client.get_ledger_information()).await?;
+		let ledger_timestamp_secs = state.inner().timestamp_usecs / 1_000_000;
+		match within(request_timeout, rest_client.get_transaction_by_hash(hash)).await {
+			Ok(response) => match response.into_inner() {
+				Transaction::PendingTransaction(_) => {}
+				txn => return Ok(txn),
+			},
+			// dropped from the mempool, it may still have been committed meanwhile
+			Err(MovementBridgeError::RpcError(RestError::Api(response)))
+				if matches!(response.error.error_code, AptosErrorCode::TransactionNotFound) => {}
+			Err(e) => return Err(e),
+		}
+		if ledger_timestamp_secs >= signed_tx.expiration_timestamp_secs() {
+			return Err(MovementBridgeError::Expired);
+		}
+		if tokio::time::Instant::now() >= deadline {
+			return Err(MovementBridgeError::Timeout);
+		}
+		tokio::time::sleep(backoff.next_delay()).await;
+	}
+}
 
+/// Bounds a single node request by `request_timeout`.
+async fn within<T>(
+	request_timeout: Duration,
+	request: impl Future<Output = Result<T, RestError>>,
+) -> Result<T, MovementBridgeError> {
+	match tokio::time::timeout(request_timeout, request).await {
+		Ok(result) => result.map_err(MovementBridgeError::from),
+		Err(_) => Err(MovementBridgeError::Timeout),
+	}
+}
+
+/// Checks that the transaction is a successful user transaction.
+fn confirm_user_transaction(
+	txn: AptosTransaction,
+) -> Result<AptosTransaction, MovementBridgeError> {
 	match &txn {
-	Transaction::UserTransaction(user_txn) => {
-		if !user_txn.info.success {
-		return Err(format!(
-			"Transaction failed with status: {}",user_txn.info.vm_status));
+		Transaction::UserTransaction(user_txn) => {
+			if !user_txn.info.success {
+				return Err(MovementBridgeError::from_vm_status(&user_txn.info.vm_status));
+			}
 		}
-	},
-	_ => return Err("Expected a UserTransaction, but got a different transaction type.".to_string()),
+		_ => return Err(MovementBridgeError::NotUserTransaction),
 	}
 
 	Ok(txn)
 }
 
+/// Send a batch of Aptos Transactions
+///
+/// The payloads are signed under consecutive sequence numbers of the signer and submitted in one
+/// request before waiting for any of them, and each gets its own result. `on_signed` is called
+/// with the index and hash of each transaction before the batch is submitted. A failed signing
+/// stops the batch, and transactions after a rejected one aren't waited for, since their
+/// sequence numbers can't execute until the gap is filled.
+/// Each node request is bounded by `request_timeout` and transactions are waited for as in
+/// [send_and_confirm_aptos_transaction_with], but unlike single transactions, expired ones
+/// aren't resubmitted.
+///
+/// Fails as a whole if the batch can't be prepared, in which case nothing was submitted.
+pub async fn send_and_confirm_aptos_transactions(
+	rest_client: &RestClient,
+	signer: &dyn Signer,
+	payloads: Vec<TransactionPayload>,
+	gas_params: GasParams,
+	request_timeout: Duration,
+	on_signed: &(dyn Fn(usize, HashValue) + Send + Sync),
+) -> Result<Vec<Result<AptosTransaction, MovementBridgeError>>, MovementBridgeError> {
+	let count = payloads.len();
+	let setup = async {
+		let state = within(request_timeout, rest_client.get_ledger_information()).await?;
+		let account = within(request_timeout, rest_client.get_account(signer.address())).await?;
+		Ok::<_, MovementBridgeError>((state.into_inner(), account.into_inner().sequence_number))
+	};
+	let (state, first_sequence_number) = setup.await.map_err(|e| {
+		error!("Failed to prepare the batch: {}", e);
+		e
+	})?;
+
+	let transaction_factory = gas_params.transaction_factory(state.chain_id);
+	let expiration_timestamp_secs = gas_params.expiration_timestamp_secs(state.timestamp_usecs);
+
+	let mut signed = Vec::with_capacity(count);
+	let mut sign_error = None;
+	for (sequence_number, payload) in (first_sequence_number..).zip(payloads) {
+		let raw_tx = transaction_factory
+			.payload(payload)
+			.sender(signer.address())
+			.sequence_number(sequence_number)
+			.expiration_timestamp_secs(expiration_timestamp_secs)
+			.build();
+		match sign_transaction(signer, raw_tx).await {
+			Ok(signed_tx) => {
+				on_signed(signed.len(), signed_tx.committed_hash());
+				signed.push(signed_tx);
+			}
+			Err(e) => {
+				error!("Batch signing stopped at sequence number {}: {}", sequence_number, e);
+				sign_error = Some(e);
+				break;
+			}
+		}
+	}
+
+	let mut results = Vec::with_capacity(count);
+	let mut blocked = false;
+	let submitted =
+		match tokio::time::timeout(request_timeout, submit_aptos_transactions(rest_client, &signed))
+			.await
+		{
+			Ok(submitted) => submitted,
+			Err(_) => {
+				// the node may have accepted them, waiting tells
+				warn!("Submitting the batch timed out, waiting for its transactions");
+				signed.iter().map(|_| Ok(())).collect()
+			}
+		};
+	for (signed_tx, submitted) in signed.iter().zip(submitted) {
+		let result = match submitted {
+			Ok(()) if blocked => Err(MovementBridgeError::Blocked),
+			Ok(()) => wait_for_transaction(rest_client, signed_tx, gas_params, request_timeout)
+				.await
+				.and_then(confirm_user_transaction),
+			Err(e) => {
+				blocked = true;
+				Err(e)
+			}
+		};
+		results.push(result);
+	}
+	results.extend(sign_error.map(Err));
+	results.resize_with(count, || Err(MovementBridgeError::NotSubmitted));
+	Ok(results)
+}
+
+/// Submits the signed transactions to the batch endpoint of the node in one request, without
+/// waiting for them, and returns whether each was accepted. If the request fails as a whole,
+/// the first transaction gets its error and the others [MovementBridgeError::NotSubmitted].
+pub async fn submit_aptos_transactions(
+	rest_client: &RestClient,
+	txns: &[SignedTransaction],
+) -> Vec<Result<(), MovementBridgeError>> {
+	if txns.is_empty() {
+		return Vec::new();
+	}
+	let mut results: Vec<Result<(), MovementBridgeError>> = txns.iter().map(|_| Ok(())).collect();
+	match rest_client.submit_batch_bcs(txns).await {
+		Ok(response) => {
+			for failure in response.into_inner().transaction_failures {
+				if let Some(result) = results.get_mut(failure.transaction_index) {
+					error!(
+						"Batch transaction {} rejected: {}",
+						failure.transaction_index, failure.error.message
+					);
+					*result = Err(MovementBridgeError::Rejected(failure.error.message));
+				}
+			}
+		}
+		Err(e) => {
+			error!("Batch submission failed: {}", e);
+			results[0] = Err(MovementBridgeError::from(e));
+			for result in &mut results[1..] {
+				*result = Err(MovementBridgeError::NotSubmitted);
+			}
+		}
+	}
+	results
+}
+
 pub fn val_as_str(value: Option<&Value>) -> Result<&str, BridgeContractCounterpartyError> {
 	value
 		.as_ref()
@@ -181,6 +542,329 @@ pub fn val_as_u64_initiator(value: Option<&Value>) -> Result<u64, BridgeContract
 	    .ok_or(BridgeContractInitiatorError::SerializationError)
 }
 
+/// Decodes the counterparty `bridge_transfers` view response, either the returned tuple
+/// or a Move struct with the `originator`, `recipient`, `amount`, `hash_lock`, `time_lock`
+/// and `state` fields.
+///
+/// The `originator` is the `vector<u8>` of the Ethereum address, left padded into the
+/// initiator [MovementAddress], and the `recipient` is the Move `address`, kept as its bytes.
+/// Byte fields may be hex strings or arrays of bytes, and numbers may be strings,
+/// as the REST API renders u64 and wider values as strings. The `amount` is decoded as the
+/// `amount_type` it was locked as.
+pub fn decode_transfer_details(
+	bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	value: Value,
+	amount_type: MoveAmountType,
+) -> Result<BridgeTransferDetails<MovementAddress, [u8; 32]>, BridgeContractCounterpartyError> {
+	const FIELDS: [&str; 6] = ["originator", "recipient", "amount", "hash_lock", "time_lock", "state"];
+	let fields = match value {
+		Value::Array(values) if values.len() == FIELDS.len() => values,
+		Value::Array(_) => return Err(BridgeContractCounterpartyError::InvalidResponseLength),
+		Value::Object(mut object) => FIELDS
+			.iter()
+			.map(|field| object.remove(*field))
+			.collect::<Option<Vec<_>>>()
+			.ok_or(BridgeContractCounterpartyError::SerializationError)?,
+		_ => return Err(BridgeContractCounterpartyError::SerializationError),
+	};
+
+	// addresses may come in the short form, e.g. `0x1`
+	let recipient = match &fields[1] {
+		Value::String(address) => AccountAddress::from_hex_literal(address)
+			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?,
+		_ => return Err(BridgeContractCounterpartyError::SerializationError),
+	};
+	let state = u8::try_from(decode_u64(&fields[5])?)
+		.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
+
+	MoveBridgeTransfer {
+		originator: decode_bytes(&fields[0])?,
+		recipient,
+		amount: decode_amount(&fields[2], amount_type)?,
+		hash_lock: decode_bytes(&fields[3])?,
+		time_lock: decode_u64(&fields[4])?,
+		state,
+	}
+	.into_details(bridge_transfer_id)
+}
+
+/// The counterparty `BridgeTransfer` Move struct, in the field order of its BCS layout.
+/// The amount is the Move integer type of the [MoveAmountType] it was locked as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveBridgeTransfer<N = u64> {
+	/// The Ethereum address of the initiator
+	pub originator: Vec<u8>,
+	pub recipient: AccountAddress,
+	pub amount: N,
+	pub hash_lock: Vec<u8>,
+	pub time_lock: u64,
+	pub state: u8,
+}
+
+impl<N: Into<Uint<256, 4>>> MoveBridgeTransfer<N> {
+	/// The `originator` is left padded into the initiator [MovementAddress], and the
+	/// `recipient` is kept as the bytes of its address.
+	/// Amounts which don't fit in u64 are kept whole as [AssetType::Token].
+	pub fn into_details(
+		self,
+		bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	) -> Result<BridgeTransferDetails<MovementAddress, [u8; 32]>, BridgeContractCounterpartyError> {
+		if self.originator.len() > AccountAddress::LENGTH {
+			return Err(BridgeContractCounterpartyError::SerializationError);
+		}
+		let mut initiator = [0u8; AccountAddress::LENGTH];
+		initiator[AccountAddress::LENGTH - self.originator.len()..]
+			.copy_from_slice(&self.originator);
+		let hash_lock: [u8; 32] = self
+			.hash_lock
+			.try_into()
+			.map_err(|_| BridgeContractCounterpartyError::SerializationError)?;
+
+		Ok(BridgeTransferDetails {
+			bridge_transfer_id,
+			initiator_address: InitiatorAddress(MovementAddress(AccountAddress::new(initiator))),
+			recipient_address: RecipientAddress(self.recipient.to_vec()),
+			amount: move_amount(self.amount.into()),
+			hash_lock: HashLock(hash_lock),
+			time_lock: TimeLock(self.time_lock),
+			state: self.state,
+		})
+	}
+}
+
+/// A Move `u256`, which BCS encodes as its 32 little endian bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveU256(pub [u8; 32]);
+
+impl From<MoveU256> for Uint<256, 4> {
+	fn from(MoveU256(bytes): MoveU256) -> Self {
+		Uint::from_le_bytes(bytes)
+	}
+}
+
+/// The bridge amount of a Move amount, [AssetType::Moveth] if it fits in u64.
+fn move_amount(amount: Uint<256, 4>) -> Amount {
+	match u64::try_from(amount) {
+		Ok(amount) => Amount(AssetType::Moveth(amount)),
+		Err(_) => Amount(AssetType::Token(amount)),
+	}
+}
+
+/// Where a counterparty bridge transfer is stored: the bucket of the `transfers` smart table
+/// of the `BridgeTransferStore`, which is a table item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferLocation {
+	pub state_key: StateKey,
+	/// The hash of the bridge transfer id, kept by the entries of the bucket
+	pub hash: u64,
+}
+
+/// An entry of a bucket of an `aptos_std::smart_table::SmartTable`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SmartTableEntry<K, V> {
+	hash: u64,
+	key: K,
+	value: V,
+}
+
+/// Locates a bridge transfer in the `transfers` smart table of the JSON of a
+/// `BridgeTransferStore` resource, hashing its id and picking its bucket as
+/// `aptos_std::smart_table` does.
+pub fn locate_bridge_transfer(
+	store: &Value,
+	bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+) -> Result<TransferLocation> {
+	let transfers = &store["transfers"];
+	let handle = transfers["buckets"]["inner"]["handle"]
+		.as_str()
+		.and_then(|handle| AccountAddress::from_hex_literal(handle).ok())
+		.context("Invalid bridge transfer table handle")?;
+	let num_buckets = decode_u64(&transfers["num_buckets"])?;
+	let level = u8::try_from(decode_u64(&transfers["level"])?)?;
+
+	let mut hasher = siphasher::sip::SipHasher::new();
+	hasher.write(&bcs::to_bytes(&bridge_transfer_id.0.to_vec())?);
+	let hash = hasher.finish();
+	let bucket = bucket_index(level, num_buckets, hash);
+
+	// a `StateKey::TableItem`, the second variant, of the bucket index keyed in the table
+	let state_key = bcs::to_bytes(&(1u8, handle, bcs::to_bytes(&bucket)?))?;
+	Ok(TransferLocation { state_key: bcs::from_bytes(&state_key)?, hash })
+}
+
+/// The bucket of a hash in a smart table of `num_buckets` buckets at `level`, as
+/// `smart_table::bucket_index`.
+fn bucket_index(level: u8, num_buckets: u64, hash: u64) -> u64 {
+	let index = hash % (1 << (level + 1));
+	if index < num_buckets {
+		index
+	} else {
+		index % (1 << level)
+	}
+}
+
+/// Finds a bridge transfer in the proven value of its bucket, see [locate_bridge_transfer],
+/// `None` if it isn't stored. The amounts are decoded as `amount_type`.
+pub fn proven_transfer_details(
+	bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	location: &TransferLocation,
+	bucket: Option<&StateValue>,
+	amount_type: MoveAmountType,
+) -> Result<Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>> {
+	let Some(bucket) = bucket else {
+		return Ok(None);
+	};
+	match amount_type {
+		MoveAmountType::U64 => find_transfer::<u64>(bridge_transfer_id, location, bucket),
+		MoveAmountType::U128 => find_transfer::<u128>(bridge_transfer_id, location, bucket),
+		MoveAmountType::U256 => find_transfer::<MoveU256>(bridge_transfer_id, location, bucket),
+	}
+}
+
+fn find_transfer<N: serde::de::DeserializeOwned + Into<Uint<256, 4>>>(
+	bridge_transfer_id: BridgeTransferId<[u8; 32]>,
+	location: &TransferLocation,
+	bucket: &StateValue,
+) -> Result<Option<BridgeTransferDetails<MovementAddress, [u8; 32]>>> {
+	let entries: Vec<SmartTableEntry<Vec<u8>, MoveBridgeTransfer<N>>> =
+		bcs::from_bytes(bucket.bytes())?;
+	entries
+		.into_iter()
+		.find(|entry| entry.hash == location.hash && entry.key == bridge_transfer_id.0)
+		.map(|entry| entry.value.into_details(bridge_transfer_id))
+		.transpose()
+		.map_err(Into::into)
+}
+
+fn decode_bytes(value: &Value) -> Result<Vec<u8>, BridgeContractCounterpartyError> {
+	match value {
+		Value::String(hex) => hex::decode(hex.trim_start_matches("0x"))
+			.map_err(|_| BridgeContractCounterpartyError::SerializationError),
+		Value::Array(bytes) => bytes
+			.iter()
+			.map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+			.collect::<Option<Vec<_>>>()
+			.ok_or(BridgeContractCounterpartyError::SerializationError),
+		_ => Err(BridgeContractCounterpartyError::SerializationError),
+	}
+}
+
+fn decode_u64(value: &Value) -> Result<u64, BridgeContractCounterpartyError> {
+	match value {
+		Value::String(number) => number.parse().ok(),
+		_ => value.as_u64(),
+	}
+	.ok_or(BridgeContractCounterpartyError::SerializationError)
+}
+
+/// Decodes an amount of the Move integer type, failing if it doesn't fit the type.
+fn decode_amount(
+	value: &Value,
+	amount_type: MoveAmountType,
+) -> Result<Uint<256, 4>, BridgeContractCounterpartyError> {
+	let amount = match value {
+		Value::String(number) => number.parse().ok(),
+		_ => value.as_u64().map(Uint::from),
+	}
+	.ok_or(BridgeContractCounterpartyError::SerializationError)?;
+	if amount.bit_len() > amount_type.byte_len() * 8 {
+		return Err(BridgeContractCounterpartyError::SerializationError);
+	}
+	Ok(amount)
+}
+
+/// BCS-serializes an amount as the Move integer type, failing instead of truncating.
+pub fn serialize_amount(
+	amount: Uint<256, 4>,
+	amount_type: MoveAmountType,
+) -> Result<Vec<u8>, MovementBridgeError> {
+	let bytes = amount.to_le_bytes::<32>();
+	let (value, overflow) = bytes.split_at(amount_type.byte_len());
+	if overflow.iter().any(|byte| *byte != 0) {
+		return Err(MovementBridgeError::AmountOverflow { amount, amount_type });
+	}
+	Ok(value.to_vec())
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DecimalConversionError {
+	#[error("Amount {amount} overflows when scaled from {from} to {to} decimals")]
+	Overflow { amount: Uint<256, 4>, from: u8, to: u8 },
+	#[error("Amount {amount} loses {remainder} when scaled from {from} to {to} decimals")]
+	PrecisionLoss { amount: Uint<256, 4>, remainder: Uint<256, 4>, from: u8, to: u8 },
+}
+
+/// Scales amounts between chains whose tokens use different decimals,
+/// e.g., from an 18 decimal ERC-20 token to an 8 decimal Move coin.
+///
+/// Conversions fail rather than round or truncate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, new)]
+pub struct DecimalConverter {
+	pub from_decimals: u8,
+	pub to_decimals: u8,
+}
+
+impl DecimalConverter {
+	/// Converts in the opposite direction.
+	pub fn reversed(&self) -> Self {
+		Self::new(self.to_decimals, self.from_decimals)
+	}
+
+	/// Scales a raw token value.
+	pub fn convert_value(
+		&self,
+		amount: Uint<256, 4>,
+	) -> Result<Uint<256, 4>, DecimalConversionError> {
+		let (from, to) = (self.from_decimals, self.to_decimals);
+		let scale = Uint::<256, 4>::from(10u64).checked_pow(Uint::from(from.abs_diff(to)));
+		if to >= from {
+			scale
+				.and_then(|scale| amount.checked_mul(scale))
+				.ok_or(DecimalConversionError::Overflow { amount, from, to })
+		} else {
+			// a scale beyond u256 exceeds any amount, so only zero converts
+			let Some(scale) = scale else {
+				return if amount == Uint::ZERO {
+					Ok(amount)
+				} else {
+					Err(DecimalConversionError::PrecisionLoss {
+						amount,
+						remainder: amount,
+						from,
+						to,
+					})
+				};
+			};
+			let remainder = amount % scale;
+			if remainder != Uint::ZERO {
+				return Err(DecimalConversionError::PrecisionLoss { amount, remainder, from, to });
+			}
+			Ok(amount / scale)
+		}
+	}
+
+	/// Scales each value of the amount, keeping its asset type.
+	pub fn convert(&self, amount: Amount) -> Result<Amount, DecimalConversionError> {
+		let asset = match amount.0 {
+			AssetType::EthAndWeth((eth, weth)) => {
+				AssetType::EthAndWeth((self.convert_u64(eth)?, self.convert_u64(weth)?))
+			}
+			AssetType::Moveth(value) => AssetType::Moveth(self.convert_u64(value)?),
+			AssetType::Token(value) => AssetType::Token(self.convert_value(value)?),
+		};
+		Ok(Amount(asset))
+	}
+
+	fn convert_u64(&self, value: u64) -> Result<u64, DecimalConversionError> {
+		let converted = self.convert_value(Uint::from(value))?;
+		u64::try_from(converted).map_err(|_| DecimalConversionError::Overflow {
+			amount: Uint::from(value),
+			from: self.from_decimals,
+			to: self.to_decimals,
+		})
+	}
+}
+
 pub fn serialize_u64(value: &u64) -> Result<Vec<u8>, BridgeContractCounterpartyError> {
 	bcs::to_bytes(value).map_err(|_| BridgeContractCounterpartyError::SerializationError)
 }
@@ -211,17 +895,15 @@ pub async fn simulate_aptos_transaction(
 	payload: TransactionPayload,
 ) -> Result<TransactionInfo> {
 	let state = aptos_client
-		.rest_client
+		.rest_client()
 		.get_ledger_information()
 		.await
 		.context("Failed in getting chain id")?
 		.into_inner();
 
-	let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
-		.with_gas_unit_price(GAS_UNIT_PRICE)
-		.with_max_gas_amount(GAS_UNIT_LIMIT);
+	let transaction_factory = GasParams::default().transaction_factory(state.chain_id);
 
-	let latest_account_info = aptos_client.rest_client.get_account(signer.address()).await?;
+	let latest_account_info = aptos_client.rest_client().get_account(signer.address()).await?;
 	let account = latest_account_info.into_inner();
 	let latest_sequence_number = account.sequence_number;
 
@@ -237,7 +919,7 @@ pub async fn simulate_aptos_transaction(
 		Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
 	);
 
-	let response_txns = aptos_client.rest_client.simulate(&signed_tx).await?.into_inner();
+	let response_txns = aptos_client.rest_client().simulate(&signed_tx).await?.into_inner();
 	let response = response_txns[0].clone();
 
 	Ok(response.info)
@@ -259,28 +941,1018 @@ pub fn make_aptos_payload(
 	))
 }
 
-/// Send View Request
-pub async fn send_view_request(
-	aptos_client: &MovementClient,
-	package_address: String,
-	module_name: String,
-	function_name: String,
-	type_arguments: Vec<MoveType>,
-	arguments: Vec<serde_json::Value>,
-) -> Result<Vec<serde_json::Value>, anyhow::Error> {
-	let view_response = aptos_client
-		.rest_client
-		.view(
-			&ViewRequest {
-				function: EntryFunctionId::from_str(&format!(
-					"{package_address}::{module_name}::{function_name}"
-				))
-				.unwrap(),
-				type_arguments,
-				arguments,
+/// Like [make_aptos_payload], for module and function names only known at runtime, e.g., configured.
+pub fn try_make_aptos_payload(
+	package_address: AccountAddress,
+	module_name: &str,
+	function_name: &str,
+	ty_args: Vec<TypeTag>,
+	args: Vec<Vec<u8>>,
+) -> Result<TransactionPayload, MovementBridgeError> {
+	let identifier = |name: &str| {
+		Identifier::new(name).map_err(|_| MovementBridgeError::InvalidIdentifier(name.to_string()))
+	};
+	Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+		ModuleId::new(package_address, identifier(module_name)?),
+		identifier(function_name)?,
+		ty_args,
+		args,
+	)))
+}
+
+/// Name of the framework module managing multisig accounts
+pub const MULTISIG_MODULE_NAME: &str = "multisig_account";
+
+/// Make a payload proposing `payload` as a transaction of the multisig account
+pub fn make_multisig_propose_payload(
+	multisig_address: AccountAddress,
+	payload: TransactionPayload,
+) -> Result<TransactionPayload> {
+	let entry_function = match payload {
+		TransactionPayload::EntryFunction(entry_function) => entry_function,
+		_ => return Err(anyhow::anyhow!("Only entry function payloads can be proposed to a multisig")),
+	};
+	let multisig_payload =
+		bcs::to_bytes(&MultisigTransactionPayload::EntryFunction(entry_function))?;
+
+	Ok(make_aptos_payload(
+		AccountAddress::ONE,
+		MULTISIG_MODULE_NAME,
+		"create_transaction",
+		Vec::new(),
+		vec![bcs::to_bytes(&multisig_address)?, bcs::to_bytes(&multisig_payload)?],
+	))
+}
+
+/// The sequence number of the multisig transaction proposed to `multisig_address` by the
+/// committed `transaction`, read from the event the `multisig_account` module emitted, so a
+/// concurrent proposal can't be mistaken for it.
+pub fn proposed_sequence_number(
+	transaction: &AptosTransaction,
+	multisig_address: AccountAddress,
+) -> Result<u64> {
+	let AptosTransaction::UserTransaction(user_transaction) = transaction else {
+		return Err(MovementBridgeError::NotUserTransaction.into());
+	};
+	user_transaction
+		.events
+		.iter()
+		.find_map(|event| {
+			let MoveType::Struct(tag) = &event.typ else {
+				return None;
+			};
+			if *tag.address.inner() != AccountAddress::ONE
+				|| tag.module.as_str() != MULTISIG_MODULE_NAME
+			{
+				return None;
+			}
+			// the event of the handle of the multisig account, or the module event naming it
+			let account = match tag.name.as_str() {
+				"CreateTransactionEvent" => *event.guid.account_address.inner(),
+				"CreateTransaction" => event.data["multisig_account"]
+					.as_str()
+					.and_then(|address| AccountAddress::from_hex_literal(address).ok())?,
+				_ => return None,
+			};
+			(account == multisig_address)
+				.then(|| decode_u64(&event.data["sequence_number"]).ok())
+				.flatten()
+		})
+		.with_context(|| format!("No multisig transaction proposed to {}", multisig_address))
+}
+
+/// Make a payload approving the multisig transaction with the given sequence number
+pub fn make_multisig_approve_payload(
+	multisig_address: AccountAddress,
+	sequence_number: u64,
+) -> Result<TransactionPayload> {
+	Ok(make_aptos_payload(
+		AccountAddress::ONE,
+		MULTISIG_MODULE_NAME,
+		"approve_transaction",
+		Vec::new(),
+		vec![bcs::to_bytes(&multisig_address)?, bcs::to_bytes(&sequence_number)?],
+	))
+}
+
+/// Calls a view function with BCS encoded arguments, optionally at a ledger version, returning
+/// the JSON encoded return values. Aborts of the view function are reported as
+/// [MovementBridgeError::AbortCode].
+pub async fn send_view_function(
+	rest_client: &RestClient,
+	address: AccountAddress,
+	module: &str,
+	function: &str,
+	type_args: Vec<TypeTag>,
+	args: Vec<Vec<u8>>,
+	version: Option<u64>,
+) -> Result<Vec<Value>, MovementBridgeError> {
+	let identifier = |name: &str| {
+		Identifier::new(name).map_err(|_| MovementBridgeError::InvalidIdentifier(name.to_string()))
+	};
+	let request = ViewFunction {
+		module: ModuleId::new(address, identifier(module)?),
+		function: identifier(function)?,
+		ty_args: type_args,
+		args,
+	};
+	let response = rest_client
+		.view_bcs_with_json_response(&request, version)
+		.await
+		.map_err(MovementBridgeError::from_view_error)?;
+	Ok(response.into_inner())
+}
+
+/// Joins the path of an endpoint to the base URL of the movement REST service, which is
+/// taken as a directory, so a base path like `http://host/rest` keeps its last segment.
+pub fn movement_rest_endpoint(movement_rest_url: &url::Url, path: &str) -> Result<url::Url> {
+	let mut base = movement_rest_url.clone();
+	if !base.path().ends_with('/') {
+		base.set_path(&format!("{}/", base.path()));
+	}
+	Ok(base.join(path)?)
+}
+
+/// Fetches the proof of the transaction at `version` from the movement REST service
+/// and checks it with [verify_state_proof].
+pub async fn fetch_state_proof(
+	http_client: &reqwest::Client,
+	movement_rest_url: &url::Url,
+	version: u64,
+) -> Result<StateProofResponse> {
+	let url =
+		movement_rest_endpoint(movement_rest_url, &format!("movement/v1/state-proof/{}", version))?;
+	let proof = http_client.get(url).send().await?.error_for_status()?.json().await?;
+	verify_state_proof(&proof, version)?;
+	Ok(proof)
+}
+
+/// Fetches the proof of the value of `state_key` at `version` from the movement REST service
+/// and checks it with [verify_state_value_proof].
+pub async fn fetch_state_value_proof(
+	http_client: &reqwest::Client,
+	movement_rest_url: &url::Url,
+	version: u64,
+	state_key: &StateKey,
+) -> Result<StateValueProofResponse> {
+	let mut url = movement_rest_endpoint(
+		movement_rest_url,
+		&format!("movement/v1/state-value-proof/{}", version),
+	)?;
+	url.query_pairs_mut().append_pair("state_key", &hex::encode(bcs::to_bytes(state_key)?));
+	let proof = http_client.get(url).send().await?.error_for_status()?.json().await?;
+	verify_state_value_proof(&proof, version, state_key)?;
+	Ok(proof)
+}
+
+/// Checks a proof served by the movement REST service has a known schema, is for the
+/// transaction at `version` and leads to the accumulator root of the ledger info it carries.
+///
+/// The ledger info itself isn't checked here, see [verify_committed_ledger_info].
+pub fn verify_state_proof(proof: &StateProofResponse, version: u64) -> Result<()> {
+	check_state_proof_of(proof, version)?;
+	let ledger_info = proof.state_proof.latest_ledger_info();
+	proof.verify_transaction(ledger_info.transaction_accumulator_hash())
+}
+
+/// Checks a state value proof is for `state_key` at `version` as [verify_state_proof] does,
+/// and proves the value in the state checkpoint of that version.
+pub fn verify_state_value_proof(
+	proof: &StateValueProofResponse,
+	version: u64,
+	state_key: &StateKey,
+) -> Result<()> {
+	check_state_proof_of(&proof.proof, version)?;
+	if proof.state_key != *state_key {
+		anyhow::bail!("Expected the proof of the requested state key");
+	}
+	let ledger_info = proof.proof.state_proof.latest_ledger_info();
+	proof.verify(ledger_info.transaction_accumulator_hash())
+}
+
+fn check_state_proof_of(proof: &StateProofResponse, version: u64) -> Result<()> {
+	if proof.version != StateProofResponse::VERSION {
+		anyhow::bail!("Unknown state proof schema version {}", proof.version);
+	}
+	if proof.tx_index != version {
+		anyhow::bail!("Expected the proof of version {}, got version {}", version, proof.tx_index);
+	}
+	Ok(())
+}
+
+/// Checks the ledger info a proof leads to was committed, by the accumulator root the node
+/// reports for the transaction at its version. This trusts the node, as its reads do,
+/// instead of checking the signatures of the validators.
+pub fn verify_committed_ledger_info(
+	ledger_info: &LedgerInfo,
+	transaction: &AptosTransaction,
+) -> Result<()> {
+	let committed_root = transaction.transaction_info()?.accumulator_root_hash;
+	if committed_root.0 != ledger_info.transaction_accumulator_hash() {
+		anyhow::bail!(
+			"Ledger info at version {} isn't committed, its accumulator root differs from the node",
+			ledger_info.version()
+		);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_sdk::crypto::hash::CryptoHash;
+	use aptos_types::{
+		aggregate_signature::AggregateSignature,
+		block_info::BlockInfo,
+		epoch_change::EpochChangeProof,
+		ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+		proof::{
+			SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorProof,
+			TransactionInfoWithProof,
+		},
+		state_proof::StateProof,
+		transaction::ExecutionStatus,
+	};
+	use rand::SeedableRng;
+	use std::time::{SystemTime, UNIX_EPOCH};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::TcpListener;
+
+	/// Answers a single request with the given status and JSON body, returning the request.
+	async fn serve_once(
+		status: &'static str,
+		body: impl Into<String>,
+	) -> (url::Url, tokio::task::JoinHandle<String>) {
+		let body = body.into();
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = url::Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+		let handle = tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut request = Vec::new();
+			let mut buffer = [0u8; 4096];
+			// read the headers and as much body as announced
+			loop {
+				let read = stream.read(&mut buffer).await.unwrap();
+				request.extend_from_slice(&buffer[..read]);
+				let text = String::from_utf8_lossy(&request).to_lowercase();
+				if let Some(end) = text.find("\r\n\r\n") {
+					let length = text
+						.lines()
+						.find_map(|line| line.strip_prefix("content-length:"))
+						.map_or(0, |length| length.trim().parse().unwrap());
+					if request.len() >= end + 4 + length || read == 0 {
+						break;
+					}
+				}
+			}
+			let response = format!(
+				"HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+				x-aptos-chain-id: 4\r\nx-aptos-ledger-version: 10\r\n\
+				x-aptos-ledger-oldest-version: 0\r\nx-aptos-ledger-timestampusec: 1000\r\n\
+				x-aptos-epoch: 1\r\nx-aptos-block-height: 5\r\nx-aptos-oldest-block-height: 0\r\n\
+				connection: close\r\n\r\n{}",
+				status,
+				body.len(),
+				body
+			);
+			stream.write_all(response.as_bytes()).await.unwrap();
+			String::from_utf8_lossy(&request).into_owned()
+		});
+		(url, handle)
+	}
+
+	#[tokio::test]
+	async fn test_lock_coin_type_is_checked_once() {
+		let abi = serde_json::json!({
+			"bytecode": "0x",
+			"abi": {
+				"address": "0xcafe",
+				"name": "atomic_bridge_counterparty",
+				"friends": [],
+				"exposed_functions": [{
+					"name": "lock_bridge_transfer",
+					"visibility": "public",
+					"is_entry": true,
+					"is_view": false,
+					"generic_type_params": [{ "constraints": [] }],
+					"params": [],
+					"return": [],
+				}],
+				"structs": [],
 			},
-			Option::None,
+		});
+		let (url, request) = serve_once("200 OK", abi.to_string()).await;
+		let config = Config {
+			rpc_url: Some(url.to_string()),
+			coin_type: Some(TypeTag::from_str("0x1::aptos_coin::AptosCoin").unwrap()),
+			request_timeout: Duration::from_secs(5),
+			..Config::build_for_test()
+		};
+		let mut client = MovementClient::new(config).await.unwrap();
+		client.check_configured_coin_type().await.unwrap();
+		assert!(request.await.unwrap().contains("/module/atomic_bridge_counterparty"));
+
+		// the node is gone, so only the cached result can pass
+		client.check_configured_coin_type().await.unwrap();
+		client.clone().check_configured_coin_type().await.unwrap();
+
+		// another counterparty is checked again
+		client.counterparty_address = AccountAddress::new([7; 32]);
+		assert!(client.check_configured_coin_type().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_send_view_function() {
+		// recorded from `0x1::multisig_account::next_sequence_number`
+		let (url, request) = serve_once("200 OK", r#"["2"]"#).await;
+		let values = send_view_function(
+			&RestClient::new(url),
+			AccountAddress::ONE,
+			MULTISIG_MODULE_NAME,
+			"next_sequence_number",
+			Vec::new(),
+			vec![bcs::to_bytes(&AccountAddress::new([0xab; 32])).unwrap()],
+			None,
+		)
+		.await
+		.unwrap();
+		assert_eq!(values, vec![Value::from("2")]);
+		assert!(request.await.unwrap().starts_with("POST /v1/view"));
+
+		// recorded from a `bridge_transfers` call for an unknown transfer
+		let (url, _) = serve_once(
+			"400 Bad Request",
+			r#"{"message":"Move abort in 0xcafe::atomic_bridge_counterparty: 0x1","error_code":"invalid_input","vm_error_code":null}"#,
+		)
+		.await;
+		let error = send_view_function(
+			&RestClient::new(url),
+			AccountAddress::ONE,
+			"atomic_bridge_counterparty",
+			"bridge_transfers",
+			Vec::new(),
+			vec![bcs::to_bytes(&vec![0u8; 32]).unwrap()],
+			Some(7),
 		)
-		.await?;
-	Ok(view_response.inner().clone())
+		.await
+		.unwrap_err();
+		assert!(matches!(error, MovementBridgeError::AbortCode(0x1)));
+
+		let error = send_view_function(
+			&RestClient::new(url::Url::parse("http://127.0.0.1:1").unwrap()),
+			AccountAddress::ONE,
+			"not an identifier",
+			"f",
+			Vec::new(),
+			Vec::new(),
+			None,
+		)
+		.await
+		.unwrap_err();
+		assert!(matches!(error, MovementBridgeError::InvalidIdentifier(_)));
+	}
+
+	#[tokio::test]
+	async fn test_submit_aptos_transactions() {
+		let mut rng = rand::rngs::StdRng::from_seed([3u8; 32]);
+		let account = LocalAccount::generate(&mut rng);
+		let txns: Vec<_> = (0..3)
+			.map(|sequence_number| {
+				account.sign_transaction(
+					GasParams::default()
+						.transaction_factory(4)
+						.payload(make_aptos_payload(
+							AccountAddress::ONE,
+							"coin",
+							"transfer",
+							Vec::new(),
+							Vec::new(),
+						))
+						.sender(account.address())
+						.sequence_number(sequence_number)
+						.build(),
+				)
+			})
+			.collect();
+
+		// recorded from a batch with a stale sequence number
+		let (url, request) = serve_once(
+			"206 Partial Content",
+			r#"{"transaction_failures":[{"error":{"message":"Invalid transaction: Type: Validation Code: SEQUENCE_NUMBER_TOO_OLD","error_code":"vm_error","vm_error_code":3},"transaction_index":1}]}"#,
+		)
+		.await;
+		let results = submit_aptos_transactions(&RestClient::new(url), &txns).await;
+		assert!(request.await.unwrap().starts_with("POST /v1/transactions/batch"));
+		assert_eq!(results.len(), 3);
+		assert!(results[0].is_ok());
+		let Err(MovementBridgeError::Rejected(message)) = &results[1] else {
+			panic!("Expected a rejection, got {:?}", results[1]);
+		};
+		assert!(message.contains("SEQUENCE_NUMBER_TOO_OLD"));
+		assert!(results[2].is_ok());
+
+		let results = submit_aptos_transactions(
+			&RestClient::new(url::Url::parse("http://127.0.0.1:1").unwrap()),
+			&txns,
+		)
+		.await;
+		assert!(matches!(results[0], Err(MovementBridgeError::RpcError(_))));
+		assert!(matches!(results[1], Err(MovementBridgeError::NotSubmitted)));
+		assert!(matches!(results[2], Err(MovementBridgeError::NotSubmitted)));
+		assert!(submit_aptos_transactions(&RestClient::new(url), &[]).await.is_empty());
+	}
+
+	/// A transaction in the shape the node returns it, with the given type and fields.
+	fn transaction(type_str: &str, success: bool, fields: serde_json::Value) -> AptosTransaction {
+		let hash = format!("0x{}", "00".repeat(32));
+		let vm_status = if success {
+			"Executed successfully"
+		} else {
+			"Move abort in 0xcafe::atomic_bridge_counterparty: 0x1"
+		};
+		let mut transaction = serde_json::json!({
+			"type": type_str,
+			"version": "10",
+			"hash": hash,
+			"state_change_hash": hash,
+			"event_root_hash": hash,
+			"state_checkpoint_hash": null,
+			"gas_used": "7",
+			"success": success,
+			"vm_status": vm_status,
+			"accumulator_root_hash": hash,
+			"changes": [],
+			"timestamp": "1000",
+		});
+		transaction.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+		serde_json::from_value(transaction).unwrap()
+	}
+
+	fn user_transaction(success: bool) -> AptosTransaction {
+		transaction(
+			"user_transaction",
+			success,
+			serde_json::json!({
+				"sender": "0x1",
+				"sequence_number": "0",
+				"max_gas_amount": "100",
+				"gas_unit_price": "1",
+				"expiration_timestamp_secs": "10",
+				"payload": {
+					"type": "entry_function_payload",
+					"function": "0xcafe::atomic_bridge_counterparty::lock_bridge_transfer",
+					"type_arguments": [],
+					"arguments": [],
+				},
+				"events": [],
+			}),
+		)
+	}
+
+	#[test]
+	fn test_proposed_sequence_number() {
+		let multisig_address = AccountAddress::from_hex_literal("0x5").unwrap();
+		let with_events = |events: Value| {
+			let mut transaction = serde_json::to_value(user_transaction(true)).unwrap();
+			transaction["events"] = events;
+			serde_json::from_value::<AptosTransaction>(transaction).unwrap()
+		};
+		let event = |account: &str, event_type: &str, data: Value| {
+			serde_json::json!({
+				"guid": { "creation_number": "4", "account_address": account },
+				"sequence_number": "0",
+				"type": event_type,
+				"data": data,
+			})
+		};
+		let withdraw =
+			event("0x1", "0x1::coin::WithdrawEvent", serde_json::json!({ "amount": "1" }));
+
+		let proposed = with_events(serde_json::json!([
+			withdraw,
+			event(
+				"0x6",
+				"0x1::multisig_account::CreateTransactionEvent",
+				serde_json::json!({ "creator": "0x1", "sequence_number": "9" })
+			),
+			event(
+				"0x5",
+				"0x1::multisig_account::CreateTransactionEvent",
+				serde_json::json!({ "creator": "0x1", "sequence_number": "3" })
+			),
+		]));
+		assert_eq!(proposed_sequence_number(&proposed, multisig_address).unwrap(), 3);
+
+		let module_event = with_events(serde_json::json!([event(
+			"0x0",
+			"0x1::multisig_account::CreateTransaction",
+			serde_json::json!({ "multisig_account": "0x5", "creator": "0x1", "sequence_number": "4" })
+		)]));
+		assert_eq!(proposed_sequence_number(&module_event, multisig_address).unwrap(), 4);
+
+		let unrelated = with_events(serde_json::json!([withdraw]));
+		assert!(proposed_sequence_number(&unrelated, multisig_address).is_err());
+	}
+
+	#[test]
+	fn test_confirm_user_transaction() {
+		assert!(confirm_user_transaction(user_transaction(true)).is_ok());
+		assert!(matches!(
+			confirm_user_transaction(user_transaction(false)),
+			Err(MovementBridgeError::AbortCode(0x1))
+		));
+		assert!(matches!(
+			confirm_user_transaction(transaction(
+				"state_checkpoint_transaction",
+				true,
+				serde_json::json!({})
+			)),
+			Err(MovementBridgeError::NotUserTransaction)
+		));
+	}
+
+	#[test]
+	fn test_movement_address_conversions() {
+		let account_address = AccountAddress::new([0xca; AccountAddress::LENGTH]);
+
+		let movement_address = MovementAddress::from(account_address);
+		assert_eq!(AccountAddress::from(movement_address.clone()), account_address);
+		assert_eq!(
+			MovementAddress::try_from(account_address.to_vec().as_slice()).unwrap(),
+			movement_address
+		);
+		assert_eq!(MovementAddress::try_from(account_address.to_vec()).unwrap(), movement_address);
+
+		assert!(matches!(
+			MovementAddress::try_from([0xca, 0xfe].as_slice()),
+			Err(MovementAddressError::InvalidByteLength)
+		));
+		assert!(matches!(
+			MovementAddress::try_from(vec![0xca; AccountAddress::LENGTH + 1]),
+			Err(MovementAddressError::InvalidByteLength)
+		));
+	}
+
+	#[test]
+	fn test_movement_address_serde() {
+		let address = MovementAddress(AccountAddress::new([0xca; AccountAddress::LENGTH]));
+		let literal = format!("0x{}", "ca".repeat(AccountAddress::LENGTH));
+		assert_eq!(address.to_string(), literal);
+		assert_eq!(serde_json::to_value(&address).unwrap(), Value::String(literal.clone()));
+		assert_eq!(
+			serde_json::from_value::<MovementAddress>(Value::String(literal)).unwrap(),
+			address
+		);
+		assert_eq!(
+			serde_json::from_str::<MovementAddress>(&format!(r#""{}""#, address.0.to_hex()))
+				.unwrap(),
+			address
+		);
+
+		let one = MovementAddress(AccountAddress::ONE);
+		assert_eq!(serde_json::to_string(&one).unwrap(), r#""0x1""#);
+		assert_eq!(serde_json::from_str::<MovementAddress>(r#""0x1""#).unwrap(), one);
+		assert!(serde_json::from_str::<MovementAddress>(r#""0xnope""#).is_err());
+
+		// binary formats keep the layout of the account address
+		assert_eq!(bcs::to_bytes(&address).unwrap(), bcs::to_bytes(&address.0).unwrap());
+		assert_eq!(
+			bcs::from_bytes::<MovementAddress>(&bcs::to_bytes(&address).unwrap()).unwrap(),
+			address
+		);
+	}
+
+	#[test]
+	fn test_decode_transfer_details() {
+		let bridge_transfer_id = BridgeTransferId([1; 32]);
+		let decode = |response| {
+			decode_transfer_details(bridge_transfer_id.clone(), response, MoveAmountType::U64)
+		};
+		// the body of a `POST /v1/view` of `bridge_transfers`, the eth originator as
+		// `vector<u8>`, the recipient `address`, u64 values as strings and the u8 state as number
+		let response: Value = serde_json::from_str(
+			r#"[
+				"0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+				"0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+				"100",
+				"0xc2e8bbb3d1df37b8a72a4d9b4b1fc0e9f0e5c8a07c6a7aa3d7bbf1b1d1c4e7b8",
+				"1727702400",
+				1
+			]"#,
+		)
+		.unwrap();
+		let details = decode(response).unwrap();
+		assert_eq!(details.bridge_transfer_id, bridge_transfer_id);
+		let originator = hex::decode("3c44cdddb6a900fa2b585dd299e03d12fa4293bc").unwrap();
+		assert_eq!(&details.initiator_address.0 .0[32 - originator.len()..], originator);
+		assert!(details.initiator_address.0 .0[..32 - originator.len()].iter().all(|b| *b == 0));
+		assert_eq!(
+			details.recipient_address.0,
+			MovementAddress::from_str(
+				"0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16"
+			)
+			.unwrap()
+			.0
+			.to_vec()
+		);
+		assert_eq!(details.amount.0, AssetType::Moveth(100));
+		assert_eq!(details.hash_lock.0[0], 0xc2);
+		assert_eq!(details.time_lock.0, 1727702400);
+		assert_eq!(details.state, 1);
+
+		// a Move struct with byte arrays and plain numbers decodes the same
+		let mut hash_lock = details.hash_lock.0.to_vec();
+		let response = serde_json::json!({
+			"originator": originator,
+			"recipient": "0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+			"amount": 100,
+			"hash_lock": hash_lock,
+			"time_lock": 1727702400,
+			"state": "1",
+		});
+		assert_eq!(decode(response).unwrap(), details);
+
+		hash_lock.pop();
+		let response = serde_json::json!({
+			"originator": "0x3c44",
+			"recipient": "0x1",
+			"amount": "100",
+			"hash_lock": hash_lock,
+			"time_lock": "0",
+			"state": 2,
+		});
+		assert_eq!(decode(response), Err(BridgeContractCounterpartyError::SerializationError));
+		assert_eq!(
+			decode(serde_json::json!(["0x1"])),
+			Err(BridgeContractCounterpartyError::InvalidResponseLength)
+		);
+	}
+
+	#[test]
+	fn test_gas_params_transaction_factory() {
+		let gas_params =
+			GasParams { max_gas_amount: 5_000, gas_unit_price: 150, expiration_secs: 60 };
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+		let raw_tx = gas_params
+			.transaction_factory(4)
+			.payload(make_aptos_payload(AccountAddress::ONE, "coin", "transfer", vec![], vec![]))
+			.sender(AccountAddress::ONE)
+			.sequence_number(0)
+			.build();
+		assert_eq!(raw_tx.max_gas_amount(), 5_000);
+		assert_eq!(raw_tx.gas_unit_price(), 150);
+		assert_eq!(raw_tx.chain_id(), ChainId::new(4));
+		assert!((now + 60..=now + 61).contains(&raw_tx.expiration_timestamp_secs()));
+		// relative to the ledger timestamp when submitting
+		assert_eq!(gas_params.expiration_timestamp_secs(1_000_500_000), 1_060);
+	}
+
+	#[test]
+	fn test_serialize_amount() {
+		let amount = Uint::<256, 4>::from(u64::MAX);
+		assert_eq!(
+			serialize_amount(amount, MoveAmountType::U64).unwrap(),
+			bcs::to_bytes(&u64::MAX).unwrap()
+		);
+		assert_eq!(
+			serialize_amount(amount, MoveAmountType::U128).unwrap(),
+			bcs::to_bytes(&(u64::MAX as u128)).unwrap()
+		);
+
+		let amount = amount + Uint::from(1);
+		assert!(matches!(
+			serialize_amount(amount, MoveAmountType::U64),
+			Err(MovementBridgeError::AmountOverflow { amount_type: MoveAmountType::U64, .. })
+		));
+		assert_eq!(serialize_amount(Uint::MAX, MoveAmountType::U256).unwrap(), vec![0xff; 32]);
+	}
+
+	#[test]
+	fn test_decimal_converter() {
+		let eth_to_move = DecimalConverter::new(18, 8);
+		let one_eth = Uint::<256, 4>::from(10u64).pow(Uint::from(18));
+		assert_eq!(eth_to_move.convert_value(one_eth), Ok(Uint::from(100_000_000u64)));
+		assert_eq!(eth_to_move.reversed().convert_value(Uint::from(100_000_000u64)), Ok(one_eth));
+		assert_eq!(
+			eth_to_move.convert(Amount(AssetType::Token(one_eth))),
+			Ok(Amount(AssetType::Token(Uint::from(100_000_000u64))))
+		);
+		assert_eq!(DecimalConverter::new(8, 8).convert_value(Uint::from(7)), Ok(Uint::from(7)));
+
+		// amounts that don't divide evenly are rejected, not truncated
+		assert_eq!(
+			eth_to_move.convert_value(one_eth + Uint::from(1)),
+			Err(DecimalConversionError::PrecisionLoss {
+				amount: one_eth + Uint::from(1),
+				remainder: Uint::from(1),
+				from: 18,
+				to: 8
+			})
+		);
+		assert!(matches!(
+			DecimalConverter::new(200, 0).convert_value(Uint::from(1)),
+			Err(DecimalConversionError::PrecisionLoss { .. })
+		));
+		assert_eq!(DecimalConverter::new(200, 0).convert_value(Uint::ZERO), Ok(Uint::ZERO));
+
+		// overflows of u256, and of u64 for the u64 asset types
+		assert!(matches!(
+			eth_to_move.reversed().convert_value(Uint::MAX),
+			Err(DecimalConversionError::Overflow { from: 8, to: 18, .. })
+		));
+		assert!(matches!(
+			eth_to_move.reversed().convert(Amount(AssetType::Moveth(u64::MAX))),
+			Err(DecimalConversionError::Overflow { .. })
+		));
+		assert_eq!(
+			eth_to_move.reversed().convert(Amount(AssetType::EthAndWeth((1, 2)))),
+			Ok(Amount(AssetType::EthAndWeth((10_000_000_000, 20_000_000_000))))
+		);
+	}
+
+	fn transaction_proof(state_checkpoint_hash: u8) -> TransactionInfoWithProof {
+		checkpoint_proof(HashValue::new([state_checkpoint_hash; 32]))
+	}
+
+	fn checkpoint_proof(state_root: HashValue) -> TransactionInfoWithProof {
+		let transaction_info = aptos_types::proof::TransactionInfo::new(
+			HashValue::zero(),
+			HashValue::zero(),
+			HashValue::zero(),
+			Some(state_root),
+			0,
+			ExecutionStatus::Success,
+		);
+		TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), transaction_info)
+	}
+
+	/// The proof of the only transaction of a ledger, whose info hash is the accumulator root.
+	fn single_transaction_proof() -> StateProofResponse {
+		ledger_proof(transaction_proof(1))
+	}
+
+	fn ledger_proof(tx_proof: TransactionInfoWithProof) -> StateProofResponse {
+		let root = tx_proof.transaction_info().hash();
+		let block_info = BlockInfo::new(1, 0, HashValue::zero(), root, 0, 0, None);
+		let state_proof = StateProof::new(
+			LedgerInfoWithSignatures::new(
+				LedgerInfo::new(block_info, HashValue::zero()),
+				AggregateSignature::empty(),
+			),
+			EpochChangeProof::new(vec![], false),
+		);
+		StateProofResponse::new(0, state_proof, tx_proof)
+	}
+
+	#[test]
+	fn test_verify_state_proof() {
+		let proof = single_transaction_proof();
+		verify_state_proof(&proof, 0).unwrap();
+		assert!(verify_state_proof(&proof, 1).is_err());
+
+		let tampered = StateProofResponse { tx_proof: transaction_proof(2), ..proof.clone() };
+		assert!(verify_state_proof(&tampered, 0).is_err());
+
+		let unknown = StateProofResponse { version: StateProofResponse::VERSION + 1, ..proof };
+		assert!(verify_state_proof(&unknown, 0).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_fetch_state_proof() {
+		let proof = single_transaction_proof();
+		let (url, request) = serve_once("200 OK", serde_json::to_string(&proof).unwrap()).await;
+		let fetched = fetch_state_proof(&reqwest::Client::new(), &url, 0).await.unwrap();
+		assert_eq!(fetched, proof);
+		assert!(request.await.unwrap().starts_with("GET /movement/v1/state-proof/0 "));
+
+		let tampered = StateProofResponse { tx_proof: transaction_proof(2), ..proof };
+		let (url, _) = serve_once("200 OK", serde_json::to_string(&tampered).unwrap()).await;
+		assert!(fetch_state_proof(&reqwest::Client::new(), &url, 0).await.is_err());
+
+		let (url, _) = serve_once("404 Not Found", r#"{"error":"not found"}"#).await;
+		assert!(fetch_state_proof(&reqwest::Client::new(), &url, 0).await.is_err());
+	}
+
+	#[test]
+	fn test_movement_rest_endpoint() {
+		for base in ["http://localhost:30731", "http://localhost:30731/"] {
+			let url = movement_rest_endpoint(&base.parse().unwrap(), "movement/v1/state-proof/1");
+			assert_eq!(url.unwrap().as_str(), "http://localhost:30731/movement/v1/state-proof/1");
+		}
+		for base in ["http://node.example.com/rest", "http://node.example.com/rest/"] {
+			let url = movement_rest_endpoint(&base.parse().unwrap(), "movement/v1/state-proof/1");
+			assert_eq!(
+				url.unwrap().as_str(),
+				"http://node.example.com/rest/movement/v1/state-proof/1"
+			);
+		}
+	}
+
+	fn transfer() -> MoveBridgeTransfer {
+		MoveBridgeTransfer {
+			originator: vec![0x3c; 20],
+			recipient: AccountAddress::from_hex_literal("0x2").unwrap(),
+			amount: 100,
+			hash_lock: vec![1; 32],
+			time_lock: 1727702400,
+			state: 1,
+		}
+	}
+
+	/// A `BridgeTransferStore` of a single bucket, in the JSON of the REST API.
+	fn transfer_store() -> Value {
+		serde_json::json!({
+			"transfers": {
+				"buckets": { "inner": { "handle": "0x7" }, "length": "1" },
+				"num_buckets": "1",
+				"level": 0,
+				"size": "1",
+				"split_load_threshold": 75,
+				"target_bucket_size": "1",
+			},
+		})
+	}
+
+	fn bucket(entries: &[(BridgeTransferId<[u8; 32]>, u64)]) -> StateValue {
+		let entries: Vec<_> = entries
+			.iter()
+			.map(|(id, hash)| SmartTableEntry {
+				hash: *hash,
+				key: id.0.to_vec(),
+				value: transfer(),
+			})
+			.collect();
+		StateValue::from(bcs::to_bytes(&entries).unwrap())
+	}
+
+	#[test]
+	fn test_bucket_index() {
+		assert_eq!(bucket_index(0, 1, 7), 0);
+		assert_eq!(bucket_index(1, 3, 6), 2);
+		// bucket 3 isn't split yet, its entries are in bucket 1
+		assert_eq!(bucket_index(1, 3, 7), 1);
+	}
+
+	#[test]
+	fn test_locate_and_prove_bridge_transfer() {
+		let bridge_transfer_id = BridgeTransferId([1; 32]);
+		let location =
+			locate_bridge_transfer(&transfer_store(), bridge_transfer_id.clone()).unwrap();
+		// the only bucket, keyed by its u64 index in the table of handle 0x7
+		let mut state_key = vec![1];
+		state_key.extend(AccountAddress::from_hex_literal("0x7").unwrap().to_vec());
+		state_key.extend([8, 0, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(bcs::to_bytes(&location.state_key).unwrap(), state_key);
+
+		let other_id = BridgeTransferId([2; 32]);
+		let other = locate_bridge_transfer(&transfer_store(), other_id.clone()).unwrap();
+		let prove = |value| {
+			proven_transfer_details(
+				bridge_transfer_id.clone(),
+				&location,
+				value,
+				MoveAmountType::U64,
+			)
+		};
+		let value =
+			bucket(&[(other_id.clone(), other.hash), (bridge_transfer_id.clone(), location.hash)]);
+		let details = prove(Some(&value)).unwrap().unwrap();
+		assert_eq!(details, transfer().into_details(bridge_transfer_id.clone()).unwrap());
+
+		let value = bucket(&[(other_id, other.hash)]);
+		let details = prove(Some(&value));
+		assert_eq!(details.unwrap(), None);
+		assert_eq!(prove(None).unwrap(), None);
+
+		let invalid = serde_json::json!({ "transfers": { "num_buckets": "1", "level": 0 } });
+		assert!(locate_bridge_transfer(&invalid, bridge_transfer_id).is_err());
+	}
+
+	/// A bucket holding only `transfer`, with the amount of its Move integer type.
+	fn bucket_of<N: Serialize>(
+		location: &TransferLocation,
+		bridge_transfer_id: &BridgeTransferId<[u8; 32]>,
+		amount: N,
+	) -> StateValue {
+		let MoveBridgeTransfer { originator, recipient, hash_lock, time_lock, state, .. } =
+			transfer();
+		let value =
+			MoveBridgeTransfer { originator, recipient, amount, hash_lock, time_lock, state };
+		let entries = vec![SmartTableEntry {
+			hash: location.hash,
+			key: bridge_transfer_id.0.to_vec(),
+			value,
+		}];
+		StateValue::from(bcs::to_bytes(&entries).unwrap())
+	}
+
+	#[test]
+	fn test_transfer_amounts_wider_than_u64() {
+		let bridge_transfer_id = BridgeTransferId([1; 32]);
+		let amount = Uint::<256, 4>::from(u64::MAX) + Uint::from(1);
+		let token = Amount(AssetType::Token(amount));
+
+		// the view response renders u128 and u256 values as strings
+		let response = serde_json::json!({
+			"originator": vec![0x3c; 20],
+			"recipient": "0x2",
+			"amount": amount.to_string(),
+			"hash_lock": vec![1; 32],
+			"time_lock": "1727702400",
+			"state": 1,
+		});
+		let decode = |amount_type| {
+			decode_transfer_details(bridge_transfer_id.clone(), response.clone(), amount_type)
+		};
+		assert_eq!(decode(MoveAmountType::U128).unwrap().amount, token);
+		assert_eq!(decode(MoveAmountType::U256).unwrap().amount, token);
+		assert_eq!(
+			decode(MoveAmountType::U64),
+			Err(BridgeContractCounterpartyError::SerializationError)
+		);
+
+		// the stored transfer encodes the amount as the Move integer type
+		let location =
+			locate_bridge_transfer(&transfer_store(), bridge_transfer_id.clone()).unwrap();
+		let prove = |bucket: StateValue, amount_type| {
+			proven_transfer_details(
+				bridge_transfer_id.clone(),
+				&location,
+				Some(&bucket),
+				amount_type,
+			)
+			.unwrap()
+			.unwrap()
+			.amount
+		};
+		let u128_bucket = bucket_of(&location, &bridge_transfer_id, u64::MAX as u128 + 1);
+		assert_eq!(prove(u128_bucket, MoveAmountType::U128), token);
+		let u256_bucket =
+			bucket_of(&location, &bridge_transfer_id, MoveU256(amount.to_le_bytes::<32>()));
+		assert_eq!(prove(u256_bucket, MoveAmountType::U256), token);
+
+		// amounts which fit in u64 keep coming back as Moveth
+		let u128_bucket = bucket_of(&location, &bridge_transfer_id, 100u128);
+		assert_eq!(prove(u128_bucket, MoveAmountType::U128), Amount(AssetType::Moveth(100)));
+	}
+
+	/// The proof of a bucket holding a transfer, as the only state value at version 0.
+	fn state_value_proof(state_key: StateKey, state_value: StateValue) -> StateValueProofResponse {
+		let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+		StateValueProofResponse {
+			state_key,
+			state_value: Some(state_value),
+			state_value_proof: SparseMerkleProof::new(Some(leaf.clone()), vec![]),
+			proof: ledger_proof(checkpoint_proof(leaf.hash())),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_fetch_state_value_proof() {
+		let bridge_transfer_id = BridgeTransferId([1; 32]);
+		let location = locate_bridge_transfer(&transfer_store(), bridge_transfer_id).unwrap();
+		let value = bucket(&[(bridge_transfer_id, location.hash)]);
+		let proof = state_value_proof(location.state_key.clone(), value);
+
+		let (url, request) = serve_once("200 OK", serde_json::to_string(&proof).unwrap()).await;
+		let url = url.join("rest").unwrap();
+		let fetched = fetch_state_value_proof(&reqwest::Client::new(), &url, 0, &location.state_key)
+			.await
+			.unwrap();
+		assert_eq!(fetched, proof);
+		let state_key = hex::encode(bcs::to_bytes(&location.state_key).unwrap());
+		let path = format!("GET /rest/movement/v1/state-value-proof/0?state_key={} ", state_key);
+		assert!(request.await.unwrap().starts_with(&path));
+
+		// another value than the proven one
+		let tampered = StateValueProofResponse {
+			state_value: Some(bucket(&[(BridgeTransferId([2; 32]), location.hash)])),
+			..proof.clone()
+		};
+		let (url, _) = serve_once("200 OK", serde_json::to_string(&tampered).unwrap()).await;
+		let fetched =
+			fetch_state_value_proof(&reqwest::Client::new(), &url, 0, &location.state_key).await;
+		assert!(fetched.is_err());
+
+		// the proof of another state key, a raw one
+		let raw_key: StateKey = bcs::from_bytes(&[2, 1, 0]).unwrap();
+		let (url, _) = serve_once("200 OK", serde_json::to_string(&proof).unwrap()).await;
+		assert!(fetch_state_value_proof(&reqwest::Client::new(), &url, 0, &raw_key).await.is_err());
+	}
+
+	#[test]
+	fn test_verify_committed_ledger_info() {
+		let proof = single_transaction_proof();
+		let ledger_info = proof.state_proof.latest_ledger_info();
+		let committed = |root: HashValue| {
+			transaction(
+				"state_checkpoint_transaction",
+				true,
+				serde_json::json!({ "accumulator_root_hash": root.to_hex_literal() }),
+			)
+		};
+		let root = ledger_info.transaction_accumulator_hash();
+		verify_committed_ledger_info(ledger_info, &committed(root)).unwrap();
+		assert!(verify_committed_ledger_info(ledger_info, &committed(HashValue::zero())).is_err());
+	}
 }
@@ -18,7 +18,7 @@ use ethereum_bridge::types::{AlloyProvider, AtomicBridgeInitiator, EthAddress, W
 use movement_bridge::{utils::MovementAddress, Config as MovementConfig};
 use movement_bridge::MovementClient;
 use rand::SeedableRng;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 pub mod utils;
 
@@ -88,11 +88,22 @@ impl TestHarness {
 		(Self { eth_client: None, movement_client: Some(movement_client) }, child)
 	}
 
+	/// Like [TestHarness::new_with_movement], but with an in-process node, so it doesn't need
+	/// the movement CLI. The Movement signer is funded, but there's no faucet client.
+	#[cfg(feature = "test-node")]
+	pub async fn new_with_test_node() -> (Self, movement_bridge::test_node::TestNode) {
+		let (movement_client, node) =
+			MovementClient::new_with_test_node(MovementConfig::build_for_test())
+				.await
+				.expect("Failed to start the test node");
+		(Self { eth_client: None, movement_client: Some(movement_client) }, node)
+	}
+
 	pub fn movement_rest_client(&self) -> &Client {
 		self.movement_client().expect("Could not fetch Movement client").rest_client()
 	}
 
-	pub fn movement_faucet_client(&self) -> &Arc<RwLock<FaucetClient>> {
+	pub fn movement_faucet_client(&self) -> &Arc<FaucetClient> {
 		self.movement_client()
 			.expect("Could not fetch Movement client")
 			.faucet_client()
@@ -73,12 +73,7 @@ pub async fn fund_and_check_balance(
     let movement_client_signer = movement_client.signer();
     let rest_client = movement_client.rest_client();
     let coin_client = CoinClient::new(&rest_client);
-    let faucet_client = movement_client
-            .faucet_client()
-            .expect("Failed to get FaucetClient")
-            .write()
-            .unwrap();
-    faucet_client
+    movement_client
             .fund(movement_client_signer.address(), expected_balance)
             .await?;
 
@@ -126,6 +121,7 @@ pub async fn initiate_bridge_transfer_helper(
         &movement_client.rest_client(),
         movement_client.signer(),
         mint_payload,
+        movement_client.gas_params(),
     )
     .await
     .map_err(|_| BridgeContractInitiatorError::MintError)?;
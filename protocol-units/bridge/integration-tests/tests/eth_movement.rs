@@ -6,11 +6,18 @@ use alloy::{
 	providers::Provider,
 };
 use anyhow::Result;
+use std::str::FromStr;
 
-use aptos_sdk::coin_client::CoinClient;
+use aptos_sdk::{
+	coin_client::CoinClient,
+	move_types::language_storage::TypeTag,
+	types::account_address::AccountAddress,
+};
 use bridge_integration_tests::{EthToMovementCallArgs, TestHarness};
 use bridge_shared::{
-	bridge_contracts::{BridgeContractCounterparty, BridgeContractInitiator},
+	bridge_contracts::{
+		BridgeContractCounterparty, BridgeContractCounterpartyError, BridgeContractInitiator,
+	},
 	types::{
 		Amount, AssetType, BridgeTransferId, HashLock, HashLockPreImage, InitiatorAddress,
 		RecipientAddress, TimeLock,
@@ -18,6 +25,7 @@ use bridge_shared::{
 };
 
 use ethereum_bridge::types::EthAddress;
+use movement_bridge::types::TransferState;
 
 use tokio::{
 	self
@@ -36,8 +44,6 @@ async fn test_movement_client_build_and_fund_accounts() -> Result<(), anyhow::Er
 	let faucet_client = movement_client.faucet_client().expect("Failed to get // FaucetClient");
 	let movement_client_signer = movement_client.signer();
 
-	let faucet_client = faucet_client.write().unwrap();
-
 	faucet_client
 	.fund(movement_client_signer.address(), 100_000_000)
 	.await?;
@@ -89,10 +95,7 @@ async fn test_movement_client_should_successfully_call_lock_and_complete(
 		let faucet_client = movement_client.faucet_client().expect("Failed to get FaucetClient");
 		let movement_client_signer = movement_client.signer();
 
-                {
-                        let faucet_client = faucet_client.write().unwrap();
-                        faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-                } 
+		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
 
 		let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 		assert!(
@@ -101,6 +104,17 @@ async fn test_movement_client_should_successfully_call_lock_and_complete(
 			balance
 		);
 
+		let aptos_coin = TypeTag::from_str("0x1::aptos_coin::AptosCoin")?;
+		assert_eq!(
+			movement_client
+				.coin_balance(movement_client_signer.address(), aptos_coin.clone())
+				.await?,
+			balance
+		);
+		// an account that was never funded has no coin store
+		let unfunded = AccountAddress::new([0xab; 32]);
+		assert_eq!(movement_client.coin_balance(unfunded, aptos_coin).await?, 0);
+
 		movement_client
 			.lock_bridge_transfer(
 				BridgeTransferId(args.bridge_transfer_id),
@@ -130,6 +144,14 @@ async fn test_movement_client_should_successfully_call_lock_and_complete(
                 assert_eq!(details.recipient_address.0, args.recipient.0.to_vec());
                 assert_eq!(details.amount.0, AssetType::Moveth(args.amount));
                 assert_eq!(details.state, 1, "Bridge transfer is supposed to be locked but it's not.");
+		assert_eq!(
+			movement_client.transfer_state(BridgeTransferId(args.bridge_transfer_id)).await?,
+			TransferState::Locked
+		);
+		assert_eq!(
+			movement_client.transfer_state(BridgeTransferId([0xab; 32])).await?,
+			TransferState::NotFound
+		);
 
 		BridgeContractCounterparty::complete_bridge_transfer(
 				movement_client,
@@ -155,6 +177,40 @@ async fn test_movement_client_should_successfully_call_lock_and_complete(
                 assert_eq!(details.recipient_address.0, args.recipient.0.to_vec());
                 assert_eq!(details.amount.0, AssetType::Moveth(args.amount));
                 assert_eq!(details.state, 2, "Bridge transfer is supposed to be completed but it's not.");
+		assert_eq!(
+			movement_client.transfer_state(BridgeTransferId(args.bridge_transfer_id)).await?,
+			TransferState::Completed
+		);
+		let completed = movement_client
+			.wait_for_transfer_state(
+				BridgeTransferId(args.bridge_transfer_id),
+				TransferState::Completed,
+				Duration::from_secs(10),
+			)
+			.await?
+			.expect("Expected the completed transfer details");
+		assert_eq!(completed.state, 2);
+		// completing again is caught before submitting
+		assert_eq!(
+			BridgeContractCounterparty::complete_bridge_transfer(
+				movement_client,
+				BridgeTransferId(args.bridge_transfer_id),
+				HashLockPreImage(b"secret".to_vec()),
+			)
+			.await,
+			Err(BridgeContractCounterpartyError::AlreadyCompleted)
+		);
+		// a transfer that is already completed never gets aborted
+		assert_eq!(
+			movement_client
+				.wait_for_transfer_state(
+					BridgeTransferId(args.bridge_transfer_id),
+					TransferState::Aborted,
+					Duration::from_secs(2),
+				)
+				.await,
+			Err(BridgeContractCounterpartyError::Timeout)
+		);
 
 		Ok(())
 	}
@@ -186,10 +242,7 @@ async fn test_movement_client_should_successfully_call_lock_and_abort() -> Resul
 		let faucet_client = movement_client.faucet_client().expect("Failed to get FaucetClient");
 		let movement_client_signer = movement_client.signer();
 
-		{
-			let faucet_client = faucet_client.write().unwrap();
-			faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-		}
+		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
 
 		let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 		assert!(
@@ -256,6 +309,68 @@ async fn test_movement_client_should_successfully_call_lock_and_abort() -> Resul
 			abort_details.state, 3,
 			"Bridge transfer is supposed to be cancelled but it's not."
 		);
+		assert_eq!(
+			movement_client.transfer_state(BridgeTransferId(args.bridge_transfer_id)).await?,
+			TransferState::Aborted
+		);
+
+		Ok(())
+	}
+	.await;
+
+	if let Err(e) = child.kill().await {
+		eprintln!("Failed to kill child process: {:?}", e);
+	}
+
+	test_result
+}
+
+#[tokio::test]
+async fn test_movement_client_should_lock_and_fetch_transfer_proof() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::DEBUG).try_init();
+
+	let (mut harness, mut child) = TestHarness::new_with_movement().await;
+
+	let args = EthToMovementCallArgs::default();
+
+	let test_result = async {
+		let movement_client = harness.movement_client_mut().expect("Failed to get MovementClient");
+		let _ = movement_client.publish_for_test();
+
+		let faucet_client = movement_client.faucet_client().expect("Failed to get FaucetClient");
+		let movement_client_signer = movement_client.signer();
+
+		faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
+
+		movement_client
+			.lock_bridge_transfer(
+				BridgeTransferId(args.bridge_transfer_id),
+				HashLock(args.hash_lock),
+				TimeLock(args.time_lock),
+				InitiatorAddress(args.initiator.clone()),
+				RecipientAddress(args.recipient.clone()),
+				Amount(AssetType::Moveth(args.amount)),
+			)
+			.await
+			.expect("Failed to lock bridge transfer");
+
+		let (details, proof) = movement_client
+			.get_transfer_with_state_value_proof(BridgeTransferId(args.bridge_transfer_id))
+			.await
+			.expect("Failed to get bridge transfer with proof");
+
+		// decoded from the proven table bucket, which the client verified
+		assert_eq!(details.bridge_transfer_id.0, args.bridge_transfer_id);
+		assert_eq!(details.hash_lock.0, args.hash_lock);
+		assert_eq!(details.time_lock.0, args.time_lock);
+		assert_eq!(details.amount.0, AssetType::Moveth(args.amount));
+		assert_eq!(details.state, 1, "Bridge transfer is supposed to be locked but it's not.");
+		assert!(proof.state_value.is_some());
+
+		// the bucket is proven in the state checkpoint ending a block, against the ledger info
+		let ledger_info = proof.proof.state_proof.latest_ledger_info();
+		proof.verify(ledger_info.transaction_accumulator_hash())?;
+		assert!(ledger_info.version() >= proof.proof.tx_index);
 
 		Ok(())
 	}
@@ -468,3 +583,62 @@ async fn test_eth_client_should_successfully_complete_transfer() {
 
 	//TODO: Here call complete with the id captured from the event
 }
+
+#[tokio::test]
+async fn test_movement_client_dry_run_lock_transfer() -> Result<(), anyhow::Error> {
+	let config =
+		movement_bridge::Config { dry_run: true, ..movement_bridge::Config::build_for_test() };
+	let mut movement_client = movement_bridge::MovementClient::new(config).await?;
+	let args = EthToMovementCallArgs::default();
+
+	// no node is running, so this only succeeds if nothing is submitted
+	movement_client
+		.lock_bridge_transfer(
+			BridgeTransferId(args.bridge_transfer_id),
+			HashLock(args.hash_lock),
+			TimeLock(args.time_lock),
+			InitiatorAddress(args.initiator.clone()),
+			RecipientAddress(args.recipient.clone()),
+			Amount(AssetType::Moveth(args.amount)),
+		)
+		.await
+		.expect("Failed to lock bridge transfer");
+
+	let payload = movement_client.last_payload().expect("Expected a dry-run payload");
+	let entry_function = match payload {
+		aptos_sdk::types::transaction::TransactionPayload::EntryFunction(entry_function) => {
+			entry_function
+		}
+		_ => panic!("Expected an entry function payload"),
+	};
+	assert_eq!(entry_function.function().as_str(), "lock_bridge_transfer");
+	assert_eq!(entry_function.args()[1], bcs::to_bytes(&args.bridge_transfer_id.to_vec())?);
+	assert_eq!(entry_function.args()[4], bcs::to_bytes(&args.amount)?);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_movement_client_dry_run_complete_batch() -> Result<(), anyhow::Error> {
+	let config =
+		movement_bridge::Config { dry_run: true, ..movement_bridge::Config::build_for_test() };
+	let mut movement_client = movement_bridge::MovementClient::new(config).await?;
+
+	let items = vec![
+		(BridgeTransferId([1; 32]), HashLockPreImage(b"first".to_vec())),
+		(BridgeTransferId([2; 32]), HashLockPreImage(b"second".to_vec())),
+	];
+	let results = movement_client.complete_bridge_transfers_batch(items).await;
+	assert_eq!(results.len(), 2);
+	assert!(results.iter().all(|result| result.is_ok()));
+
+	let expected = movement_client
+		.complete_bridge_transfer_payload(
+			BridgeTransferId([2; 32]),
+			HashLockPreImage(b"second".to_vec()),
+		)
+		.expect("Failed to build the complete payload");
+	assert_eq!(movement_client.last_payload(), Some(expected));
+
+	Ok(())
+}
@@ -1,6 +1,7 @@
 use tokio::time::{sleep, Duration}; // Add these imports
 
 use anyhow::Result;
+use aptos_sdk::types::{account_address::AccountAddress, LocalAccount};
 use bridge_integration_tests::TestHarness;
 use bridge_integration_tests::{
 	utils::{self as test_utils},
@@ -13,6 +14,8 @@ use bridge_shared::{
 	},
 };
 
+use movement_bridge::utils as movement_utils;
+use serde_json::json;
 use tokio::{self};
 
 use tracing::info;
@@ -243,3 +246,97 @@ async fn test_movement_client_refund_transfer() -> Result<(), anyhow::Error> {
 
 	test_result
 }
+
+#[tokio::test]
+async fn test_movement_client_propose_and_approve_multisig() -> Result<(), anyhow::Error> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::DEBUG).try_init();
+
+	let (mut harness, mut child) = TestHarness::new_with_movement().await;
+
+	let test_result = async {
+		let mut movement_client =
+			harness.movement_client_mut().expect("Failed to get MovementClient");
+		test_utils::fund_and_check_balance(&mut movement_client, 100_000_000_000).await?;
+
+		let second_owner = LocalAccount::generate(&mut rand::rngs::OsRng);
+		movement_client.fund(second_owner.address(), 100_000_000_000).await?;
+
+		let creator = movement_client.signer().address();
+		let multisig_address = movement_client
+			.view_function(
+				AccountAddress::ONE,
+				movement_utils::MULTISIG_MODULE_NAME,
+				"get_next_multisig_account_address",
+				vec![],
+				vec![bcs::to_bytes(&creator)?],
+				None,
+			)
+			.await?;
+		let multisig_address = AccountAddress::from_hex_literal(
+			multisig_address.first().and_then(|v| v.as_str()).expect("Expected an address"),
+		)?;
+
+		// Create a 2-of-2 multisig owned by the client signer and the second owner.
+		let create_payload = movement_utils::make_aptos_payload(
+			AccountAddress::ONE,
+			"multisig_account",
+			"create_with_owners",
+			vec![],
+			vec![
+				bcs::to_bytes(&vec![second_owner.address()])?,
+				bcs::to_bytes(&2u64)?,
+				bcs::to_bytes(&Vec::<String>::new())?,
+				bcs::to_bytes(&Vec::<Vec<u8>>::new())?,
+			],
+		);
+		movement_utils::send_and_confirm_aptos_transaction(
+			movement_client.rest_client(),
+			movement_client.signer(),
+			create_payload,
+			movement_client.gas_params(),
+		)
+		.await
+		.map_err(|e| anyhow::anyhow!(e))?;
+
+		let call = movement_utils::make_aptos_payload(
+			AccountAddress::ONE,
+			"aptos_account",
+			"transfer",
+			vec![],
+			vec![bcs::to_bytes(&creator)?, bcs::to_bytes(&1u64)?],
+		);
+		let sequence_number = movement_client.propose_multisig(multisig_address, call).await?;
+		assert_eq!(sequence_number, 1);
+
+		movement_client.approve_multisig(multisig_address, sequence_number).await?;
+		movement_utils::send_and_confirm_aptos_transaction(
+			movement_client.rest_client(),
+			&second_owner,
+			movement_utils::make_multisig_approve_payload(multisig_address, sequence_number)?,
+			movement_client.gas_params(),
+		)
+		.await
+		.map_err(|e| anyhow::anyhow!(e))?;
+
+		let can_be_executed = movement_client
+			.view_function(
+				AccountAddress::ONE,
+				movement_utils::MULTISIG_MODULE_NAME,
+				"can_be_executed",
+				vec![],
+				vec![bcs::to_bytes(&multisig_address)?, bcs::to_bytes(&sequence_number)?],
+				None,
+			)
+			.await?;
+		assert_eq!(can_be_executed.first(), Some(&json!(true)));
+
+		Ok(())
+	}
+	.await;
+
+	if let Err(e) = child.kill().await {
+		eprintln!("Failed to kill child process: {:?}", e);
+	}
+
+	test_result
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "test-node")]
+
+use anyhow::Result;
+use aptos_sdk::move_types::language_storage::TypeTag;
+use bridge_integration_tests::{EthToMovementCallArgs, TestHarness};
+use bridge_shared::bridge_contracts::BridgeContractCounterparty;
+use bridge_shared::types::{
+	Amount, AssetType, BridgeTransferId, HashLock, InitiatorAddress, RecipientAddress, TimeLock,
+};
+use movement_bridge::error::MovementBridgeError;
+use movement_bridge::test_node::BRIDGE_MODULES_DIR;
+use movement_bridge::types::TransferState;
+use std::str::FromStr;
+
+#[tokio::test]
+async fn test_test_node_publishes_bridge_modules() -> Result<()> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::DEBUG).try_init();
+	let (mut harness, node) = TestHarness::new_with_test_node().await;
+	let movement_client = harness.movement_client_mut()?;
+
+	let aptos_coin = TypeTag::from_str("0x1::aptos_coin::AptosCoin")?;
+	let balance = movement_client
+		.coin_balance(movement_client.signer().address(), aptos_coin.clone())
+		.await?;
+	assert!(balance > 0);
+
+	let resource_address = node.publish_bridge_modules(movement_client, BRIDGE_MODULES_DIR).await?;
+	assert_eq!(movement_client.counterparty_address, resource_address);
+
+	// the counterparty module answers for unknown transfers
+	assert_eq!(
+		movement_client.transfer_state(BridgeTransferId([9; 32])).await?,
+		TransferState::NotFound
+	);
+
+	// the counterparty module locks its own asset, so it takes no coin type
+	movement_client.check_lock_coin_type(None).await?;
+	assert!(matches!(
+		movement_client.check_lock_coin_type(Some(&aptos_coin)).await,
+		Err(MovementBridgeError::CoinTypeUnsupported { .. })
+	));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_test_node_proves_locked_transfer() -> Result<()> {
+	let _ = tracing_subscriber::fmt().with_max_level(tracing::Level::DEBUG).try_init();
+	let (mut harness, node) = TestHarness::new_with_test_node().await;
+	let movement_client = harness.movement_client_mut()?;
+	node.publish_bridge_modules(movement_client, BRIDGE_MODULES_DIR).await?;
+
+	let args = EthToMovementCallArgs::default();
+	movement_client
+		.lock_bridge_transfer(
+			BridgeTransferId(args.bridge_transfer_id),
+			HashLock(args.hash_lock),
+			TimeLock(args.time_lock),
+			InitiatorAddress(args.initiator.clone()),
+			RecipientAddress(args.recipient.clone()),
+			Amount(AssetType::Moveth(args.amount)),
+		)
+		.await?;
+
+	let (details, proof) = movement_client
+		.get_transfer_with_state_value_proof(BridgeTransferId(args.bridge_transfer_id))
+		.await?;
+	assert_eq!(details.bridge_transfer_id.0, args.bridge_transfer_id);
+	assert_eq!(details.hash_lock.0, args.hash_lock);
+	assert_eq!(details.recipient_address, RecipientAddress(args.recipient.0.to_vec()));
+	assert_eq!(details.amount, Amount(AssetType::Moveth(args.amount)));
+	assert_eq!(TransferState::from_state(details.state), Some(TransferState::Locked));
+
+	// the proof holds on its own, against the ledger info it carries
+	let ledger_info = proof.proof.state_proof.latest_ledger_info();
+	proof.verify(ledger_info.transaction_accumulator_hash())?;
+	assert!(proof.state_value.is_some());
+
+	// unknown transfers aren't proven
+	assert!(movement_client
+		.get_transfer_with_state_value_proof(BridgeTransferId([9; 32]))
+		.await
+		.is_err());
+
+	Ok(())
+}
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use thiserror::Error;
 
 use crate::types::{
@@ -29,6 +30,8 @@ pub enum BridgeContractInitiatorError {
 	InitiatorAddressNotSet,
 	#[error("Failed to convert")]
 	ConversionError,
+	#[error("Request timed out")]
+	Timeout,
 	#[error("Generic error: {0}")]
 	GenericError(String),
 }
@@ -57,6 +60,8 @@ pub enum BridgeContractCounterpartyError {
 	LockTransferError,
 	#[error("Failed to complete bridge transfer")]
 	CompleteTransferError,
+	#[error("Bridge transfer already completed")]
+	AlreadyCompleted,
 	#[error("Failed to abort bridge transfer")]
 	AbortTransferError,
 	#[error("Counterparty address not set")]
@@ -65,6 +70,10 @@ pub enum BridgeContractCounterpartyError {
 	SignerError,
 	#[error("Failed to convert")]
 	ConversionError,
+	#[error("Request timed out")]
+	Timeout,
+	#[error("Chain error: {0}")]
+	ChainError(#[source] ChainError),
 	#[error("Generic error: {0}")]
 	GenericError(String),
 }
@@ -75,6 +84,32 @@ impl BridgeContractCounterpartyError {
 	}
 }
 
+/// A chain specific error kept as the source of a shared bridge error.
+///
+/// Callers can branch on the concrete error with [ChainError::downcast_ref].
+/// Errors compare equal by their message.
+#[derive(Error, Debug, Clone)]
+#[error(transparent)]
+pub struct ChainError(Arc<dyn std::error::Error + Send + Sync>);
+
+impl ChainError {
+	pub fn new<E: std::error::Error + Send + Sync + 'static>(e: E) -> Self {
+		Self(Arc::new(e))
+	}
+
+	pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+		self.0.downcast_ref()
+	}
+}
+
+impl PartialEq for ChainError {
+	fn eq(&self, other: &Self) -> bool {
+		self.to_string() == other.to_string()
+	}
+}
+
+impl Eq for ChainError {}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BridgeContractWETH9Error {
 	#[error("Insufficient balance")]
@@ -1,7 +1,7 @@
 use crate::types::{
-	Amount, AssetType, BridgeAddressType, BridgeHashType, BridgeTransferId,
-	CounterpartyCompletedDetails, GenUniqueHash, HashLock, HashLockPreImage, InitiatorAddress,
-	LockDetails, RecipientAddress, TimeLock,
+	Amount, BridgeAddressType, BridgeHashType, BridgeTransferId, CounterpartyCompletedDetails,
+	GenUniqueHash, HashLock, HashLockPreImage, InitiatorAddress, LockDetails, RecipientAddress,
+	TimeLock,
 };
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -22,6 +22,10 @@ pub enum SmartContractCounterpartyError {
 	TransferNotFound,
 	#[error("Invalid hash lock pre image (secret)")]
 	InvalidHashLockPreImage,
+	#[error("Recipient balance overflows")]
+	BalanceOverflow,
+	#[error("Transfer asset differs from the recipient balance")]
+	AssetMismatch,
 }
 
 #[derive(Debug)]
@@ -96,7 +100,7 @@ where
 	) -> SCCResult<A, H> {
 		let transfer = self
 			.locked_transfers
-			.remove(bridge_transfer_id)
+			.get(bridge_transfer_id)
 			.ok_or(SmartContractCounterpartyError::TransferNotFound)?;
 
 		tracing::trace!("SmartContractCounterparty: Completing bridge transfer: {:?}", transfer);
@@ -114,12 +118,116 @@ where
 		// TODO: fix this
 		let account = A::from(transfer.recipient_address.clone());
 
-		let balance = accounts.entry(account).or_insert(Amount(AssetType::EthAndWeth((0, 0))));
-		todo!();
-		// balance += **transfer.amount;
+		let balance = accounts.get(&account).copied().unwrap_or(Amount(transfer.amount.zeroed()));
+		if !balance.is_same_asset(&transfer.amount) {
+			return Err(SmartContractCounterpartyError::AssetMismatch);
+		}
+		let balance = balance
+			.checked_add(transfer.amount)
+			.ok_or(SmartContractCounterpartyError::BalanceOverflow)?;
+
+		// the transfer stays locked until every check has passed
+		accounts.insert(account, balance);
+		let transfer = self
+			.locked_transfers
+			.remove(bridge_transfer_id)
+			.ok_or(SmartContractCounterpartyError::TransferNotFound)?;
 
 		Ok(SmartContractCounterpartyEvent::CompletedBridgeTransfer(
 			CounterpartyCompletedDetails::from_lock_details(transfer, pre_image),
 		))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::AssetType;
+	use rand::Rng;
+
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	struct TestAddress(Vec<u8>);
+
+	impl From<Vec<u8>> for TestAddress {
+		fn from(value: Vec<u8>) -> Self {
+			Self(value)
+		}
+	}
+
+	impl From<RecipientAddress<TestAddress>> for TestAddress {
+		fn from(RecipientAddress(address): RecipientAddress<TestAddress>) -> Self {
+			address
+		}
+	}
+
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	struct TestHash(Vec<u8>);
+
+	impl From<HashLockPreImage> for TestHash {
+		fn from(value: HashLockPreImage) -> Self {
+			Self(value.0)
+		}
+	}
+
+	impl GenUniqueHash for TestHash {
+		fn gen_unique_hash<R: Rng>(rng: &mut R) -> Self {
+			Self(rng.gen::<[u8; 8]>().to_vec())
+		}
+	}
+
+	#[test]
+	fn test_complete_bridge_transfer_asset_mismatch() {
+		let mut contract = SmartContractCounterparty::<TestAddress, TestHash>::new();
+		let recipient = TestAddress(b"recipient".to_vec());
+		let balance = Amount(AssetType::EthAndWeth((1000, 0)));
+		let mut accounts = HashMap::from([(recipient.clone(), balance)]);
+
+		let bridge_transfer_id = BridgeTransferId(TestHash(b"transfer_id".to_vec()));
+		let pre_image = HashLockPreImage(b"secret".to_vec());
+		contract
+			.lock_bridge_transfer(
+				bridge_transfer_id.clone(),
+				HashLock(TestHash::from(pre_image.clone())),
+				TimeLock(100),
+				InitiatorAddress(Vec::new()),
+				RecipientAddress(recipient.clone()),
+				Amount(AssetType::Moveth(10)),
+			)
+			.expect("lock_bridge_transfer failed");
+
+		assert_eq!(
+			contract.complete_bridge_transfer(&mut accounts, &bridge_transfer_id, pre_image),
+			Err(SmartContractCounterpartyError::AssetMismatch)
+		);
+		assert_eq!(accounts.get(&recipient), Some(&balance));
+		// a failed completion keeps the transfer locked
+		assert!(contract.locked_transfers.contains_key(&bridge_transfer_id));
+	}
+
+	#[test]
+	fn test_complete_bridge_transfer_to_new_account() {
+		let mut contract = SmartContractCounterparty::<TestAddress, TestHash>::new();
+		let recipient = TestAddress(b"recipient".to_vec());
+		let mut accounts = HashMap::new();
+
+		let bridge_transfer_id = BridgeTransferId(TestHash(b"transfer_id".to_vec()));
+		let pre_image = HashLockPreImage(b"secret".to_vec());
+		contract
+			.lock_bridge_transfer(
+				bridge_transfer_id.clone(),
+				HashLock(TestHash::from(pre_image.clone())),
+				TimeLock(100),
+				InitiatorAddress(Vec::new()),
+				RecipientAddress(recipient.clone()),
+				Amount(AssetType::Moveth(10)),
+			)
+			.expect("lock_bridge_transfer failed");
+
+		assert!(matches!(
+			contract.complete_bridge_transfer(&mut accounts, &bridge_transfer_id, pre_image),
+			Ok(SmartContractCounterpartyEvent::CompletedBridgeTransfer(_))
+		));
+		assert_eq!(accounts.get(&recipient), Some(&Amount(AssetType::Moveth(10))));
+		assert!(contract.locked_transfers.is_empty());
+	}
+}
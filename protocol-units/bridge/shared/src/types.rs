@@ -134,6 +134,18 @@ impl HashLockPreImage {
 #[derive(Deref, Debug, Clone, PartialEq, Eq)]
 pub struct TimeLock(pub u64);
 
+impl TimeLock {
+	/// Whether the time lock elapsed at `now`, in seconds since the unix epoch like the time lock.
+	pub fn is_expired(&self, now: u64) -> bool {
+		now >= self.0
+	}
+
+	/// The seconds left at `now` before the time lock elapses, zero once it did.
+	pub fn remaining(&self, now: u64) -> u64 {
+		self.0.saturating_sub(now)
+	}
+}
+
 impl From<Uint<256, 4>> for TimeLock {
 	fn from(value: Uint<256, 4>) -> Self {
 		// Extract the lower 64 bits.
@@ -150,6 +162,8 @@ pub enum AssetType {
 	/// Where the first tuple value is `Eth` and the second tuple value is `Weth`  
 	EthAndWeth((u64, u64)),
 	Moveth(u64),
+	/// A token amount which may exceed u64, e.g., of an 18 decimal ERC-20 token.
+	Token(Uint<256, 4>),
 }
 
 #[derive(Error, Debug)]
@@ -169,25 +183,80 @@ impl TryFrom<AssetType> for Uint<256, 4> {
 				Ok(Uint::from(combined_value))
 			}
 			AssetType::Moveth(value) => Ok(Uint::from(value as u128)),
+			AssetType::Token(value) => Ok(value),
 			_ => Err(ConversionError::InvalidConversion), // Add more cases as needed
 		}
 	}
 }
 
+impl AssetType {
+	/// A zero amount of the same asset, e.g., the starting balance of a new account.
+	pub fn zeroed(&self) -> Self {
+		match self {
+			AssetType::EthAndWeth(_) => AssetType::EthAndWeth((0, 0)),
+			AssetType::Moveth(_) => AssetType::Moveth(0),
+			AssetType::Token(_) => AssetType::Token(Uint::ZERO),
+		}
+	}
+
+	/// Whether both are amounts of the same asset, regardless of their values.
+	pub fn is_same_asset(&self, other: &Self) -> bool {
+		std::mem::discriminant(self) == std::mem::discriminant(other)
+	}
+
+	/// Adds amounts of the same asset, `None` on overflow or if the assets differ.
+	pub fn checked_add(self, other: Self) -> Option<Self> {
+		match (self, other) {
+			(AssetType::EthAndWeth((a, b)), AssetType::EthAndWeth((c, d))) => {
+				Some(AssetType::EthAndWeth((a.checked_add(c)?, b.checked_add(d)?)))
+			}
+			(AssetType::Moveth(a), AssetType::Moveth(b)) => {
+				Some(AssetType::Moveth(a.checked_add(b)?))
+			}
+			(AssetType::Token(a), AssetType::Token(b)) => Some(AssetType::Token(a.checked_add(b)?)),
+			_ => None,
+		}
+	}
+
+	/// Subtracts amounts of the same asset, `None` on underflow or if the assets differ.
+	pub fn checked_sub(self, other: Self) -> Option<Self> {
+		match (self, other) {
+			(AssetType::EthAndWeth((a, b)), AssetType::EthAndWeth((c, d))) => {
+				Some(AssetType::EthAndWeth((a.checked_sub(c)?, b.checked_sub(d)?)))
+			}
+			(AssetType::Moveth(a), AssetType::Moveth(b)) => {
+				Some(AssetType::Moveth(a.checked_sub(b)?))
+			}
+			(AssetType::Token(a), AssetType::Token(b)) => Some(AssetType::Token(a.checked_sub(b)?)),
+			_ => None,
+		}
+	}
+}
+
+/// Saturates on overflow and ignores other assets, use [AssetType::checked_add] to detect either.
 impl AddAssign for AssetType {
 	fn add_assign(&mut self, other: Self) {
 		match (self, other) {
-			(AssetType::Moveth(ref mut a), AssetType::Moveth(b)) => *a += b,
+			(AssetType::Moveth(ref mut a), AssetType::Moveth(b)) => *a = a.saturating_add(b),
 			(AssetType::EthAndWeth((ref mut a, ref mut b)), AssetType::EthAndWeth((c, d))) => {
-				*a += c;
-				*b += d;
+				*a = a.saturating_add(c);
+				*b = b.saturating_add(d);
 			}
+			(AssetType::Token(ref mut a), AssetType::Token(b)) => *a = a.saturating_add(b),
 			_ => (),
 		}
 	}
 }
 
 impl Amount {
+	pub fn checked_add(self, other: Amount) -> Option<Amount> {
+		self.0.checked_add(other.0).map(Amount)
+	}
+
+	pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+		self.0.checked_sub(other.0).map(Amount)
+	}
+
 	pub fn weth(&self) -> u64 {
 		match self.0 {
 			AssetType::EthAndWeth((_, weth_value)) => weth_value,
@@ -206,19 +275,23 @@ impl Amount {
 			_ => 0,
 		}
 	}
+	/// Amounts exceeding u64 saturate.
 	pub fn value(&self) -> u64 {
 		match self.0 {
-			AssetType::EthAndWeth((weth_value, eth_value)) => weth_value + eth_value,
+			AssetType::EthAndWeth((weth_value, eth_value)) => weth_value.saturating_add(eth_value),
 			AssetType::Moveth(value) => value,
+			AssetType::Token(value) => value.try_into().unwrap_or(u64::MAX),
 		}
 	}
 }
 
 impl From<Uint<256, 4>> for Amount {
 	fn from(value: Uint<256, 4>) -> Self {
-		// Extract the lower 64 bits.
-		let lower_64_bits = value.as_limbs()[0];
-		Amount(AssetType::EthAndWeth((0, lower_64_bits)))
+		// amounts beyond u64 are kept whole rather than truncated
+		match u64::try_from(value) {
+			Ok(value) => Amount(AssetType::EthAndWeth((0, value))),
+			Err(_) => Amount(AssetType::Token(value)),
+		}
 	}
 }
 
@@ -321,3 +394,61 @@ impl<T> BridgeAddressType for T where
 pub trait GenUniqueHash {
 	fn gen_unique_hash<R: Rng>(rng: &mut R) -> Self;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_time_lock_expiry() {
+		let time_lock = TimeLock(1_000);
+		assert!(!time_lock.is_expired(999));
+		assert_eq!(time_lock.remaining(999), 1);
+		assert!(time_lock.is_expired(1_000));
+		assert_eq!(time_lock.remaining(1_000), 0);
+		assert_eq!(time_lock.remaining(5_000), 0);
+	}
+
+	#[test]
+	fn test_amount_checked_arithmetic() {
+		let max = Amount(AssetType::EthAndWeth((u64::MAX - 1, 0)));
+		assert_eq!(
+			max.checked_add(Amount(AssetType::EthAndWeth((1, 2)))),
+			Some(Amount(AssetType::EthAndWeth((u64::MAX, 2))))
+		);
+		assert_eq!(max.checked_add(Amount(AssetType::EthAndWeth((2, 0)))), None);
+		assert_eq!(
+			max.checked_sub(Amount(AssetType::EthAndWeth((u64::MAX - 1, 0)))),
+			Some(Amount(AssetType::EthAndWeth((0, 0))))
+		);
+		assert_eq!(max.checked_sub(Amount(AssetType::EthAndWeth((0, 1)))), None);
+
+		let moveth = Amount(AssetType::Moveth(u64::MAX));
+		assert_eq!(moveth.checked_add(Amount(AssetType::Moveth(1))), None);
+		assert_eq!(
+			moveth.checked_sub(Amount(AssetType::Moveth(1))),
+			Some(Amount(AssetType::Moveth(u64::MAX - 1)))
+		);
+		// different assets don't combine
+		assert!(!moveth.is_same_asset(&AssetType::EthAndWeth((1, 0))));
+		assert_eq!(moveth.checked_sub(Amount(AssetType::EthAndWeth((1, 0)))), None);
+
+		let token = Amount(AssetType::Token(Uint::MAX));
+		assert_eq!(token.checked_add(Amount(AssetType::Token(Uint::from(1)))), None);
+		assert_eq!(
+			Amount(AssetType::Token(Uint::from(u64::MAX)))
+				.checked_add(Amount(AssetType::Token(Uint::from(1)))),
+			Some(Amount(AssetType::Token(Uint::from(u64::MAX as u128 + 1))))
+		);
+	}
+
+	#[test]
+	fn test_amount_saturates() {
+		assert_eq!(Amount(AssetType::EthAndWeth((u64::MAX, 1))).value(), u64::MAX);
+		assert_eq!(Amount(AssetType::Token(Uint::MAX)).value(), u64::MAX);
+
+		let mut amount = AssetType::Moveth(u64::MAX - 1);
+		amount += AssetType::Moveth(2);
+		assert_eq!(amount, AssetType::Moveth(u64::MAX));
+	}
+}
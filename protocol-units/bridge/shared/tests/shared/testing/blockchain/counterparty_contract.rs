@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
 use bridge_shared::types::{
-	Amount, AssetType, BridgeAddressType, BridgeHashType, BridgeTransferId,
-	CounterpartyCompletedDetails, GenUniqueHash, HashLock, HashLockPreImage, InitiatorAddress,
-	LockDetails, RecipientAddress, TimeLock,
+	Amount, BridgeAddressType, BridgeHashType, BridgeTransferId, CounterpartyCompletedDetails,
+	GenUniqueHash, HashLock, HashLockPreImage, InitiatorAddress, LockDetails, RecipientAddress,
+	TimeLock,
 };
 use thiserror::Error;
 
@@ -19,6 +19,10 @@ pub enum SmartContractCounterpartyError {
 	TransferNotFound,
 	#[error("Invalid hash lock pre image (secret)")]
 	InvalidHashLockPreImage,
+	#[error("Recipient balance overflows")]
+	BalanceOverflow,
+	#[error("Transfer asset differs from the recipient balance")]
+	AssetMismatch,
 }
 
 #[derive(Debug)]
@@ -96,7 +100,7 @@ where
 	) -> SCCResult<A, H> {
 		let transfer = self
 			.locked_transfers
-			.remove(bridge_transfer_id)
+			.get(bridge_transfer_id)
 			.ok_or(SmartContractCounterpartyError::TransferNotFound)?;
 
 		tracing::trace!("SmartContractCounterparty: Completing bridge transfer: {:?}", transfer);
@@ -113,8 +117,20 @@ where
 
 		// TODO: fix this
 		let account = A::from(transfer.recipient_address.clone());
-		let balance = accounts.entry(account).or_insert(Amount(AssetType::EthAndWeth((0, 0))));
-		**balance += *transfer.amount;
+		let balance = accounts.get(&account).copied().unwrap_or(Amount(transfer.amount.zeroed()));
+		if !balance.is_same_asset(&transfer.amount) {
+			return Err(SmartContractCounterpartyError::AssetMismatch);
+		}
+		let balance = balance
+			.checked_add(transfer.amount)
+			.ok_or(SmartContractCounterpartyError::BalanceOverflow)?;
+
+		// the transfer stays locked until every check has passed
+		accounts.insert(account, balance);
+		let transfer = self
+			.locked_transfers
+			.remove(bridge_transfer_id)
+			.ok_or(SmartContractCounterpartyError::TransferNotFound)?;
 
 		Ok(SmartContractCounterpartyEvent::CompletedBridgeTransfer(
 			CounterpartyCompletedDetails::from_lock_details(transfer, pre_image),
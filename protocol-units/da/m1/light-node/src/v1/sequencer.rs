@@ -3,7 +3,7 @@ use std::{
 	time::Duration,
 };
 use tokio_stream::Stream;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use celestia_rpc::HeaderClient;
 use m1_da_light_node_grpc::light_node_service_server::LightNodeService;
@@ -12,9 +12,10 @@ use std::{fmt::Debug, path::PathBuf};
 // FIXME: glob imports are bad style
 use m1_da_light_node_grpc::*;
 use memseq::{Sequencer, Transaction};
+use movement_algs::backoff::Backoff;
 use movement_algs::grouping_heuristic::{
 	apply::ToApply, binpacking::FirstFitBinpacking, drop_success::DropSuccess, skip::SkipFor,
-	splitting::Splitting, GroupingHeuristicStack, GroupingOutcome,
+	splitting::Splitting, GroupingHeuristicStack, GroupingOutcome, MaxIterationsExceeded,
 };
 use movement_types::block::Block;
 use std::boxed::Box;
@@ -27,6 +28,10 @@ use crate::v1::{passthrough::LightNodeV1 as LightNodeV1PassThrough, LightNodeV1O
 
 const LOGGING_UID: AtomicU64 = AtomicU64::new(0);
 
+/// How often [LightNodeV1::submit_with_heuristic] regroups and resubmits blocks before it
+/// backs off and starts over with the blocks left.
+const SUBMIT_MAX_ITERATIONS: usize = 16;
+
 #[derive(Clone)]
 pub struct LightNodeV1 {
 	pub pass_through: LightNodeV1PassThrough,
@@ -128,42 +133,67 @@ impl LightNodeV1 {
 		})
 		.await??;
 
-		let mut heuristic: GroupingHeuristicStack<block::WrappedBlock> =
-			GroupingHeuristicStack::new(vec![
-				DropSuccess::boxed(),
-				ToApply::boxed(),
-				SkipFor::boxed(1, Splitting::boxed(2)),
-				FirstFitBinpacking::boxed(1_700_000),
-			]);
-
-		let start_distribution = GroupingOutcome::new_apply_distribution(blocks);
-		let block_group_results = heuristic
-			.run_async_sequential_with_metadata(
-				start_distribution,
-				|index, grouping, mut flag| async move {
-					if index == 0 {
-						flag = false;
-					}
-
-					// if the flag is set then we are going to change this grouping outcome to failures and not run anything
-					if flag {
-						return Ok((grouping.to_failures_prefer_instrumental(), flag));
-					}
-
-					let blocks = grouping.into_original();
-					let outcome = match self.submit_blocks(&blocks).await {
-						Ok(_) => GroupingOutcome::new_all_success(blocks.len()),
-						Err(_) => {
-							flag = true;
-							GroupingOutcome::new_apply(blocks)
+		// a sustained DA outage exceeds the iterations, the blocks left are retried after a backoff
+		// instead of failing the publisher and dropping them
+		let mut backoff = Backoff::default();
+		let mut distribution = GroupingOutcome::new_apply_distribution(blocks);
+		let block_group_results = loop {
+			let mut heuristic: GroupingHeuristicStack<block::WrappedBlock> =
+				GroupingHeuristicStack::new(vec![
+					DropSuccess::boxed(),
+					ToApply::boxed(),
+					SkipFor::boxed(1, Splitting::boxed(2)),
+					FirstFitBinpacking::boxed(1_700_000),
+				]);
+
+			let result = heuristic
+				.run_async_sequential_with_metadata(
+					distribution,
+					|index, grouping, mut flag| async move {
+						if index == 0 {
+							flag = false;
+						}
+
+						// if the flag is set then we are going to change this grouping outcome to failures and not run anything
+						if flag {
+							return Ok((grouping.to_failures_prefer_instrumental(), flag));
 						}
-					};
 
-					Ok((outcome, flag))
-				},
-				false,
-			)
-			.await?;
+						let blocks = grouping.into_original();
+						let outcome = match self.submit_blocks(&blocks).await {
+							Ok(_) => GroupingOutcome::new_all_success(blocks.len()),
+							Err(_) => {
+								flag = true;
+								GroupingOutcome::new_apply(blocks)
+							}
+						};
+
+						Ok((outcome, flag))
+					},
+					false,
+					SUBMIT_MAX_ITERATIONS,
+				)
+				.await;
+
+			let exceeded = match result {
+				Ok(block_group_results) => break block_group_results,
+				Err(error) => error.downcast::<MaxIterationsExceeded<block::WrappedBlock>>()?,
+			};
+			let blocks = exceeded
+				.distribution
+				.into_iter()
+				.flat_map(GroupingOutcome::into_original)
+				.collect::<Vec<_>>();
+			let delay = backoff.next_delay();
+			warn!(
+				block_count = blocks.len(),
+				?delay,
+				"blocks not submitted within {} iterations, retrying",
+				exceeded.max_iterations
+			);
+			tokio::time::sleep(delay).await;
+			distribution = GroupingOutcome::new_apply_distribution(blocks);
+		};
 
 		info!("block group results: {:?}", block_group_results);
 		for block_group_result in &block_group_results {
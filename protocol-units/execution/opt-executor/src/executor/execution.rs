@@ -15,7 +15,7 @@ use aptos_types::{
 	transaction::{Transaction, Version},
 	validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
 };
-use movement_types::block::{BlockCommitment, Commitment, Id};
+use movement_types::block::{BlockCommitment, Id};
 use tracing::{debug, info};
 
 impl Executor {
@@ -87,8 +87,11 @@ impl Executor {
 		// Race conditions, anyone?
 		let block_height = self.get_block_head_height()?;
 
-		let commitment = Commitment::digest_state_proof(&proof);
-		Ok(BlockCommitment::new(block_height.into(), Id::new(*block_id.clone()), commitment))
+		Ok(BlockCommitment::from_state_proof(
+			block_height.into(),
+			Id::new(*block_id.clone()),
+			&proof,
+		))
 	}
 
 	pub fn get_block_head_height(&self) -> Result<u64, anyhow::Error> {
@@ -237,6 +240,7 @@ mod tests {
 		},
 		transaction::{RawTransaction, Script, SignedTransaction, Transaction, TransactionPayload},
 	};
+	use movement_types::block::Commitment;
 	use rand::SeedableRng;
 	use tokio::sync::mpsc;
 
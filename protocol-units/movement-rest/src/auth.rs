@@ -0,0 +1,93 @@
+use crate::MovementRestError;
+use poem::{
+	async_trait, http::header, Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use std::fmt;
+use std::sync::Arc;
+
+/// A bearer token clients have to present, redacted when debug printed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthToken(Arc<str>);
+
+impl AuthToken {
+	pub fn new(token: impl Into<String>) -> Self {
+		Self(token.into().into())
+	}
+
+	/// Checks an `Authorization` header value, comparing in constant time for equal lengths.
+	pub fn matches(&self, authorization: &str) -> bool {
+		let presented = match authorization.strip_prefix("Bearer ") {
+			Some(presented) => presented.trim().as_bytes(),
+			None => return false,
+		};
+		let expected = self.0.as_bytes();
+		presented.len() == expected.len()
+			&& presented.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+	}
+}
+
+impl fmt::Debug for AuthToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("AuthToken(..)")
+	}
+}
+
+/// Middleware rejecting requests without the configured bearer token with 401.
+/// Does nothing when no token is configured.
+#[derive(Debug, Clone)]
+pub struct BearerAuth {
+	token: Option<AuthToken>,
+}
+
+impl BearerAuth {
+	pub fn new(token: Option<AuthToken>) -> Self {
+		Self { token }
+	}
+}
+
+impl<E: Endpoint> Middleware<E> for BearerAuth {
+	type Output = BearerAuthEndpoint<E>;
+
+	fn transform(&self, ep: E) -> Self::Output {
+		BearerAuthEndpoint { inner: ep, token: self.token.clone() }
+	}
+}
+
+pub struct BearerAuthEndpoint<E> {
+	inner: E,
+	token: Option<AuthToken>,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for BearerAuthEndpoint<E> {
+	type Output = Response;
+
+	async fn call(&self, req: Request) -> Result<Self::Output> {
+		if let Some(token) = &self.token {
+			let authorized = req
+				.headers()
+				.get(header::AUTHORIZATION)
+				.and_then(|value| value.to_str().ok())
+				.is_some_and(|authorization| token.matches(authorization));
+			if !authorized {
+				return Err(MovementRestError::Unauthorized.into());
+			}
+		}
+		self.inner.call(req).await.map(IntoResponse::into_response)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_token_matches() {
+		let token = AuthToken::new("secret");
+		assert!(token.matches("Bearer secret"));
+		assert!(!token.matches("Bearer secreT"));
+		assert!(!token.matches("Bearer secret2"));
+		assert!(!token.matches("Basic secret"));
+		assert_eq!(format!("{:?}", token), "AuthToken(..)");
+	}
+}
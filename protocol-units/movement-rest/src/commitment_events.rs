@@ -0,0 +1,256 @@
+use futures::{stream, Stream, StreamExt};
+use movement_types::block::BlockCommitmentEvent;
+use poem::{
+	handler,
+	http::HeaderMap,
+	web::{
+		sse::{Event, SSE},
+		Data, Query,
+	},
+};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+/// The header a reconnecting SSE client sends the id of the last event it received in.
+pub const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+/// The id of a published event, formatted as `<boot>-<sequence>`.
+///
+/// The sequence increases in the order of publishing and restarts with the process, so the
+/// boot tells the ids of an earlier process apart from the ids of this one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventId {
+	pub boot: u64,
+	pub sequence: u64,
+}
+
+impl fmt::Display for EventId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:x}-{}", self.boot, self.sequence)
+	}
+}
+
+impl FromStr for EventId {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (boot, sequence) =
+			s.split_once('-').ok_or_else(|| anyhow::anyhow!("Invalid event id {}", s))?;
+		Ok(Self { boot: u64::from_str_radix(boot, 16)?, sequence: sequence.parse()? })
+	}
+}
+
+/// Fans out settlement events to subscribers, keeping the latest ones so they can resume.
+#[derive(Debug)]
+pub struct CommitmentEvents {
+	sender: broadcast::Sender<(EventId, BlockCommitmentEvent)>,
+	/// Distinguishes the event ids of this instance from those of an earlier process.
+	boot: u64,
+	history: Mutex<History>,
+	history_size: usize,
+}
+
+#[derive(Debug, Default)]
+struct History {
+	next_sequence: u64,
+	events: VecDeque<(EventId, BlockCommitmentEvent)>,
+}
+
+impl CommitmentEvents {
+	pub const DEFAULT_HISTORY_SIZE: usize = 1024;
+
+	pub fn new(history_size: usize) -> Self {
+		let (sender, _) = broadcast::channel(history_size.max(1));
+		let history = History { next_sequence: 0, events: VecDeque::with_capacity(history_size) };
+		// the start time of the instance, unique across restarts
+		let boot = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|since_epoch| since_epoch.as_nanos() as u64)
+			.unwrap_or_default();
+		Self { sender, boot, history: Mutex::new(history), history_size }
+	}
+
+	pub fn publish(&self, event: BlockCommitmentEvent) {
+		let mut history = self.history.lock().expect("commitment events lock poisoned");
+		let id = EventId { boot: self.boot, sequence: history.next_sequence };
+		history.next_sequence += 1;
+		if history.events.len() == self.history_size {
+			history.events.pop_front();
+		}
+		if self.history_size > 0 {
+			history.events.push_back((id, event.clone()));
+		}
+		// no subscribers is fine
+		let _ = self.sender.send((id, event));
+	}
+
+	/// Streams the events with their ids as they are published.
+	///
+	/// With `from_height`, the kept events at or above that height are replayed first,
+	/// and later events below it are skipped. With `last_event_id`, e.g., when a client
+	/// reconnects, the kept events published after that id are replayed first. An id this
+	/// instance didn't hand out, e.g., from before a restart, replays all kept events.
+	pub fn subscribe(
+		&self,
+		from_height: Option<u64>,
+		last_event_id: Option<&str>,
+	) -> impl Stream<Item = (EventId, BlockCommitmentEvent)> + Send + 'static {
+		let boot = self.boot;
+		let after = last_event_id
+			.and_then(|id| id.trim().parse::<EventId>().ok())
+			.filter(|id| id.boot == boot)
+			.map(|id| id.sequence);
+		let keep = move |id: EventId, event: &BlockCommitmentEvent| {
+			after.into_iter().all(|after| id.sequence > after)
+				&& from_height.into_iter().all(|from_height| event.height() >= from_height)
+		};
+
+		// subscribe under the lock, so no event is missed or replayed twice
+		let history = self.history.lock().expect("commitment events lock poisoned");
+		let receiver = self.sender.subscribe();
+		let replay: Vec<_> = if from_height.is_some() || last_event_id.is_some() {
+			history.events.iter().filter(|(id, event)| keep(*id, event)).cloned().collect()
+		} else {
+			Vec::new()
+		};
+		drop(history);
+
+		let live = stream::unfold(receiver, |mut receiver| async move {
+			loop {
+				match receiver.recv().await {
+					Ok(event) => return Some((event, receiver)),
+					Err(RecvError::Lagged(skipped)) => {
+						warn!("Commitment event subscriber lagged, skipped {} events", skipped);
+					}
+					Err(RecvError::Closed) => return None,
+				}
+			}
+		});
+		stream::iter(replay).chain(live.filter(move |(id, event)| {
+			let keep = keep(*id, event);
+			async move { keep }
+		}))
+	}
+}
+
+impl Default for CommitmentEvents {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_HISTORY_SIZE)
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitmentEventsQuery {
+	pub from_height: Option<u64>,
+}
+
+/// Server-sent events carrying the tagged JSON of each [BlockCommitmentEvent].
+///
+/// Each event has its [EventId] as the SSE id, so clients reconnecting with `Last-Event-ID`
+/// resume after the last event they received, see [CommitmentEvents::subscribe].
+#[handler]
+pub async fn stream_commitment_events(
+	Query(query): Query<CommitmentEventsQuery>,
+	headers: &HeaderMap,
+	events: Data<&Arc<CommitmentEvents>>,
+) -> SSE {
+	// an unreadable id is unknown, so it replays the history too
+	let last_event_id = headers.get(LAST_EVENT_ID).map(|value| value.to_str().unwrap_or_default());
+	let stream = events.subscribe(query.from_height, last_event_id).map(|(id, event)| {
+		let data = serde_json::to_string(&event).expect("commitment events serialize to JSON");
+		Event::message(data).id(id.to_string())
+	});
+	SSE::new(stream).keep_alive(Duration::from_secs(15))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use movement_types::block::{BlockCommitment, BlockCommitmentRejectionReason, Commitment, Id};
+
+	fn accepted(height: u64) -> BlockCommitmentEvent {
+		BlockCommitmentEvent::Accepted(BlockCommitment::new(height, Id::test(), Commitment::test()))
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_from_height() {
+		let events = CommitmentEvents::new(2);
+		events.publish(accepted(1));
+		events.publish(accepted(2));
+		events.publish(accepted(3));
+
+		let mut subscriber = Box::pin(events.subscribe(Some(2), None).map(|(_, event)| event));
+		let mut live = Box::pin(events.subscribe(None, None).map(|(_, event)| event));
+		events.publish(accepted(1));
+		let rejected = BlockCommitmentEvent::Rejected {
+			height: 4,
+			reason: BlockCommitmentRejectionReason::InvalidCommitment,
+		};
+		events.publish(rejected.clone());
+
+		// the history only kept the last two events, the late event at height 1 is skipped
+		assert_eq!(subscriber.next().await, Some(accepted(2)));
+		assert_eq!(subscriber.next().await, Some(accepted(3)));
+		assert_eq!(subscriber.next().await, Some(rejected.clone()));
+
+		assert_eq!(live.next().await, Some(accepted(1)));
+		assert_eq!(live.next().await, Some(rejected));
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_after_last_event_id() {
+		let events = CommitmentEvents::new(8);
+		let rejected = BlockCommitmentEvent::Rejected {
+			height: 1,
+			reason: BlockCommitmentRejectionReason::InvalidCommitment,
+		};
+		// events at the same height still get their own ids
+		events.publish(accepted(1));
+		events.publish(rejected.clone());
+		events.publish(accepted(2));
+
+		let id = |sequence| EventId { boot: events.boot, sequence };
+		let mut subscriber = Box::pin(events.subscribe(None, Some(&id(0).to_string())));
+		events.publish(accepted(3));
+		assert_eq!(subscriber.next().await, Some((id(1), rejected)));
+		assert_eq!(subscriber.next().await, Some((id(2), accepted(2))));
+		assert_eq!(subscriber.next().await, Some((id(3), accepted(3))));
+
+		// both filters apply when resuming a stream that started at a height
+		let mut subscriber = Box::pin(events.subscribe(Some(2), Some(&id(2).to_string())));
+		assert_eq!(subscriber.next().await, Some((id(3), accepted(3))));
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_after_unknown_event_id() {
+		let events = CommitmentEvents::new(8);
+		events.publish(accepted(1));
+		events.publish(accepted(2));
+		events.publish(accepted(3));
+		let heights = |id: Option<&str>| {
+			let replay = events.subscribe(None, id).map(|(_, event)| event.height()).take(3);
+			replay.collect::<Vec<_>>()
+		};
+
+		// an id of an earlier process with a higher sequence, a malformed id and an empty one
+		let earlier = EventId { boot: events.boot.wrapping_sub(1), sequence: 7 }.to_string();
+		for id in [earlier.as_str(), "7", ""] {
+			assert_eq!(heights(Some(id)).await, vec![1, 2, 3]);
+		}
+	}
+
+	#[test]
+	fn test_event_id_round_trip() {
+		let id = EventId { boot: 0x17f3a, sequence: 42 };
+		assert_eq!(id.to_string(), "17f3a-42");
+		assert_eq!("17f3a-42".parse::<EventId>().unwrap(), id);
+		assert!("42".parse::<EventId>().is_err());
+		assert!("x-42".parse::<EventId>().is_err());
+	}
+}
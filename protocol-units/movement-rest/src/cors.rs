@@ -0,0 +1,107 @@
+use poem::{http::Method, middleware::Cors};
+
+/// Cross-origin access for browser clients, e.g., light clients verifying state proofs.
+///
+/// Only localhost origins are allowed by default, wider access has to be configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+	/// Exact origins such as `https://app.example.com`, only localhost origins are allowed if empty.
+	pub allowed_origins: Vec<String>,
+	/// Allows any origin, e.g., for a public endpoint, regardless of `allowed_origins`.
+	pub allow_any_origin: bool,
+	pub allowed_methods: Vec<Method>,
+}
+
+impl Default for CorsConfig {
+	fn default() -> Self {
+		Self {
+			allowed_origins: Vec::new(),
+			allow_any_origin: false,
+			allowed_methods: vec![Method::GET, Method::OPTIONS],
+		}
+	}
+}
+
+impl CorsConfig {
+	/// Parses a comma separated list of origins, `*` allows any origin.
+	/// An empty list keeps allowing localhost origins only.
+	pub fn from_origins(origins: &str) -> Self {
+		let (any, allowed_origins): (Vec<&str>, Vec<&str>) = origins
+			.split(',')
+			.map(str::trim)
+			.filter(|origin| !origin.is_empty())
+			.partition(|origin| *origin == "*");
+		Self {
+			allowed_origins: allowed_origins.into_iter().map(str::to_string).collect(),
+			allow_any_origin: !any.is_empty(),
+			..Self::default()
+		}
+	}
+
+	/// Builds the middleware, which also answers preflight `OPTIONS` requests.
+	/// Requests from origins that are not allowed are rejected with 401.
+	pub fn middleware(&self) -> Cors {
+		let cors = Cors::new().allow_methods(self.allowed_methods.iter().cloned());
+		if self.allow_any_origin {
+			// poem allows any origin when none is listed
+			cors
+		} else if self.allowed_origins.is_empty() {
+			cors.allow_origins_fn(is_localhost_origin)
+		} else {
+			cors.allow_origins(self.allowed_origins.iter().map(String::as_str))
+		}
+	}
+}
+
+/// Whether the origin is served from the local machine, on any port.
+fn is_localhost_origin(origin: &str) -> bool {
+	let Some(authority) = ["http://", "https://"]
+		.into_iter()
+		.find_map(|scheme| origin.strip_prefix(scheme))
+	else {
+		return false;
+	};
+	let host = match authority.rsplit_once(':') {
+		Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+		_ => authority,
+	};
+	matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_origins() {
+		let config = CorsConfig::from_origins(" https://a.example.com,,https://b.example.com ");
+		assert_eq!(config.allowed_origins, vec!["https://a.example.com", "https://b.example.com"]);
+		assert!(!config.allow_any_origin);
+		assert_eq!(CorsConfig::from_origins(""), CorsConfig::default());
+
+		let config = CorsConfig::from_origins("*, https://a.example.com");
+		assert!(config.allow_any_origin);
+		assert_eq!(config.allowed_origins, vec!["https://a.example.com"]);
+	}
+
+	#[test]
+	fn test_is_localhost_origin() {
+		for origin in [
+			"http://localhost",
+			"http://localhost:3000",
+			"https://127.0.0.1:8443",
+			"http://[::1]:80",
+		] {
+			assert!(is_localhost_origin(origin), "{}", origin);
+		}
+		for origin in [
+			"https://example.com",
+			"http://localhost.example.com",
+			"http://example.com:localhost",
+			"localhost:3000",
+			"file://localhost",
+		] {
+			assert!(!is_localhost_origin(origin), "{}", origin);
+		}
+	}
+}
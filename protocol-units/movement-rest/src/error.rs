@@ -0,0 +1,59 @@
+use poem::{error::ResponseError, http::StatusCode, web::Json, IntoResponse, Response};
+use thiserror::Error;
+
+/// Errors returned by the movement REST handlers, rendered as a JSON `{ "error": ... }` body.
+#[derive(Debug, Error)]
+pub enum MovementRestError {
+	#[error("{0}")]
+	BadRequest(String),
+	#[error("{0}")]
+	NotFound(String),
+	#[error("{0}")]
+	NotAcceptable(String),
+	#[error("Block height {height} not yet produced, latest height is {latest_height}")]
+	BlockNotProduced { height: u64, latest_height: u64 },
+	#[error("Missing or invalid bearer token")]
+	Unauthorized,
+	#[error("{0}")]
+	Unavailable(String),
+	#[error("Internal error: {0}")]
+	Internal(#[from] anyhow::Error),
+}
+
+impl MovementRestError {
+	/// Wraps any error, e.g., from the database, as an internal error.
+	pub fn internal(error: impl Into<anyhow::Error>) -> Self {
+		MovementRestError::Internal(error.into())
+	}
+}
+
+impl ResponseError for MovementRestError {
+	fn status(&self) -> StatusCode {
+		match self {
+			MovementRestError::BadRequest(_) => StatusCode::BAD_REQUEST,
+			MovementRestError::NotFound(_) | MovementRestError::BlockNotProduced { .. } => {
+				StatusCode::NOT_FOUND
+			}
+			MovementRestError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+			MovementRestError::Unauthorized => StatusCode::UNAUTHORIZED,
+			MovementRestError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+			MovementRestError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+		}
+	}
+
+	fn as_response(&self) -> Response {
+		let body = match self {
+			// lets clients polling at the chain tip back off
+			MovementRestError::BlockNotProduced { latest_height, .. } => {
+				serde_json::json!({ "error": self.to_string(), "latest_height": latest_height })
+			}
+			// the cause may describe the node's internals, it only goes to the logs
+			MovementRestError::Internal(error) => {
+				tracing::error!("Internal error: {:?}", error);
+				serde_json::json!({ "error": "Internal error" })
+			}
+			_ => serde_json::json!({ "error": self.to_string() }),
+		};
+		Json(body).with_status(self.status()).into_response()
+	}
+}
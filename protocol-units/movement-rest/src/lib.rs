@@ -1,35 +1,165 @@
+pub mod auth;
+pub mod commitment_events;
+pub mod cors;
+pub mod error;
+pub mod limits;
+pub mod metrics;
+pub mod version;
+
+pub use auth::AuthToken;
+pub use commitment_events::CommitmentEvents;
+pub use cors::CorsConfig;
+pub use error::MovementRestError;
+pub use limits::{InFlightPermit, RequestLimits};
+pub use metrics::Metrics;
+pub use version::ApiVersion;
+
 use aptos_api::Context;
+use aptos_types::{
+	account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures,
+	proof::TransactionInfoWithProof, state_store::state_key::StateKey, transaction::Transaction,
+};
 
 use anyhow::Error;
+use auth::BearerAuth;
+use commitment_events::stream_commitment_events;
 use futures::prelude::*;
+use metrics::{prometheus_metrics, RecordMetrics};
+use movement_types::proof::{
+	account_state_key, AccountProofResponse, StateProofResponse, StateValueProofResponse,
+};
+use poem::endpoint::BoxEndpoint;
 use poem::listener::TcpListener;
 use poem::{
 	get, handler,
+	http::{header, HeaderMap, StatusCode},
 	middleware::Tracing,
-	web::{Data, Path},
+	web::{Data, Json, Path, Query},
 	EndpointExt, IntoResponse, Response, Route, Server,
 };
+use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use std::env;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct MovementRest {
-	/// The URL to bind the REST service to.
-	pub url: String,
+	/// The address to bind the REST service to.
+	pub bind_addr: SocketAddr,
 	pub context: Option<Arc<Context>>,
+	/// Request metrics, served at `/metrics` when enabled.
+	pub metrics: Option<Arc<Metrics>>,
+	/// Origins and methods browser clients may use.
+	pub cors: CorsConfig,
+	/// Required as a bearer token by every route except `/health` when set.
+	pub auth_token: Option<AuthToken>,
+	/// Settlement events, served at `/movement/{v1,v2}/commitment-events` when set.
+	pub commitment_events: Option<Arc<CommitmentEvents>>,
+	/// Timeout and concurrency limit of the routes reading the database.
+	pub limits: RequestLimits,
 	// More fields to be added here, log verboisty, etc.
 }
 
 impl MovementRest {
 	pub const MOVEMENT_REST_ENV_VAR: &'static str = "MOVEMENT_REST_URL";
+	pub const MOVEMENT_REST_METRICS_ENV_VAR: &'static str = "MOVEMENT_REST_METRICS";
+	pub const MOVEMENT_REST_CORS_ORIGINS_ENV_VAR: &'static str = "MOVEMENT_REST_CORS_ORIGINS";
+	pub const MOVEMENT_REST_AUTH_TOKEN_ENV_VAR: &'static str = "MOVEMENT_REST_AUTH_TOKEN";
+	pub const DEFAULT_BIND_ADDR: &'static str = "0.0.0.0:30832";
 
+	pub fn new(bind_addr: SocketAddr, context: Arc<Context>) -> Self {
+		Self {
+			bind_addr,
+			context: Some(context),
+			metrics: None,
+			cors: CorsConfig::default(),
+			auth_token: None,
+			commitment_events: None,
+			limits: RequestLimits::default(),
+		}
+	}
+
+	/// Reads the bind address from the environment, the context is set later with `set_context`.
+	/// Metrics are enabled by setting `MOVEMENT_REST_METRICS` to `true`, and browser origins other
+	/// than localhost are allowed by listing them in `MOVEMENT_REST_CORS_ORIGINS`, or `*` for any.
+	/// Setting `MOVEMENT_REST_AUTH_TOKEN` requires clients to send it as a bearer token.
 	pub fn try_from_env() -> Result<Self, Error> {
+		let cors = env::var(Self::MOVEMENT_REST_CORS_ORIGINS_ENV_VAR)
+			.map(|origins| CorsConfig::from_origins(&origins))
+			.unwrap_or_default();
+		let auth_token = env::var(Self::MOVEMENT_REST_AUTH_TOKEN_ENV_VAR)
+			.ok()
+			.filter(|token| !token.is_empty())
+			.map(AuthToken::new);
+		let rest = Self {
+			bind_addr: Self::bind_addr_from_env()?,
+			context: None,
+			metrics: None,
+			cors,
+			auth_token,
+			commitment_events: None,
+			limits: RequestLimits::default(),
+		};
+		let metrics_enabled = env::var(Self::MOVEMENT_REST_METRICS_ENV_VAR)
+			.map(|value| value.eq_ignore_ascii_case("true") || value == "1")
+			.unwrap_or(false);
+		Ok(if metrics_enabled { rest.with_metrics() } else { rest })
+	}
+
+	/// Enables request metrics and the `/metrics` endpoint.
+	pub fn with_metrics(mut self) -> Self {
+		self.metrics = Some(Arc::new(Metrics::new()));
+		self
+	}
+
+	pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+		self.cors = cors;
+		self
+	}
+
+	pub fn with_auth_token(mut self, token: AuthToken) -> Self {
+		self.auth_token = Some(token);
+		self
+	}
+
+	/// Sets the timeout of the routes reading the database.
+	pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+		self.limits = RequestLimits::new(timeout, self.limits.max_in_flight());
+		self
+	}
+
+	/// Sets how many requests to the routes reading the database may run at once.
+	pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+		self.limits = RequestLimits::new(self.limits.timeout(), max_in_flight);
+		self
+	}
+
+	/// Serves the events published to `commitment_events` to subscribers.
+	pub fn with_commitment_events(mut self, events: Arc<CommitmentEvents>) -> Self {
+		self.commitment_events = Some(events);
+		self
+	}
+
+	pub fn bind_addr_from_env() -> Result<SocketAddr, Error> {
 		let url = env::var(Self::MOVEMENT_REST_ENV_VAR)
-			.unwrap_or_else(|_| "http://0.0.0.0:30832".to_string());
-		Ok(Self { url, context: None })
+			.unwrap_or_else(|_| Self::DEFAULT_BIND_ADDR.to_string());
+		Self::parse_bind_addr(&url)
+	}
+
+	/// Parses a bind address, accepting URLs such as `http://0.0.0.0:30832` by stripping the scheme.
+	pub fn parse_bind_addr(url: &str) -> Result<SocketAddr, Error> {
+		let addr = url.split_once("://").map_or(url, |(_, addr)| addr).trim_end_matches('/');
+		addr.parse()
+			.map_err(|e| anyhow::anyhow!("Invalid movement rest bind address {}: {}", url, e))
+	}
+
+	/// The URL the REST service is reachable at.
+	pub fn url(&self) -> String {
+		format!("http://{}", self.bind_addr)
 	}
 
 	pub fn set_context(&mut self, context: Arc<Context>) {
@@ -37,20 +167,94 @@ impl MovementRest {
 	}
 
 	pub fn run_service(&self) -> impl Future<Output = Result<(), Error>> + Send {
-		info!("Starting movement rest service at {}", self.url);
+		self.run_service_with_shutdown(future::pending())
+	}
+
+	/// Runs the service until `shutdown` resolves, then stops accepting connections
+	/// and waits for in-flight requests to finish.
+	pub fn run_service_with_shutdown(
+		&self,
+		shutdown: impl Future<Output = ()> + Send + 'static,
+	) -> impl Future<Output = Result<(), Error>> + Send {
+		info!("Starting movement rest service at {}", self.url());
 		let movement_rest = self.create_routes();
-		Server::new(TcpListener::bind(self.url.clone()))
-			.run(movement_rest)
+		Server::new(TcpListener::bind(self.bind_addr))
+			.run_with_graceful_shutdown(movement_rest, shutdown, None)
 			.map_err(Into::into)
 	}
 
 	pub fn create_routes(&self) -> impl EndpointExt {
-		Route::new()
-			.at("/health", get(health))
-			.at("/movement/v1/state-root-hash/:blockheight", get(state_root_hash))
-			.at("movement/v1/richard", get(richard))
-			.data(self.context.clone())
-			.with(Tracing)
+		// record the metrics of each route under its path pattern,
+		// health checks stay open to load balancers when auth is configured
+		let auth = BearerAuth::new(self.auth_token.clone());
+		let at = |routes: Route, path: &'static str, ep: BoxEndpoint<'static>| {
+			let ep = if path == "/health" { ep } else { ep.with(auth.clone()).boxed() };
+			routes.at(path, ep.with(RecordMetrics::new(self.metrics.clone(), path)))
+		};
+		// the database routes share one in-flight budget
+		let limited = |ep: BoxEndpoint<'static>| ep.with(self.limits.clone()).boxed();
+		let mut routes = Route::new();
+		routes = at(routes, "/health", get(health).boxed());
+		// the metrics label each route by its path, so the paths of each version are spelled out
+		let versions = [
+			(
+				ApiVersion::V1,
+				[
+					"/movement/v1/state-root-hash/:blockheight",
+					"/movement/v1/block/:blockheight",
+					"/movement/v1/transaction/:version",
+					"/movement/v1/state-proof/:version",
+					"/movement/v1/state-proofs",
+					"/movement/v1/state-value-proof/:version",
+					"/movement/v1/account-proof/:version",
+					"/movement/v1/commitment-events",
+				],
+			),
+			(
+				ApiVersion::V2,
+				[
+					"/movement/v2/state-root-hash/:blockheight",
+					"/movement/v2/block/:blockheight",
+					"/movement/v2/transaction/:version",
+					"/movement/v2/state-proof/:version",
+					"/movement/v2/state-proofs",
+					"/movement/v2/state-value-proof/:version",
+					"/movement/v2/account-proof/:version",
+					"/movement/v2/commitment-events",
+				],
+			),
+		];
+		for (
+			version,
+			[state_root_hash_path, block_path, transaction_path, state_proof_path, state_proofs_path, state_value_proof_path, account_proof_path, events_path],
+		) in versions
+		{
+			routes = at(
+				routes,
+				state_root_hash_path,
+				limited(get(state_root_hash).data(version).boxed()),
+			);
+			routes = at(routes, block_path, limited(get(block_metadata).boxed()));
+			routes = at(routes, transaction_path, limited(get(transaction_by_version).boxed()));
+			routes = at(routes, state_proof_path, limited(get(state_proof).boxed()));
+			routes = at(routes, state_proofs_path, limited(get(state_proofs).boxed()));
+			routes = at(routes, state_value_proof_path, limited(get(state_value_proof).boxed()));
+			routes = at(routes, account_proof_path, limited(get(account_proof).boxed()));
+			if let Some(events) = &self.commitment_events {
+				let ep = get(stream_commitment_events).data(events.clone()).boxed();
+				routes = at(routes, events_path, ep);
+			}
+		}
+		// debug scaffolding, not served by release builds
+		if cfg!(debug_assertions) {
+			routes = routes.at("/movement/v1/richard", get(richard).with(auth.clone()));
+		}
+		if let Some(metrics) = &self.metrics {
+			routes = routes
+				.at("/metrics", get(prometheus_metrics).data(metrics.clone()).with(auth.clone()));
+		}
+		// handlers extract the context itself, not the option
+		routes.data_opt(self.context.clone()).with(self.cors.middleware()).with(Tracing)
 	}
 }
 
@@ -64,42 +268,1175 @@ pub async fn richard() -> Response {
 	"Well Done".into_response()
 }
 
-#[handler]
-pub async fn state_root_hash(
-	Path(blockheight): Path<u64>,
-	context: Data<&Arc<Context>>,
-) -> Result<Response, anyhow::Error> {
-	let latest_ledger_info = context.db.get_latest_ledger_info()?;
-	let (_, end_version, _) = context.db.get_block_info_by_height(blockheight)?;
-	tracing::info!("end_version: {}", end_version);
-	let txn_with_proof = context.db.get_transaction_by_version(
-		end_version,
-		latest_ledger_info.ledger_info().version(),
-		false,
-	)?;
-	tracing::info!("txn_with_proof: {:?}", txn_with_proof);
-	let state_root_hash = txn_with_proof
+/// Builds the ETag for data derived from a finalized block.
+/// Finalized proofs never change, so the block height and end version identify them.
+///
+/// Only the state root hash and block metadata routes are tagged: range pages grow with the
+/// chain and transactions are served with a proof against the latest ledger info.
+pub fn block_etag(blockheight: u64, end_version: u64) -> String {
+	format!("\"{}-{}\"", blockheight, end_version)
+}
+
+/// Checks whether any of the request's `If-None-Match` tags matches the ETag.
+pub fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+	headers
+		.get_all(header::IF_NONE_MATCH)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.map(|tag| tag.trim())
+		.any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// A 304 response for a client that already holds the current representation.
+pub fn not_modified(etag: &str) -> Response {
+	Response::builder()
+		.status(StatusCode::NOT_MODIFIED)
+		.header(header::ETAG, etag)
+		.finish()
+}
+
+/// Parses a numeric path parameter, e.g., a block height or version.
+pub fn parse_u64_param(name: &str, value: &str) -> Result<u64, MovementRestError> {
+	value
+		.parse()
+		.map_err(|_| MovementRestError::BadRequest(format!("Invalid {} {}", name, value)))
+}
+
+/// The state root hash committed at the end of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateRootHashResponse {
+	pub block_height: u64,
+	pub end_version: u64,
+	pub state_root_hash: String,
+}
+
+/// The body format of the state root hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+	Json,
+	/// Only the hex literal, as served by v1 unless JSON is requested.
+	Raw,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FormatQuery {
+	#[serde(default)]
+	pub format: Option<ResponseFormat>,
+}
+
+impl StateRootHashResponse {
+	/// Renders the response in the requested format.
+	pub fn into_format(self, format: ResponseFormat) -> Response {
+		match format {
+			ResponseFormat::Json => Json(self).into_response(),
+			ResponseFormat::Raw => self.state_root_hash.into_response(),
+		}
+	}
+}
+
+/// Runs the database reads of a handler on the blocking thread pool, so the request timeout of
+/// [RequestLimits] can fire while they run.
+///
+/// The read holds the request's permit, so a read abandoned by a timeout still counts against
+/// the in-flight limit until it finishes.
+async fn read_db<T: Send + 'static>(
+	context: &Arc<Context>,
+	permit: &InFlightPermit,
+	read: impl FnOnce(&Context) -> Result<T, MovementRestError> + Send + 'static,
+) -> Result<T, MovementRestError> {
+	let context = context.clone();
+	let permit = permit.clone();
+	tokio::task::spawn_blocking(move || {
+		let _permit = permit;
+		read(&context)
+	})
+	.await
+	.map_err(MovementRestError::internal)?
+}
+
+/// The version of the latest ledger info.
+fn latest_ledger_version(context: &Context) -> Result<u64, MovementRestError> {
+	let latest_ledger_info =
+		context.db.get_latest_ledger_info().map_err(MovementRestError::internal)?;
+	Ok(latest_ledger_info.ledger_info().version())
+}
+
+/// Looks up the height of the latest block at the ledger version.
+fn latest_block_height(context: &Context, ledger_version: u64) -> Result<u64, MovementRestError> {
+	let (_, _, new_block_event) = context
+		.db
+		.get_block_info_by_version(ledger_version)
+		.map_err(MovementRestError::internal)?;
+	Ok(new_block_event.height())
+}
+
+/// Fails with a 404 carrying the latest height if the block was not produced yet.
+fn check_block_produced(
+	context: &Context,
+	blockheight: u64,
+	ledger_version: u64,
+) -> Result<(), MovementRestError> {
+	let latest_height = latest_block_height(context, ledger_version)?;
+	if blockheight > latest_height {
+		return Err(MovementRestError::BlockNotProduced { height: blockheight, latest_height });
+	}
+	Ok(())
+}
+
+/// Looks up the last version of the block at the given height.
+/// The height has to be checked with [check_block_produced] first, lookups failing past that
+/// are database faults.
+fn block_end_version(context: &Context, blockheight: u64) -> Result<u64, MovementRestError> {
+	let (_, end_version, _) = context
+		.db
+		.get_block_info_by_height(blockheight)
+		.map_err(MovementRestError::internal)?;
+	Ok(end_version)
+}
+
+/// Reads the state root hash of the block ending at `end_version`.
+fn block_state_root_hash(
+	context: &Context,
+	blockheight: u64,
+	end_version: u64,
+	ledger_version: u64,
+) -> Result<StateRootHashResponse, MovementRestError> {
+	let txn_with_proof = context
+		.db
+		.get_transaction_by_version(end_version, ledger_version, false)
+		.map_err(MovementRestError::internal)?;
+	tracing::debug!("txn_with_proof: {:?}", txn_with_proof);
+	let state_checkpoint_hash = txn_with_proof
 		.proof
 		.transaction_info
 		.state_checkpoint_hash()
 		.ok_or_else(|| anyhow::anyhow!("No state root hash found"))?;
-	Ok(state_root_hash.to_string().into_response())
+	Ok(StateRootHashResponse {
+		block_height: blockheight,
+		end_version,
+		state_root_hash: state_checkpoint_hash.to_string(),
+	})
+}
+
+/// Serves the state root hash in the format of the [ApiVersion] of the route.
+#[handler]
+pub async fn state_root_hash(
+	Path(blockheight): Path<String>,
+	Query(query): Query<FormatQuery>,
+	headers: &HeaderMap,
+	version: Data<&ApiVersion>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Response, MovementRestError> {
+	let format = version.response_format(&query, headers)?;
+	let blockheight = parse_u64_param("block height", &blockheight)?;
+	let (end_version, ledger_version) = read_db(context.0, permit.0, move |context| {
+		let ledger_version = latest_ledger_version(context)?;
+		check_block_produced(context, blockheight, ledger_version)?;
+		Ok((block_end_version(context, blockheight)?, ledger_version))
+	})
+	.await?;
+	tracing::info!("end_version: {}", end_version);
+	let etag = block_etag(blockheight, end_version);
+	if etag_matches(headers, &etag) {
+		return Ok(not_modified(&etag));
+	}
+	let response = read_db(context.0, permit.0, move |context| {
+		block_state_root_hash(context, blockheight, end_version, ledger_version)
+	})
+	.await?;
+	let response = response.into_format(format).with_header(header::ETAG, etag);
+	Ok(match version.0 {
+		ApiVersion::V1 => response.into_response(),
+		// caches have to tell the negotiated representations apart
+		ApiVersion::V2 => response.with_header(header::VARY, "Accept").into_response(),
+	})
+}
+
+/// The maximum number of blocks returned by a single range query.
+pub const MAX_RANGE_LIMIT: u64 = 100;
+
+/// A range of block heights, `end` is inclusive.
+#[derive(Debug, Deserialize)]
+pub struct RangeQuery {
+	pub start: u64,
+	pub end: Option<u64>,
+	pub limit: Option<u64>,
+}
+
+/// A page of a range query, `next` is the start of the following page if there is one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+	pub items: Vec<T>,
+	pub next: Option<u64>,
+}
+
+/// Computes the cursor following a page of `len` items read from `start`.
+/// A short page means the chain tip was reached, so there is no next page yet.
+pub fn next_cursor(start: u64, len: u64, limit: u64, end: u64) -> Option<u64> {
+	start.checked_add(len).filter(|next| len == limit && *next <= end)
+}
+
+/// Serves the state proofs of a range of block heights, each for the last version of its block,
+/// see [state_proof].
+#[handler]
+pub async fn state_proofs(
+	Query(query): Query<RangeQuery>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Json<Page<StateProofResponse>>, MovementRestError> {
+	let limit = query.limit.unwrap_or(MAX_RANGE_LIMIT).min(MAX_RANGE_LIMIT);
+	let end = query.end.unwrap_or(u64::MAX);
+	if limit == 0 || end < query.start {
+		return Err(MovementRestError::BadRequest(format!(
+			"Invalid range start {} end {} limit {}",
+			query.start, end, limit
+		)));
+	}
+
+	let start = query.start;
+	let items = read_db(context.0, permit.0, move |context| {
+		// every proof of the page is anchored in the same ledger info
+		let latest_ledger_info =
+			context.db.get_latest_ledger_info().map_err(MovementRestError::internal)?;
+		// heights past the chain tip are not produced yet, they make the page short
+		let latest_height =
+			latest_block_height(context, latest_ledger_info.ledger_info().version())?;
+
+		let mut items = Vec::new();
+		if start <= latest_height {
+			for blockheight in (start..=end.min(latest_height)).take(limit as usize) {
+				let end_version = block_end_version(context, blockheight)?;
+				items.push(read_state_proof(context, end_version, latest_ledger_info.clone())?);
+			}
+		}
+		Ok(items)
+	})
+	.await?;
+
+	let next = next_cursor(query.start, items.len() as u64, limit, end);
+	Ok(Json(Page { items, next }))
+}
+
+/// Metadata of a block, as needed to request proofs for its versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockMetadataResponse {
+	pub block_height: u64,
+	pub epoch: u64,
+	pub round: u64,
+	pub timestamp_usecs: u64,
+	pub first_version: u64,
+	pub last_version: u64,
+	pub block_hash: String,
+}
+
+#[handler]
+pub async fn block_metadata(
+	Path(blockheight): Path<String>,
+	headers: &HeaderMap,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Response, MovementRestError> {
+	let blockheight = parse_u64_param("block height", &blockheight)?;
+	let (first_version, last_version, new_block_event) =
+		read_db(context.0, permit.0, move |context| {
+			check_block_produced(context, blockheight, latest_ledger_version(context)?)?;
+			context
+				.db
+				.get_block_info_by_height(blockheight)
+				.map_err(MovementRestError::internal)
+		})
+		.await?;
+	let etag = block_etag(blockheight, last_version);
+	if etag_matches(headers, &etag) {
+		return Ok(not_modified(&etag));
+	}
+	Ok(Json(BlockMetadataResponse {
+		block_height: new_block_event.height(),
+		epoch: new_block_event.epoch(),
+		round: new_block_event.round(),
+		timestamp_usecs: new_block_event.proposed_time(),
+		first_version,
+		last_version,
+		block_hash: new_block_event.hash().map_err(MovementRestError::internal)?.to_string(),
+	})
+	.with_header(header::ETAG, etag)
+	.into_response())
+}
+
+/// A committed transaction with the proof of its transaction info in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionResponse {
+	pub version: u64,
+	pub transaction: Transaction,
+	pub proof: TransactionInfoWithProof,
+}
+
+#[handler]
+pub async fn transaction_by_version(
+	Path(version): Path<String>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Response, MovementRestError> {
+	let version = parse_u64_param("version", &version)?;
+	let txn_with_proof = read_db(context.0, permit.0, move |context| {
+		let latest_version = latest_ledger_version(context)?;
+		if version > latest_version {
+			return Err(MovementRestError::NotFound(format!(
+				"Transaction version {} not found, latest version is {}",
+				version, latest_version
+			)));
+		}
+		context
+			.db
+			.get_transaction_by_version(version, latest_version, false)
+			.map_err(MovementRestError::internal)
+	})
+	.await?;
+	Ok(Json(TransactionResponse {
+		version: txn_with_proof.version,
+		transaction: txn_with_proof.transaction,
+		proof: txn_with_proof.proof,
+	})
+	.into_response())
+}
+
+/// Reads the proof of the transaction at a version against `latest_ledger_info`,
+/// with the epoch changes leading to that ledger info.
+fn read_state_proof(
+	context: &Context,
+	version: u64,
+	latest_ledger_info: LedgerInfoWithSignatures,
+) -> Result<StateProofResponse, MovementRestError> {
+	let latest_version = latest_ledger_info.ledger_info().version();
+	if version > latest_version {
+		return Err(MovementRestError::NotFound(format!(
+			"Transaction version {} not found, latest version is {}",
+			version, latest_version
+		)));
+	}
+	let txn_with_proof = context
+		.db
+		.get_transaction_by_version(version, latest_version, false)
+		.map_err(MovementRestError::internal)?;
+	let proof = context
+		.db
+		.get_state_proof_with_ledger_info(version, latest_ledger_info)
+		.map_err(MovementRestError::internal)?;
+	Ok(StateProofResponse::new(version, proof, txn_with_proof.proof))
+}
+
+/// Serves the proof of the transaction at a version against the latest ledger info,
+/// with the epoch changes leading to that ledger info.
+#[handler]
+pub async fn state_proof(
+	Path(version): Path<String>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Json<StateProofResponse>, MovementRestError> {
+	let version = parse_u64_param("version", &version)?;
+	let response = read_db(context.0, permit.0, move |context| {
+		let latest_ledger_info =
+			context.db.get_latest_ledger_info().map_err(MovementRestError::internal)?;
+		read_state_proof(context, version, latest_ledger_info)
+	})
+	.await?;
+	Ok(Json(response))
+}
+
+/// The state key to prove, BCS encoded and hex encoded.
+#[derive(Debug, Deserialize)]
+pub struct StateKeyQuery {
+	pub state_key: String,
+}
+
+/// Serves the proof of the value of a state key at a version, e.g., of a table item, which a
+/// client can't prove with the transaction alone. The version has to be a state checkpoint,
+/// like the last version of a block, and the value is proven in the state checkpoint hash of
+/// its transaction, whose proof is served along as by [state_proof].
+#[handler]
+pub async fn state_value_proof(
+	Path(version): Path<String>,
+	Query(query): Query<StateKeyQuery>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Json<StateValueProofResponse>, MovementRestError> {
+	let version = parse_u64_param("version", &version)?;
+	let state_key = hex::decode(query.state_key.trim_start_matches("0x"))
+		.ok()
+		.and_then(|bytes| bcs::from_bytes::<StateKey>(&bytes).ok())
+		.ok_or_else(|| {
+			MovementRestError::BadRequest(format!("Invalid state key {}", query.state_key))
+		})?;
+	let response = read_db(context.0, permit.0, move |context| {
+		read_state_value_proof(context, version, state_key)
+	})
+	.await?;
+	Ok(Json(response))
+}
+
+/// Reads the proof of the value of a state key at a version, see [state_value_proof].
+fn read_state_value_proof(
+	context: &Context,
+	version: u64,
+	state_key: StateKey,
+) -> Result<StateValueProofResponse, MovementRestError> {
+	let latest_ledger_info =
+		context.db.get_latest_ledger_info().map_err(MovementRestError::internal)?;
+	let proof = read_state_proof(context, version, latest_ledger_info)?;
+	if proof.tx_proof.transaction_info().state_checkpoint_hash().is_none() {
+		return Err(MovementRestError::BadRequest(format!(
+			"Version {} is not a state checkpoint",
+			version
+		)));
+	}
+	let (state_value, state_value_proof) = context
+		.db
+		.get_state_value_with_proof_by_version(&state_key, version)
+		.map_err(MovementRestError::internal)?;
+	Ok(StateValueProofResponse { state_key, state_value, state_value_proof, proof })
+}
+
+/// The account to prove, hex encoded.
+#[derive(Debug, Deserialize)]
+pub struct AccountQuery {
+	pub address: String,
+}
+
+/// Serves the proof of the account resource of an address at a version, as [state_value_proof]
+/// serves the proof of its state key.
+#[handler]
+pub async fn account_proof(
+	Path(version): Path<String>,
+	Query(query): Query<AccountQuery>,
+	context: Data<&Arc<Context>>,
+	permit: Data<&InFlightPermit>,
+) -> Result<Json<AccountProofResponse>, MovementRestError> {
+	let version = parse_u64_param("version", &version)?;
+	let address = AccountAddress::from_hex_literal(&query.address)
+		.map_err(|_| MovementRestError::BadRequest(format!("Invalid address {}", query.address)))?;
+	let state_key = account_state_key(address).map_err(MovementRestError::internal)?;
+	let proof = read_db(context.0, permit.0, move |context| {
+		read_state_value_proof(context, version, state_key)
+	})
+	.await?;
+	Ok(Json(AccountProofResponse { address, proof }))
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use aptos_config::config::NodeConfig;
+	use aptos_crypto::{hash::CryptoHash, HashValue};
+	use aptos_storage_interface::{AptosDbError, DbReader};
+	use aptos_types::{
+		account_address::AccountAddress,
+		account_config::NewBlockEvent,
+		aggregate_signature::AggregateSignature,
+		block_info::BlockInfo,
+		chain_id::ChainId,
+		epoch_change::EpochChangeProof,
+		ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+		proof::{
+			SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorProof, TransactionInfo,
+			TransactionInfoWithProof,
+		},
+		state_proof::StateProof,
+		state_store::state_value::StateValue,
+		transaction::{ExecutionStatus, Transaction, TransactionWithProof, Version},
+	};
+	use movement_types::block::{
+		BlockCommitment, BlockCommitmentEvent, BlockCommitmentRejectionReason,
+	};
 	use poem::test::TestClient;
 
+	/// A database of blocks spanning `VERSIONS_PER_BLOCK` versions each, up to `LATEST_HEIGHT`.
+	#[derive(Default)]
+	struct MockDb {
+		/// Fails block lookups by height, as a corrupted block index would.
+		faulty_block_index: bool,
+		/// Blocks the thread reading the latest ledger info, as a slow disk would.
+		read_delay: Duration,
+	}
+
+	impl MockDb {
+		const VERSIONS_PER_BLOCK: u64 = 10;
+		const LATEST_HEIGHT: u64 = 5;
+
+		fn block(height: u64) -> (Version, Version, NewBlockEvent) {
+			let first_version = height * Self::VERSIONS_PER_BLOCK;
+			let event = NewBlockEvent::new(
+				AccountAddress::ZERO,
+				1,
+				height,
+				height,
+				vec![],
+				AccountAddress::ZERO,
+				vec![],
+				height * 1_000_000,
+			);
+			(first_version, first_version + Self::VERSIONS_PER_BLOCK - 1, event)
+		}
+
+		/// The first version checkpoints a state holding one value, which is proven against it.
+		fn transaction_info(version: Version) -> TransactionInfo {
+			let state_root = match version {
+				0 => Self::state_leaf().hash(),
+				version => HashValue::new([version as u8; 32]),
+			};
+			TransactionInfo::new(
+				HashValue::zero(),
+				HashValue::zero(),
+				HashValue::zero(),
+				Some(state_root),
+				0,
+				ExecutionStatus::Success,
+			)
+		}
+
+		/// A raw state key, the third variant of its BCS encoding.
+		const STATE_KEY: [u8; 5] = [2, 3, 1, 2, 3];
+
+		fn state_leaf() -> SparseMerkleLeafNode {
+			let state_key: StateKey = bcs::from_bytes(&Self::STATE_KEY).unwrap();
+			SparseMerkleLeafNode::new(state_key.hash(), StateValue::from(vec![7; 4]).hash())
+		}
+	}
+
+	impl DbReader for MockDb {
+		fn get_latest_ledger_info(
+			&self,
+		) -> aptos_storage_interface::Result<LedgerInfoWithSignatures> {
+			std::thread::sleep(self.read_delay);
+			let (_, latest_version, _) = Self::block(Self::LATEST_HEIGHT);
+			// the transaction proofs carry no siblings, so the accumulator only proves version 0
+			let accumulator_root = Self::transaction_info(0).hash();
+			let block_info =
+				BlockInfo::new(1, 0, HashValue::zero(), accumulator_root, latest_version, 0, None);
+			Ok(LedgerInfoWithSignatures::new(
+				LedgerInfo::new(block_info, HashValue::zero()),
+				AggregateSignature::empty(),
+			))
+		}
+
+		fn get_block_info_by_height(
+			&self,
+			height: u64,
+		) -> aptos_storage_interface::Result<(Version, Version, NewBlockEvent)> {
+			if self.faulty_block_index {
+				return Err(anyhow::anyhow!("Block index corrupted").into());
+			}
+			if height > Self::LATEST_HEIGHT {
+				return Err(AptosDbError::NotFound(format!("Block height {}", height)));
+			}
+			Ok(Self::block(height))
+		}
+
+		fn get_block_info_by_version(
+			&self,
+			version: Version,
+		) -> aptos_storage_interface::Result<(Version, Version, NewBlockEvent)> {
+			Ok(Self::block(version / Self::VERSIONS_PER_BLOCK))
+		}
+
+		fn get_transaction_by_version(
+			&self,
+			version: Version,
+			_ledger_version: Version,
+			_fetch_events: bool,
+		) -> aptos_storage_interface::Result<TransactionWithProof> {
+			Ok(TransactionWithProof::new(
+				version,
+				Transaction::StateCheckpoint(HashValue::zero()),
+				None,
+				TransactionInfoWithProof::new(
+					TransactionAccumulatorProof::new(vec![]),
+					Self::transaction_info(version),
+				),
+			))
+		}
+
+		fn get_state_proof_with_ledger_info(
+			&self,
+			_known_version: u64,
+			ledger_info: LedgerInfoWithSignatures,
+		) -> aptos_storage_interface::Result<StateProof> {
+			Ok(StateProof::new(ledger_info, EpochChangeProof::new(vec![], false)))
+		}
+
+		fn get_state_value_with_proof_by_version(
+			&self,
+			state_key: &StateKey,
+			_version: Version,
+		) -> aptos_storage_interface::Result<(Option<StateValue>, SparseMerkleProof)> {
+			let leaf = Self::state_leaf();
+			let state_value =
+				(state_key.hash() == *leaf.key()).then(|| StateValue::from(vec![7; 4]));
+			Ok((state_value, SparseMerkleProof::new(Some(leaf), vec![])))
+		}
+	}
+
+	/// A service without a context, so the database routes fail.
+	fn service() -> MovementRest {
+		MovementRest {
+			bind_addr: "127.0.0.1:0".parse().unwrap(),
+			context: None,
+			metrics: None,
+			cors: CorsConfig::default(),
+			auth_token: None,
+			commitment_events: None,
+			limits: RequestLimits::default(),
+		}
+	}
+
+	/// A service reading from `db`.
+	fn mock_service(db: MockDb) -> MovementRest {
+		let context = Context::new(
+			ChainId::test(),
+			Arc::new(db),
+			futures::channel::mpsc::channel(1).0,
+			NodeConfig::default(),
+			None,
+		);
+		MovementRest::new("127.0.0.1:0".parse().unwrap(), Arc::new(context))
+	}
+
 	#[tokio::test]
 	async fn test_health_endpoint() {
-		let rest_service = MovementRest::try_from_env().expect("Failed to create MovementRest");
-		assert_eq!(rest_service.url, "http://0.0.0.0:30832");
+		let rest_service = service();
+		assert_eq!(rest_service.url(), "http://127.0.0.1:0");
 		// Create a test client
 		let client = TestClient::new(rest_service.create_routes());
 
 		// Test the /health endpoint
 		let response = client.get("/health").send().await;
 		assert!(response.0.status().is_success());
+
+		// The debug endpoint is only mounted in debug builds
+		let response = client.get("/movement/v1/richard").send().await;
+		assert_eq!(response.0.status().is_success(), cfg!(debug_assertions));
+	}
+
+	#[tokio::test]
+	async fn test_graceful_shutdown() -> Result<(), anyhow::Error> {
+		let rest_service = service();
+		let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+		let service = tokio::spawn(rest_service.run_service_with_shutdown(async move {
+			let _ = shutdown_rx.await;
+		}));
+		shutdown_tx.send(()).expect("service stopped early");
+
+		tokio::time::timeout(std::time::Duration::from_secs(5), service).await???;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_metrics_endpoint() {
+		let rest_service = service();
+		let client = TestClient::new(rest_service.create_routes());
+		client.get("/metrics").send().await.assert_status(StatusCode::NOT_FOUND);
+
+		let client = TestClient::new(rest_service.with_metrics().create_routes());
+		client.get("/health").send().await.assert_status_is_ok();
+
+		let response = client.get("/metrics").send().await;
+		response.assert_status_is_ok();
+		let body = response.0.into_body().into_string().await.unwrap();
+		assert!(body.contains("movement_rest_requests_total{route=\"/health\",status=\"200\"} 1"));
+	}
+
+	#[tokio::test]
+	async fn test_cors_preflight() {
+		let rest_service = service();
+		let client = TestClient::new(rest_service.create_routes());
+
+		let response = client
+			.options("/movement/v1/state-root-hash/1")
+			.header(header::ORIGIN, "http://localhost:3000")
+			.header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+			.send()
+			.await;
+		response.assert_status_is_ok();
+		response.assert_header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "http://localhost:3000");
+
+		// only localhost origins are allowed by default
+		let response =
+			client.get("/health").header(header::ORIGIN, "https://example.com").send().await;
+		response.assert_status(StatusCode::UNAUTHORIZED);
+
+		let client =
+			TestClient::new(service().with_cors(CorsConfig::from_origins("*")).create_routes());
+		let response =
+			client.get("/health").header(header::ORIGIN, "https://example.com").send().await;
+		response.assert_status_is_ok();
+		response.assert_header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "https://example.com");
+
+		let client = TestClient::new(
+			rest_service
+				.with_cors(CorsConfig::from_origins("https://example.com"))
+				.create_routes(),
+		);
+		let response =
+			client.get("/health").header(header::ORIGIN, "https://example.com").send().await;
+		response.assert_status_is_ok();
+		response.assert_header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "https://example.com");
+
+		let response = client
+			.get("/health")
+			.header(header::ORIGIN, "https://other.example.com")
+			.send()
+			.await;
+		response.assert_status(StatusCode::UNAUTHORIZED);
+	}
+
+	#[tokio::test]
+	async fn test_bearer_auth() {
+		let rest_service = service().with_auth_token(AuthToken::new("secret"));
+		let client = TestClient::new(rest_service.create_routes());
+
+		client.get("/health").send().await.assert_status_is_ok();
+
+		let response = client.get("/movement/v1/state-root-hash/1").send().await;
+		response.assert_status(StatusCode::UNAUTHORIZED);
+		response
+			.assert_json(serde_json::json!({ "error": "Missing or invalid bearer token" }))
+			.await;
+
+		client
+			.get("/movement/v1/state-root-hash/1")
+			.header(header::AUTHORIZATION, "Bearer wrong")
+			.send()
+			.await
+			.assert_status(StatusCode::UNAUTHORIZED);
+
+		// past the auth check, the handler fails for lack of a context
+		client
+			.get("/movement/v1/state-root-hash/1")
+			.header(header::AUTHORIZATION, "Bearer secret")
+			.send()
+			.await
+			.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+	}
+
+	#[tokio::test]
+	async fn test_request_limits_cover_database_routes() {
+		let rest_service = service().with_max_in_flight(0);
+		let client = TestClient::new(rest_service.create_routes());
+
+		client.get("/health").send().await.assert_status_is_ok();
+		for path in ["/movement/v1/state-root-hash/1", "/movement/v2/state-root-hash/1"] {
+			client.get(path).send().await.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_commitment_events_endpoint() {
+		let events = Arc::new(CommitmentEvents::default());
+		let event = BlockCommitmentEvent::Accepted(BlockCommitment::test());
+		events.publish(event.clone());
+		let rest_service = service().with_commitment_events(events.clone());
+		let client = TestClient::new(rest_service.create_routes());
+
+		let response = client.get("/movement/v1/commitment-events?from_height=0").send().await;
+		response.assert_status_is_ok();
+		response.assert_content_type("text/event-stream");
+		let mut body = response.0.into_body().into_bytes_stream();
+		let chunk = body.next().await.expect("Expected the replayed event").unwrap();
+		let chunk = std::str::from_utf8(&chunk).unwrap().to_string();
+		let id = chunk
+			.strip_prefix("id: ")
+			.and_then(|chunk| chunk.split_once('\n'))
+			.map(|(id, _)| id.parse::<commitment_events::EventId>().unwrap())
+			.expect("Expected the event id");
+		assert_eq!(
+			chunk,
+			format!("id: {}\ndata: {}\n\n", id, serde_json::to_string(&event).unwrap())
+		);
+
+		// a reconnecting client resumes after the last event it received
+		let rejected = BlockCommitmentEvent::Rejected {
+			height: 0,
+			reason: BlockCommitmentRejectionReason::InvalidCommitment,
+		};
+		events.publish(rejected.clone());
+		let response = client
+			.get("/movement/v1/commitment-events?from_height=0")
+			.header(commitment_events::LAST_EVENT_ID, id.to_string())
+			.send()
+			.await;
+		let mut body = response.0.into_body().into_bytes_stream();
+		let chunk = body.next().await.expect("Expected the replayed event").unwrap();
+		let next_id = commitment_events::EventId { sequence: id.sequence + 1, ..id };
+		assert_eq!(
+			std::str::from_utf8(&chunk).unwrap(),
+			format!("id: {}\ndata: {}\n\n", next_id, serde_json::to_string(&rejected).unwrap())
+		);
+
+		// an id from before a restart replays the whole history
+		let response = client
+			.get("/movement/v1/commitment-events")
+			.header(commitment_events::LAST_EVENT_ID, "0")
+			.send()
+			.await;
+		let mut body = response.0.into_body().into_bytes_stream();
+		let chunk = body.next().await.expect("Expected the replayed event").unwrap();
+		assert_eq!(
+			std::str::from_utf8(&chunk).unwrap(),
+			format!("id: {}\ndata: {}\n\n", id, serde_json::to_string(&event).unwrap())
+		);
+	}
+
+	#[test]
+	fn test_next_cursor() {
+		// full page with more heights to come
+		assert_eq!(next_cursor(10, 5, 5, u64::MAX), Some(15));
+		// full page ending exactly at the requested end
+		assert_eq!(next_cursor(10, 5, 5, 14), None);
+		// short page, the chain tip was reached
+		assert_eq!(next_cursor(10, 3, 5, u64::MAX), None);
+	}
+
+	#[test]
+	fn test_parse_bind_addr() {
+		let expected: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+		assert_eq!(MovementRest::parse_bind_addr("127.0.0.1:8080").unwrap(), expected);
+		assert_eq!(MovementRest::parse_bind_addr("http://127.0.0.1:8080").unwrap(), expected);
+		assert_eq!(MovementRest::parse_bind_addr("https://127.0.0.1:8080/").unwrap(), expected);
+		assert_eq!(
+			MovementRest::parse_bind_addr(MovementRest::DEFAULT_BIND_ADDR).unwrap(),
+			"0.0.0.0:30832".parse().unwrap()
+		);
+		assert!(MovementRest::parse_bind_addr("http://localhost").is_err());
+	}
+
+	#[handler]
+	async fn failing(Path(blockheight): Path<String>) -> Result<Response, MovementRestError> {
+		let blockheight = parse_u64_param("block height", &blockheight)?;
+		if blockheight > 10 {
+			return Err(MovementRestError::BlockNotProduced {
+				height: blockheight,
+				latest_height: 10,
+			});
+		}
+		Err(MovementRestError::NotFound(format!("Block height {} not found", blockheight)))
+	}
+
+	#[tokio::test]
+	async fn test_json_errors() {
+		let client = TestClient::new(Route::new().at("/block/:blockheight", get(failing)));
+
+		let response = client.get("/block/abc").send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+		response
+			.assert_json(serde_json::json!({ "error": "Invalid block height abc" }))
+			.await;
+
+		let response = client.get("/block/99999999999999999999").send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+
+		let response = client.get("/block/12").send().await;
+		response.assert_status(StatusCode::NOT_FOUND);
+		response
+			.assert_json(serde_json::json!({
+				"error": "Block height 12 not yet produced, latest height is 10",
+				"latest_height": 10,
+			}))
+			.await;
+
+		let response = client.get("/block/7").send().await;
+		response.assert_status(StatusCode::NOT_FOUND);
+		response
+			.assert_json(serde_json::json!({ "error": "Block height 7 not found" }))
+			.await;
+	}
+
+	#[handler]
+	fn formatted(
+		Query(query): Query<FormatQuery>,
+		headers: &HeaderMap,
+	) -> Result<Response, MovementRestError> {
+		let format = ApiVersion::V1.response_format(&query, headers)?;
+		Ok(StateRootHashResponse {
+			block_height: 1,
+			end_version: 42,
+			state_root_hash: "0xabcd".to_string(),
+		}
+		.into_format(format))
+	}
+
+	#[tokio::test]
+	async fn test_state_root_hash_formats() {
+		let client = TestClient::new(Route::new().at("/state-root-hash", get(formatted)));
+
+		// v1 keeps serving the bare hex literal by default
+		let response = client.get("/state-root-hash").send().await;
+		response.assert_status_is_ok();
+		response.assert_text("0xabcd").await;
+
+		let response = client.get("/state-root-hash").query("format", &"json").send().await;
+		response
+			.assert_json(serde_json::json!({
+				"block_height": 1,
+				"end_version": 42,
+				"state_root_hash": "0xabcd",
+			}))
+			.await;
+
+		let response = client.get("/state-root-hash").query("format", &"raw").send().await;
+		response.assert_text("0xabcd").await;
+
+		let response = client.get("/state-root-hash").query("format", &"xml").send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+	}
+
+	#[tokio::test]
+	async fn test_conditional_request_not_modified() {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+
+		for path in [
+			"/movement/v1/state-root-hash/2",
+			"/movement/v2/state-root-hash/2",
+			"/movement/v1/block/2",
+			"/movement/v2/block/2",
+		] {
+			let response = client.get(path).send().await;
+			response.assert_status_is_ok();
+			response.assert_header(header::ETAG, block_etag(2, 29));
+
+			let response =
+				client.get(path).header(header::IF_NONE_MATCH, block_etag(2, 29)).send().await;
+			response.assert_status(StatusCode::NOT_MODIFIED);
+			response.assert_header(header::ETAG, block_etag(2, 29));
+
+			let response =
+				client.get(path).header(header::IF_NONE_MATCH, block_etag(3, 39)).send().await;
+			response.assert_status_is_ok();
+		}
+
+		let response = client.get("/movement/v1/state-root-hash/2").send().await;
+		response.assert_text(HashValue::new([29; 32]).to_string()).await;
+		let response = client.get("/movement/v1/state-root-hash/2?format=json").send().await;
+		response
+			.assert_json(serde_json::json!({
+				"block_height": 2,
+				"end_version": 29,
+				"state_root_hash": HashValue::new([29; 32]).to_string(),
+			}))
+			.await;
+	}
+
+	#[tokio::test]
+	async fn test_database_faults_are_internal_errors() {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+		for path in ["/movement/v1/state-root-hash/9", "/movement/v1/block/9"] {
+			let response = client.get(path).send().await;
+			response.assert_status(StatusCode::NOT_FOUND);
+			response
+				.assert_json(serde_json::json!({
+					"error": "Block height 9 not yet produced, latest height is 5",
+					"latest_height": 5,
+				}))
+				.await;
+		}
+
+		let db = MockDb { faulty_block_index: true, ..Default::default() };
+		let client = TestClient::new(mock_service(db).create_routes());
+		for path in [
+			"/movement/v1/state-root-hash/2",
+			"/movement/v1/state-proofs?start=0",
+			"/movement/v1/block/2",
+		] {
+			let response = client.get(path).send().await;
+			response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+			// the database error is not leaked to clients
+			response.assert_json(serde_json::json!({ "error": "Internal error" })).await;
+		}
+	}
+
+	#[tokio::test]
+	async fn test_request_timeout_covers_database_reads() {
+		let db = MockDb { read_delay: Duration::from_millis(500), ..Default::default() };
+		let rest_service = mock_service(db).with_request_timeout(Duration::from_millis(50));
+		let client = TestClient::new(rest_service.create_routes());
+
+		for path in [
+			"/movement/v1/state-root-hash/2",
+			"/movement/v1/state-proofs?start=0",
+			"/movement/v1/block/2",
+			"/movement/v1/transaction/20",
+		] {
+			let response = client.get(path).send().await;
+			response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+			response
+				.assert_json(serde_json::json!({ "error": "Request timed out after 50ms" }))
+				.await;
+		}
+	}
+
+	#[tokio::test]
+	async fn test_timed_out_reads_hold_their_permit() {
+		let db = MockDb { read_delay: Duration::from_millis(500), ..Default::default() };
+		let rest_service = mock_service(db)
+			.with_request_timeout(Duration::from_millis(50))
+			.with_max_in_flight(1);
+		let client = TestClient::new(rest_service.create_routes());
+
+		let response = client.get("/movement/v1/block/2").send().await;
+		response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		response
+			.assert_json(serde_json::json!({ "error": "Request timed out after 50ms" }))
+			.await;
+
+		// the abandoned read still runs
+		let response = client.get("/movement/v1/block/2").send().await;
+		response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		response
+			.assert_json(serde_json::json!({ "error": "Too many requests in flight" }))
+			.await;
+
+		tokio::time::sleep(Duration::from_millis(600)).await;
+		let response = client.get("/movement/v1/block/2").send().await;
+		response
+			.assert_json(serde_json::json!({ "error": "Request timed out after 50ms" }))
+			.await;
+	}
+
+	#[tokio::test]
+	async fn test_state_proof_endpoint() -> Result<(), anyhow::Error> {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+
+		for path in ["/movement/v1/state-proof/0", "/movement/v2/state-proof/0"] {
+			let response = client.get(path).send().await;
+			response.assert_status_is_ok();
+			let body = response.0.into_body().into_string().await?;
+			let proof: StateProofResponse = serde_json::from_str(&body)?;
+			assert_eq!(proof.version, StateProofResponse::VERSION);
+			assert_eq!(proof.tx_index, 0);
+			let ledger_info = proof.state_proof.latest_ledger_info();
+			assert_eq!(ledger_info.version(), 59);
+			proof.verify_transaction(ledger_info.transaction_accumulator_hash())?;
+		}
+
+		let response = client.get("/movement/v1/state-proof/60").send().await;
+		response.assert_status(StatusCode::NOT_FOUND);
+		response
+			.assert_json(serde_json::json!({
+				"error": "Transaction version 60 not found, latest version is 59",
+			}))
+			.await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_state_value_proof_endpoint() -> Result<(), anyhow::Error> {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+		let state_key = hex::encode(MockDb::STATE_KEY);
+
+		for version in ["v1", "v2"] {
+			let path = format!("/movement/{version}/state-value-proof/0?state_key=0x{state_key}");
+			let response = client.get(path).send().await;
+			response.assert_status_is_ok();
+			let body = response.0.into_body().into_string().await?;
+			let proof: StateValueProofResponse = serde_json::from_str(&body)?;
+			assert_eq!(proof.state_value, Some(StateValue::from(vec![7; 4])));
+			let ledger_info = proof.proof.state_proof.latest_ledger_info();
+			proof.verify(ledger_info.transaction_accumulator_hash())?;
+		}
+
+		let response = client.get("/movement/v1/state-value-proof/0?state_key=0x02").send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+		response
+			.assert_json(serde_json::json!({ "error": "Invalid state key 0x02" }))
+			.await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_transaction_endpoint() -> Result<(), anyhow::Error> {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+
+		for path in ["/movement/v1/transaction/20", "/movement/v2/transaction/20"] {
+			let response = client.get(path).send().await;
+			response.assert_status_is_ok();
+			let body = response.0.into_body().into_string().await?;
+			let txn: TransactionResponse = serde_json::from_str(&body)?;
+			assert_eq!(txn.version, 20);
+			assert_eq!(txn.transaction, Transaction::StateCheckpoint(HashValue::zero()));
+			assert_eq!(txn.proof.transaction_info(), &MockDb::transaction_info(20));
+		}
+
+		let response = client.get("/movement/v1/transaction/60").send().await;
+		response.assert_status(StatusCode::NOT_FOUND);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_account_proof_endpoint() -> Result<(), anyhow::Error> {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+
+		for version in ["v1", "v2"] {
+			let path = format!("/movement/{version}/account-proof/0?address=0xa11ce");
+			let response = client.get(path).send().await;
+			response.assert_status_is_ok();
+			let body = response.0.into_body().into_string().await?;
+			let proof: AccountProofResponse = serde_json::from_str(&body)?;
+			assert_eq!(proof.address, AccountAddress::from_hex_literal("0xa11ce")?);
+			// the mock state holds no account
+			assert!(proof.account()?.is_none());
+			let ledger_info = proof.proof.proof.state_proof.latest_ledger_info();
+			proof.verify(ledger_info.transaction_accumulator_hash())?;
+		}
+
+		let response = client.get("/movement/v1/account-proof/0?address=alice").send().await;
+		response.assert_status(StatusCode::BAD_REQUEST);
+		response
+			.assert_json(serde_json::json!({ "error": "Invalid address alice" }))
+			.await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_state_proofs_endpoint() -> Result<(), anyhow::Error> {
+		let client = TestClient::new(mock_service(MockDb::default()).create_routes());
+
+		let page = |path: &'static str| {
+			let client = &client;
+			async move {
+				let response = client.get(path).send().await;
+				response.assert_status_is_ok();
+				let body = response.0.into_body().into_string().await?;
+				let page: Page<StateProofResponse> = serde_json::from_str(&body)?;
+				let tx_indices: Vec<_> = page.items.iter().map(|proof| proof.tx_index).collect();
+				Ok::<_, anyhow::Error>((tx_indices, page.next))
+			}
+		};
+
+		// each proof is for the last version of its block, against the same ledger info
+		assert_eq!(
+			page("/movement/v1/state-proofs?start=1&limit=2").await?,
+			(vec![19, 29], Some(3))
+		);
+		assert_eq!(page("/movement/v2/state-proofs?start=3&end=4").await?, (vec![39, 49], None));
+		// the chain tip cuts the page short
+		assert_eq!(page("/movement/v1/state-proofs?start=4").await?, (vec![49, 59], None));
+		assert_eq!(page("/movement/v1/state-proofs?start=9").await?, (vec![], None));
+
+		let response = client.get("/movement/v1/state-proofs?start=0").send().await;
+		let body = response.0.into_body().into_string().await?;
+		let page: Page<StateProofResponse> = serde_json::from_str(&body)?;
+		assert_eq!(page.items.len(), 6);
+		for proof in &page.items {
+			assert_eq!(proof.state_proof.latest_ledger_info().version(), 59);
+		}
+
+		client
+			.get("/movement/v1/state-proofs?start=3&end=2")
+			.send()
+			.await
+			.assert_status(StatusCode::BAD_REQUEST);
+		Ok(())
 	}
 }
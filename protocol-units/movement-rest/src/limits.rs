@@ -0,0 +1,133 @@
+use crate::MovementRestError;
+use poem::{async_trait, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Middleware bounding the handlers that read the node's database, so bursts of requests for
+/// expensive proofs can't overload the node.
+///
+/// At most `max_in_flight` requests run at once across all routes it wraps, further requests
+/// are rejected with 503 rather than queued. Requests taking longer than `timeout` are answered
+/// with 503 as well. The handlers read the database on the blocking thread pool, so the timeout
+/// fires while a read runs; the abandoned read still finishes in the background and holds its
+/// [InFlightPermit] until then, so the limit bounds the reads rather than the requests.
+#[derive(Debug, Clone)]
+pub struct RequestLimits {
+	timeout: Duration,
+	max_in_flight: usize,
+	in_flight: Arc<Semaphore>,
+}
+
+impl RequestLimits {
+	pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+	pub const DEFAULT_MAX_IN_FLIGHT: usize = 64;
+
+	pub fn new(timeout: Duration, max_in_flight: usize) -> Self {
+		Self { timeout, max_in_flight, in_flight: Arc::new(Semaphore::new(max_in_flight)) }
+	}
+
+	pub fn timeout(&self) -> Duration {
+		self.timeout
+	}
+
+	pub fn max_in_flight(&self) -> usize {
+		self.max_in_flight
+	}
+}
+
+impl Default for RequestLimits {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_TIMEOUT, Self::DEFAULT_MAX_IN_FLIGHT)
+	}
+}
+
+impl<E: Endpoint> Middleware<E> for RequestLimits {
+	type Output = RequestLimitsEndpoint<E>;
+
+	fn transform(&self, ep: E) -> Self::Output {
+		RequestLimitsEndpoint {
+			inner: ep,
+			timeout: self.timeout,
+			in_flight: self.in_flight.clone(),
+		}
+	}
+}
+
+pub struct RequestLimitsEndpoint<E> {
+	inner: E,
+	timeout: Duration,
+	in_flight: Arc<Semaphore>,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for RequestLimitsEndpoint<E> {
+	type Output = Response;
+
+	async fn call(&self, mut req: Request) -> Result<Self::Output> {
+		let permit = self.in_flight.clone().try_acquire_owned().map_err(|_| {
+			MovementRestError::Unavailable("Too many requests in flight".to_string())
+		})?;
+		req.extensions_mut().insert(InFlightPermit { _permit: Arc::new(permit) });
+		match tokio::time::timeout(self.timeout, self.inner.call(req)).await {
+			Ok(response) => response.map(IntoResponse::into_response),
+			Err(_) => Err(MovementRestError::Unavailable(format!(
+				"Request timed out after {:?}",
+				self.timeout
+			))
+			.into()),
+		}
+	}
+}
+
+/// A slot of the in-flight budget of [RequestLimits], handed to the handler in the request data.
+///
+/// The slot is released once the request and every clone are dropped, so handlers move a clone
+/// into the work they spawn, e.g., a database read outliving a timed out request.
+#[derive(Debug, Clone)]
+pub struct InFlightPermit {
+	_permit: Arc<OwnedSemaphorePermit>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use poem::{get, handler, http::StatusCode, test::TestClient, web::Path, EndpointExt, Route};
+
+	#[handler]
+	async fn sleep(Path(millis): Path<u64>) -> &'static str {
+		tokio::time::sleep(Duration::from_millis(millis)).await;
+		"OK"
+	}
+
+	#[tokio::test]
+	async fn test_request_limits() {
+		let limits = RequestLimits::new(Duration::from_millis(200), 1);
+		let client =
+			Arc::new(TestClient::new(Route::new().at("/sleep/:millis", get(sleep).with(limits))));
+
+		client.get("/sleep/0").send().await.assert_status_is_ok();
+
+		let response = client.get("/sleep/1000").send().await;
+		response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		response
+			.assert_json(serde_json::json!({ "error": "Request timed out after 200ms" }))
+			.await;
+
+		// a second request while one is in flight is rejected
+		let slow = tokio::spawn({
+			let client = client.clone();
+			async move { client.get("/sleep/100").send().await.0.status() }
+		});
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		let response = client.get("/sleep/0").send().await;
+		response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+		response
+			.assert_json(serde_json::json!({ "error": "Too many requests in flight" }))
+			.await;
+		assert_eq!(slow.await.unwrap(), StatusCode::OK);
+
+		// the permit is released
+		client.get("/sleep/0").send().await.assert_status_is_ok();
+	}
+}
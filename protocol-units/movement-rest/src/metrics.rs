@@ -0,0 +1,172 @@
+use poem::{
+	async_trait, handler, http::StatusCode, web::Data, Endpoint, IntoResponse, Middleware, Request,
+	Response, Result,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the request latency histogram buckets, in seconds.
+pub const LATENCY_BUCKETS: [f64; 11] =
+	[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Debug, Default)]
+struct RouteStats {
+	statuses: BTreeMap<u16, u64>,
+	buckets: [u64; LATENCY_BUCKETS.len()],
+	sum: f64,
+	count: u64,
+}
+
+/// Request counts and latencies per route, rendered in the Prometheus text format.
+#[derive(Debug, Default)]
+pub struct Metrics {
+	routes: Mutex<BTreeMap<&'static str, RouteStats>>,
+}
+
+impl Metrics {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Records a request to `route` that completed with `status` after `elapsed`.
+	pub fn observe(&self, route: &'static str, status: StatusCode, elapsed: Duration) {
+		let mut routes = self.routes.lock().expect("metrics lock poisoned");
+		let stats = routes.entry(route).or_default();
+		*stats.statuses.entry(status.as_u16()).or_insert(0) += 1;
+		let seconds = elapsed.as_secs_f64();
+		for (bucket, bound) in stats.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+			if seconds <= bound {
+				*bucket += 1;
+			}
+		}
+		stats.sum += seconds;
+		stats.count += 1;
+	}
+
+	/// Renders the metrics in the Prometheus text exposition format.
+	pub fn render(&self) -> String {
+		let routes = self.routes.lock().expect("metrics lock poisoned");
+		let mut out = String::new();
+
+		out.push_str("# HELP movement_rest_requests_total Requests by route and status code.\n");
+		out.push_str("# TYPE movement_rest_requests_total counter\n");
+		for (route, stats) in routes.iter() {
+			for (status, count) in &stats.statuses {
+				let _ = writeln!(
+					out,
+					"movement_rest_requests_total{{route=\"{}\",status=\"{}\"}} {}",
+					route, status, count
+				);
+			}
+		}
+
+		out.push_str("# HELP movement_rest_request_duration_seconds Request latency by route.\n");
+		out.push_str("# TYPE movement_rest_request_duration_seconds histogram\n");
+		for (route, stats) in routes.iter() {
+			for (bucket, bound) in stats.buckets.iter().zip(LATENCY_BUCKETS) {
+				let _ = writeln!(
+					out,
+					"movement_rest_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+					route, bound, bucket
+				);
+			}
+			let _ = writeln!(
+				out,
+				"movement_rest_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+				route, stats.count
+			);
+			let _ = writeln!(
+				out,
+				"movement_rest_request_duration_seconds_sum{{route=\"{}\"}} {}",
+				route, stats.sum
+			);
+			let _ = writeln!(
+				out,
+				"movement_rest_request_duration_seconds_count{{route=\"{}\"}} {}",
+				route, stats.count
+			);
+		}
+
+		out
+	}
+}
+
+/// Middleware recording the requests of a route into [Metrics].
+/// Does nothing when no metrics are configured.
+pub struct RecordMetrics {
+	metrics: Option<Arc<Metrics>>,
+	route: &'static str,
+}
+
+impl RecordMetrics {
+	pub fn new(metrics: Option<Arc<Metrics>>, route: &'static str) -> Self {
+		Self { metrics, route }
+	}
+}
+
+impl<E: Endpoint> Middleware<E> for RecordMetrics {
+	type Output = RecordMetricsEndpoint<E>;
+
+	fn transform(&self, ep: E) -> Self::Output {
+		RecordMetricsEndpoint { inner: ep, metrics: self.metrics.clone(), route: self.route }
+	}
+}
+
+pub struct RecordMetricsEndpoint<E> {
+	inner: E,
+	metrics: Option<Arc<Metrics>>,
+	route: &'static str,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for RecordMetricsEndpoint<E> {
+	type Output = Response;
+
+	async fn call(&self, req: Request) -> Result<Self::Output> {
+		let metrics = match &self.metrics {
+			Some(metrics) => metrics,
+			None => return self.inner.call(req).await.map(IntoResponse::into_response),
+		};
+
+		let start = Instant::now();
+		let response = self.inner.get_response(req).await;
+		metrics.observe(self.route, response.status(), start.elapsed());
+		Ok(response)
+	}
+}
+
+#[handler]
+pub async fn prometheus_metrics(metrics: Data<&Arc<Metrics>>) -> Response {
+	metrics.render().with_content_type("text/plain; version=0.0.4").into_response()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_render() {
+		let metrics = Metrics::new();
+		metrics.observe("/health", StatusCode::OK, Duration::from_millis(20));
+		metrics.observe("/health", StatusCode::OK, Duration::from_millis(200));
+		metrics.observe("/health", StatusCode::NOT_FOUND, Duration::from_millis(1));
+
+		let rendered = metrics.render();
+		assert!(
+			rendered.contains("movement_rest_requests_total{route=\"/health\",status=\"200\"} 2\n")
+		);
+		assert!(
+			rendered.contains("movement_rest_requests_total{route=\"/health\",status=\"404\"} 1\n")
+		);
+		assert!(rendered.contains(
+			"movement_rest_request_duration_seconds_bucket{route=\"/health\",le=\"0.025\"} 2\n"
+		));
+		assert!(rendered.contains(
+			"movement_rest_request_duration_seconds_bucket{route=\"/health\",le=\"+Inf\"} 3\n"
+		));
+		assert!(rendered
+			.contains("movement_rest_request_duration_seconds_count{route=\"/health\"} 3\n"));
+	}
+}
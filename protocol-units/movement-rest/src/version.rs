@@ -0,0 +1,102 @@
+use crate::{FormatQuery, MovementRestError, ResponseFormat};
+use poem::http::{header, HeaderMap};
+
+/// The version of the `/movement` routes a request was made to.
+///
+/// The versions serve the same routes and data, they differ in how responses are rendered:
+/// - v1 renders `state-root-hash/:blockheight` as the raw hex literal, or as JSON with
+///   `?format=json`, and all other routes as JSON.
+/// - v2 negotiates the `state-root-hash/:blockheight` format from the `Accept` header,
+///   JSON or the `text/plain` hex literal, and renders all other routes as JSON.
+///
+/// v1 is frozen so existing clients keep working: its default responses stay byte-compatible,
+/// schema changes only go into v2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+	V1,
+	V2,
+}
+
+impl ApiVersion {
+	/// The format to render a response with which has a raw representation.
+	///
+	/// v1 reads the `format` query and defaults to the raw format, v2 takes the first media range
+	/// of the `Accept` header it can serve and fails with a 406 if there is none.
+	pub fn response_format(
+		&self,
+		query: &FormatQuery,
+		headers: &HeaderMap,
+	) -> Result<ResponseFormat, MovementRestError> {
+		match self {
+			ApiVersion::V1 => Ok(query.format.unwrap_or(ResponseFormat::Raw)),
+			ApiVersion::V2 => negotiate_format(headers),
+		}
+	}
+}
+
+fn negotiate_format(headers: &HeaderMap) -> Result<ResponseFormat, MovementRestError> {
+	let mut media_ranges = headers
+		.get_all(header::ACCEPT)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.filter_map(|media_range| {
+			let mut parts = media_range.split(';').map(str::trim);
+			let media_type = parts.next()?.to_ascii_lowercase();
+			// a zero quality rules the media range out
+			let excluded = parts.any(|parameter| {
+				parameter.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+			});
+			(!excluded && !media_type.is_empty()).then_some(media_type)
+		})
+		.peekable();
+	if media_ranges.peek().is_none() {
+		return Ok(ResponseFormat::Json);
+	}
+	media_ranges
+		.find_map(|media_type| match media_type.as_str() {
+			"application/json" | "application/*" | "*/*" => Some(ResponseFormat::Json),
+			"text/plain" | "text/*" => Some(ResponseFormat::Raw),
+			_ => None,
+		})
+		.ok_or_else(|| {
+			MovementRestError::NotAcceptable(
+				"Acceptable media types are application/json and text/plain".to_string(),
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use poem::http::HeaderValue;
+
+	fn accept(value: &str) -> HeaderMap {
+		let mut headers = HeaderMap::new();
+		headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+		headers
+	}
+
+	#[test]
+	fn test_negotiate_format() {
+		let raw = FormatQuery { format: Some(ResponseFormat::Raw) };
+		// v1 ignores the header and defaults to the raw format
+		assert_eq!(
+			ApiVersion::V1.response_format(&raw, &accept("application/json")).unwrap(),
+			ResponseFormat::Raw
+		);
+		let unset = FormatQuery { format: None };
+		assert_eq!(
+			ApiVersion::V1.response_format(&unset, &accept("application/json")).unwrap(),
+			ResponseFormat::Raw
+		);
+
+		let v2 = |headers: &HeaderMap| ApiVersion::V2.response_format(&raw, headers);
+		assert_eq!(v2(&HeaderMap::new()).unwrap(), ResponseFormat::Json);
+		assert_eq!(v2(&accept("*/*")).unwrap(), ResponseFormat::Json);
+		assert_eq!(v2(&accept("text/plain")).unwrap(), ResponseFormat::Raw);
+		assert_eq!(v2(&accept("text/html, text/*;q=0.5")).unwrap(), ResponseFormat::Raw);
+		assert_eq!(v2(&accept("application/json;q=0, text/plain")).unwrap(), ResponseFormat::Raw);
+		assert!(matches!(v2(&accept("text/html")), Err(MovementRestError::NotAcceptable(_))));
+	}
+}
@@ -1,11 +1,13 @@
+use aptos_types::account_address::AccountAddress;
 use mempool_util::{MempoolBlockOperations, MempoolTransactionOperations};
 pub use move_rocks::RocksdbMempool;
 pub use movement_types::{
-	block::{self, Block},
+	block::{self, Block, BlockMetadata},
 	transaction::{self, Transaction},
 };
 pub use sequencing_util::Sequencer;
 use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{path::PathBuf, sync::Arc};
 use tokio::sync::RwLock;
 
@@ -17,6 +19,8 @@ pub struct Memseq<T: MempoolBlockOperations + MempoolTransactionOperations> {
 	pub parent_block: Arc<RwLock<block::Id>>,
 	// this value should not be changed after initialization
 	building_time_ms: u64,
+	// recorded in the metadata of every built block, if known
+	proposer: Option<AccountAddress>,
 }
 
 impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
@@ -26,7 +30,7 @@ impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
 		parent_block: Arc<RwLock<block::Id>>,
 		building_time_ms: u64,
 	) -> Self {
-		Self { mempool, block_size, parent_block, building_time_ms }
+		Self { mempool, block_size, parent_block, building_time_ms, proposer: None }
 	}
 
 	pub fn with_block_size(mut self, block_size: u32) -> Self {
@@ -39,9 +43,20 @@ impl<T: MempoolBlockOperations + MempoolTransactionOperations> Memseq<T> {
 		self
 	}
 
+	pub fn with_proposer(mut self, proposer: AccountAddress) -> Self {
+		self.proposer = Some(proposer);
+		self
+	}
+
 	pub fn building_time_ms(&self) -> u64 {
 		self.building_time_ms
 	}
+
+	/// Metadata for a block built now, the sequencer has no epochs or rounds.
+	fn block_metadata(&self) -> Result<BlockMetadata, anyhow::Error> {
+		let timestamp_micros = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+		Ok(BlockMetadata::new(0, 0, timestamp_micros, self.proposer))
+	}
 }
 
 impl Memseq<RocksdbMempool> {
@@ -99,9 +114,10 @@ impl<T: MempoolBlockOperations + MempoolTransactionOperations> Sequencer for Mem
 		if transactions.is_empty() {
 			Ok(None)
 		} else {
+			let metadata = self.block_metadata()?;
 			let new_block = {
 				let parent_block = self.parent_block.read().await.clone();
-				Block::new(Default::default(), parent_block, BTreeSet::from_iter(transactions))
+				Block::new(metadata, parent_block, BTreeSet::from_iter(transactions))
 			};
 
 			// update the parent block
@@ -317,6 +333,25 @@ pub mod test {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_block_metadata() -> Result<(), anyhow::Error> {
+		let dir = tempdir()?;
+		let path = dir.path().to_path_buf();
+		let memseq = Memseq::try_move_rocks(path, 128, 250)?.with_proposer(AccountAddress::ONE);
+
+		let before = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+		memseq.publish(Transaction::new(vec![1, 2, 3], 0)).await?;
+		let block = memseq.wait_for_next_block().await?;
+		let block = block.ok_or(anyhow::anyhow!("Block not found"))?;
+		let after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+
+		let metadata = block.metadata();
+		assert!((before..=after).contains(&metadata.timestamp_micros()));
+		assert_eq!(metadata.proposer(), Some(AccountAddress::ONE));
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_respects_size() -> Result<(), anyhow::Error> {
 		let dir = tempdir()?;
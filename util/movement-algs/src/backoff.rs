@@ -0,0 +1,184 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with jitter.
+///
+/// The undelayed sequence is `base * factor^attempt`, capped at `max`.
+/// Jitter spreads each delay uniformly within `delay * (1 ± jitter)`, still capped at `max`,
+/// so that many clients retrying at once don't synchronize.
+///
+/// Used by the indexer stream to reconnect, by the bridge client to poll transfer states and by
+/// the bridge test node to poll its REST API until it serves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+	pub base: Duration,
+	pub max: Duration,
+	factor: f64,
+	jitter: f64,
+	attempt: u32,
+}
+
+impl Default for Backoff {
+	fn default() -> Self {
+		Self::new(Duration::from_millis(100), Duration::from_secs(10))
+	}
+}
+
+impl Backoff {
+	pub const DEFAULT_FACTOR: f64 = 2.0;
+	pub const DEFAULT_JITTER: f64 = 0.1;
+
+	pub fn new(base: Duration, max: Duration) -> Self {
+		Self { base, max, factor: Self::DEFAULT_FACTOR, jitter: Self::DEFAULT_JITTER, attempt: 0 }
+	}
+
+	pub fn with_factor(mut self, factor: f64) -> Self {
+		self.factor = factor.max(1.0);
+		self
+	}
+
+	/// Sets the jitter as a fraction of the delay, clamped to `[0, 1]`.
+	pub fn with_jitter(mut self, jitter: f64) -> Self {
+		self.jitter = if jitter.is_nan() { 0.0 } else { jitter.clamp(0.0, 1.0) };
+		self
+	}
+
+	/// The growth factor of the undelayed sequence, at least 1.
+	pub fn factor(&self) -> f64 {
+		self.factor
+	}
+
+	/// The jitter as a fraction of the delay, within `[0, 1]`.
+	pub fn jitter(&self) -> f64 {
+		self.jitter
+	}
+
+	/// The number of delays handed out since the last reset.
+	pub fn attempt(&self) -> u32 {
+		self.attempt
+	}
+
+	/// Starts the sequence over, e.g., after a successful call.
+	pub fn reset(&mut self) {
+		self.attempt = 0;
+	}
+
+	/// The capped delay for an attempt, without jitter.
+	pub fn delay_for(&self, attempt: u32) -> Duration {
+		let exponent = i32::try_from(attempt).unwrap_or(i32::MAX);
+		let delay = self.base.as_secs_f64() * self.factor.powi(exponent);
+		if delay >= self.max.as_secs_f64() {
+			self.max
+		} else {
+			Duration::try_from_secs_f64(delay).unwrap_or(self.max)
+		}
+	}
+
+	/// Returns the next jittered delay and advances the attempt counter.
+	pub fn next_delay(&mut self) -> Duration {
+		self.next_delay_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Like [Backoff::next_delay], with the source of randomness supplied by the caller.
+	pub fn next_delay_with_rng<R: Rng>(&mut self, rng: &mut R) -> Duration {
+		let delay = self.delay_for(self.attempt);
+		self.attempt = self.attempt.saturating_add(1);
+
+		if self.jitter == 0.0 {
+			return delay;
+		}
+		let spread = rng.gen_range(1.0 - self.jitter, 1.0 + self.jitter);
+		// a jittered delay beyond what a duration holds is past the maximum too
+		Duration::try_from_secs_f64(delay.as_secs_f64() * spread)
+			.map_or(self.max, |delay| delay.min(self.max))
+	}
+}
+
+impl Iterator for Backoff {
+	type Item = Duration;
+
+	fn next(&mut self) -> Option<Duration> {
+		Some(self.next_delay())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_backoff_sequence_is_capped() {
+		let mut backoff =
+			Backoff::new(Duration::from_millis(100), Duration::from_millis(1000)).with_jitter(0.0);
+
+		let delays: Vec<_> = (&mut backoff).take(6).collect();
+		assert_eq!(
+			delays,
+			vec![
+				Duration::from_millis(100),
+				Duration::from_millis(200),
+				Duration::from_millis(400),
+				Duration::from_millis(800),
+				Duration::from_millis(1000),
+				Duration::from_millis(1000),
+			]
+		);
+		assert_eq!(backoff.attempt(), 6);
+
+		backoff.reset();
+		assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+
+		// very large attempts saturate at the maximum
+		assert_eq!(backoff.delay_for(u32::MAX), Duration::from_millis(1000));
+	}
+
+	#[test]
+	fn test_backoff_jitter_within_range() {
+		let jitter = 0.25;
+		let mut backoff =
+			Backoff::new(Duration::from_millis(100), Duration::from_secs(5)).with_jitter(jitter);
+
+		for _ in 0..20 {
+			backoff.reset();
+			for attempt in 0..10 {
+				let undelayed = backoff.delay_for(attempt);
+				let delay = backoff.next_delay();
+				assert!(delay >= undelayed.mul_f64(1.0 - jitter), "{:?} < {:?}", delay, undelayed);
+				assert!(delay <= undelayed.mul_f64(1.0 + jitter).min(backoff.max));
+			}
+		}
+	}
+
+	#[test]
+	fn test_backoff_parameters_are_clamped() {
+		let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(5))
+			.with_factor(0.5)
+			.with_jitter(3.0);
+		assert_eq!(backoff.factor(), 1.0);
+		assert_eq!(backoff.jitter(), 1.0);
+		for _ in 0..20 {
+			assert!(backoff.next_delay() <= Duration::from_millis(200));
+		}
+
+		let backoff = Backoff::default().with_factor(f64::NAN).with_jitter(f64::NAN);
+		assert_eq!(backoff.factor(), 1.0);
+		assert_eq!(backoff.jitter(), 0.0);
+	}
+
+	#[test]
+	fn test_backoff_never_exceeds_max() {
+		// jitter around the largest duration overflows it
+		let mut backoff = Backoff::new(Duration::MAX, Duration::MAX).with_jitter(1.0);
+		for _ in 0..20 {
+			backoff.next_delay();
+		}
+
+		let mut backoff = Backoff::new(Duration::ZERO, Duration::from_secs(1))
+			.with_factor(f64::INFINITY)
+			.with_jitter(0.5);
+		for _ in 0..20 {
+			assert!(backoff.next_delay() <= Duration::from_secs(1));
+		}
+	}
+}
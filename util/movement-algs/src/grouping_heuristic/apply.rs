@@ -1,4 +1,6 @@
-use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
 
 pub struct ToApply;
 
@@ -27,11 +29,41 @@ impl<T> GroupingHeuristic<T> for ToApply {
 	}
 }
 
+/// Adapts a fallible per-element `f` to the function the stack runners apply to each group.
+///
+/// Elements to apply become successes when `f` returns `Ok`, and failures when it returns `Err`,
+/// terminal if `is_terminal` says so for the error and instrumental otherwise. Successes and
+/// failures from earlier iterations are kept as they are. The errors themselves are dropped.
+pub fn from_result_fn<T, E>(
+	f: impl Fn(&T) -> Result<(), E>,
+	is_terminal: impl Fn(&E) -> bool,
+) -> impl Fn(GroupingOutcome<T>) -> Result<GroupingOutcome<T>, anyhow::Error> {
+	move |outcome| {
+		Ok(GroupingOutcome::new(
+			outcome
+				.into_inner()
+				.into_iter()
+				.map(|outcome| match outcome {
+					ElementalOutcome::Apply(t) => match f(&t) {
+						Ok(()) => ElementalOutcome::Success,
+						Err(e) if is_terminal(&e) => {
+							ElementalOutcome::Failure(ElementalFailure::Terminal(t))
+						}
+						Err(_) => ElementalOutcome::Failure(ElementalFailure::Instrumental(t)),
+					},
+					outcome => outcome,
+				})
+				.collect(),
+		))
+	}
+}
+
 #[cfg(test)]
 pub mod test {
 
 	use super::*;
-	use crate::grouping_heuristic::{ElementalFailure, ElementalOutcome};
+	use crate::grouping_heuristic::GroupingHeuristicStack;
+	use std::cell::Cell;
 
 	#[test]
 	fn test_to_apply() -> Result<(), anyhow::Error> {
@@ -67,4 +99,44 @@ pub mod test {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_from_result_fn() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![ToApply::boxed()]);
+		let distribution = GroupingOutcome::new_apply_distribution(vec![1, 2, 3, 4]);
+
+		// even elements fail on the first attempt, 3 always fails terminally
+		let attempts = Cell::new(0);
+		let result = stack
+			.run(
+				distribution,
+				from_result_fn(
+					|t: &u64| {
+						attempts.set(attempts.get() + 1);
+						match t {
+							3 => Err("invalid"),
+							t if t % 2 == 0 && attempts.get() <= 4 => Err("busy"),
+							_ => Ok(()),
+						}
+					},
+					|e| *e == "invalid",
+				),
+				10,
+			)
+			.await?;
+
+		assert_eq!(
+			result,
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Terminal(3)),
+				ElementalOutcome::Success,
+			])]
+		);
+		// ToApply retries every failure, the terminal one included
+		assert_eq!(attempts.get(), 7);
+
+		Ok(())
+	}
 }
@@ -48,7 +48,7 @@ where
 
 			// Try to place the current element in the last knapsack
 			let remaining = if let Some(last_knapsack) = result.last_mut() {
-				let current_weight: usize = last_knapsack.0.iter().map(|item| item.weight()).sum();
+				let current_weight: usize = last_knapsack.iter().map(|item| item.weight()).sum();
 				if current_weight + element.weight() <= self.capacity {
 					last_knapsack.0.push(element);
 					None
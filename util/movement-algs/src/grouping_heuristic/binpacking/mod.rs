@@ -2,6 +2,8 @@ pub mod first_fit_decreasing;
 pub use first_fit_decreasing::*;
 pub mod first_fit;
 pub use first_fit::*;
+pub mod weighted;
+pub use weighted::*;
 
 use crate::grouping_heuristic::{ElementalFailure, ElementalOutcome};
 
@@ -0,0 +1,188 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+
+/// What to do with an element that is heavier than the capacity on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Oversized {
+	/// Place the element in a group of its own.
+	Singleton,
+	/// Place the element in a group of its own as a terminal failure.
+	Terminal,
+}
+
+/// First Fit Decreasing binpacking over a custom weight function,
+/// e.g., the byte size or gas cost of a transaction.
+///
+/// Elements are placed into the first group with enough remaining capacity,
+/// heaviest first, so each group's total weight stays within the capacity.
+/// Successes weigh nothing.
+///
+/// Like [super::FirstFitDecreasingBinpacking], this will not preserve the original order of the elements.
+pub struct WeightedBinpacking<T> {
+	pub capacity: u64,
+	pub oversized: Oversized,
+	weight: Box<dyn Fn(&T) -> u64 + Send + Sync>,
+}
+
+impl<T> WeightedBinpacking<T> {
+	pub fn new(
+		capacity: u64,
+		oversized: Oversized,
+		weight: impl Fn(&T) -> u64 + Send + Sync + 'static,
+	) -> Self {
+		Self { capacity, oversized, weight: Box::new(weight) }
+	}
+
+	pub fn boxed(
+		capacity: u64,
+		oversized: Oversized,
+		weight: impl Fn(&T) -> u64 + Send + Sync + 'static,
+	) -> Box<Self> {
+		Box::new(Self::new(capacity, oversized, weight))
+	}
+
+	fn weigh(&self, outcome: &ElementalOutcome<T>) -> u64 {
+		match outcome {
+			ElementalOutcome::Success => 0,
+			ElementalOutcome::Apply(t)
+			| ElementalOutcome::Failure(
+				ElementalFailure::Instrumental(t) | ElementalFailure::Terminal(t),
+			) => (self.weight)(t),
+		}
+	}
+}
+
+impl<T> GroupingHeuristic<T> for WeightedBinpacking<T> {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		// Flatten and weigh all of the elements, heaviest first
+		let mut elements: Vec<_> = distribution
+			.into_iter()
+			.flat_map(|outcome| outcome.into_inner())
+			.map(|element| (self.weigh(&element), element))
+			.collect();
+		elements.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+
+		let mut bins: Vec<(u64, Vec<ElementalOutcome<T>>)> = Vec::new();
+		let mut oversized = Vec::new();
+
+		for (weight, element) in elements {
+			if weight > self.capacity {
+				oversized.push(match self.oversized {
+					Oversized::Singleton => element,
+					Oversized::Terminal => element.to_terminal(),
+				});
+				continue;
+			}
+
+			// a load that would overflow doesn't fit either
+			let fits =
+				|load: &u64| load.checked_add(weight).is_some_and(|load| load <= self.capacity);
+			match bins.iter_mut().find(|(load, _)| fits(load)) {
+				Some((load, bin)) => {
+					*load += weight;
+					bin.push(element);
+				}
+				None => bins.push((weight, vec![element])),
+			}
+		}
+
+		Ok(oversized
+			.into_iter()
+			.map(|element| GroupingOutcome::new(vec![element]))
+			.chain(bins.into_iter().map(|(_, bin)| GroupingOutcome::new(bin)))
+			.collect())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_weighted_binpacking() -> Result<(), anyhow::Error> {
+		// weigh strings by their length
+		let mut heuristic =
+			WeightedBinpacking::new(6, Oversized::Singleton, |s: &&str| s.len() as u64);
+		let distribution = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Apply("aa"),
+			ElementalOutcome::Apply("bbbb"),
+			ElementalOutcome::Success,
+			ElementalOutcome::Apply("ccc"),
+			ElementalOutcome::Apply("ddd"),
+		])];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		let should_be = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply("bbbb"),
+				ElementalOutcome::Apply("aa"),
+				ElementalOutcome::Success,
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply("ccc"),
+				ElementalOutcome::Apply("ddd"),
+			]),
+		];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_weighted_binpacking_oversized() -> Result<(), anyhow::Error> {
+		let distribution = || {
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(2u64),
+				ElementalOutcome::Apply(12),
+				ElementalOutcome::Apply(3),
+			])]
+		};
+
+		let mut singleton = WeightedBinpacking::new(5, Oversized::Singleton, |w: &u64| *w);
+		assert_eq!(
+			singleton.distribute(distribution())?,
+			vec![
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(12)]),
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(3), ElementalOutcome::Apply(2)]),
+			]
+		);
+
+		let mut terminal = WeightedBinpacking::new(5, Oversized::Terminal, |w: &u64| *w);
+		assert_eq!(
+			terminal.distribute(distribution())?,
+			vec![
+				GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Terminal(
+					12
+				))]),
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(3), ElementalOutcome::Apply(2)]),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_weighted_binpacking_load_overflow() -> Result<(), anyhow::Error> {
+		let mut heuristic = WeightedBinpacking::new(u64::MAX, Oversized::Singleton, |w: &u64| *w);
+		let distribution = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Apply(u64::MAX),
+			ElementalOutcome::Apply(1),
+		])];
+
+		assert_eq!(
+			heuristic.distribute(distribution)?,
+			vec![
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(u64::MAX)]),
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(1)]),
+			]
+		);
+
+		Ok(())
+	}
+}
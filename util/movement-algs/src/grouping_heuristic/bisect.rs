@@ -0,0 +1,135 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+
+/// Halves every group containing an instrumental failure,
+/// so subsequent iterations narrow down the failing element, e.g., a single poison transaction in a batch.
+///
+/// The instrumental failures in both halves are converted to applies so they are tried again.
+/// A failed group of a single element is not split further, its failure is marked terminal instead.
+/// Groups without instrumental failures are left as they are.
+#[derive(Default)]
+pub struct Bisect;
+
+impl Bisect {
+	pub fn new() -> Self {
+		Bisect
+	}
+
+	pub fn boxed() -> Box<Self> {
+		Box::new(Bisect)
+	}
+}
+
+impl<T> GroupingHeuristic<T> for Bisect {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let mut result = Vec::new();
+
+		for outcome in distribution {
+			if !outcome.iter().any(|outcome| {
+				matches!(outcome, ElementalOutcome::Failure(ElementalFailure::Instrumental(_)))
+			}) {
+				result.push(outcome);
+				continue;
+			}
+
+			if outcome.len() == 1 {
+				result.push(outcome.all_to_terminal());
+				continue;
+			}
+
+			let mut first: Vec<_> = outcome
+				.into_inner()
+				.into_iter()
+				.map(|outcome| match outcome {
+					ElementalOutcome::Failure(ElementalFailure::Instrumental(t)) => {
+						ElementalOutcome::Apply(t)
+					}
+					outcome => outcome,
+				})
+				.collect();
+			let second = first.split_off(first.len() / 2);
+			result.push(GroupingOutcome::new(first));
+			result.push(GroupingOutcome::new(second));
+		}
+
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::GroupingHeuristicStack;
+
+	#[test]
+	fn test_bisect() -> Result<(), anyhow::Error> {
+		let mut heuristic = Bisect::new();
+		let distribution = vec![
+			GroupingOutcome::new(vec![ElementalOutcome::Success, ElementalOutcome::Apply(1)]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(3)),
+				ElementalOutcome::Success,
+			]),
+			GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Instrumental(
+				4,
+			))]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Terminal(5)),
+				ElementalOutcome::Apply(6),
+			]),
+		];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		let should_be = vec![
+			GroupingOutcome::new(vec![ElementalOutcome::Success, ElementalOutcome::Apply(1)]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(2)]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(3), ElementalOutcome::Success]),
+			GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Terminal(4))]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Terminal(5)),
+				ElementalOutcome::Apply(6),
+			]),
+		];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_bisect_isolates_poison_element() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![Bisect::boxed()]);
+
+		let distribution = GroupingOutcome::new_apply_distribution((0..8).collect::<Vec<usize>>());
+
+		// a group fails as a whole if it contains the poison element
+		let result = stack
+			.run(
+				distribution,
+				|outcome| {
+					if outcome.all_done() {
+						return Ok(outcome);
+					}
+					if outcome.0.contains(&ElementalOutcome::Apply(5)) {
+						Ok(outcome.to_failures_prefer_instrumental())
+					} else {
+						Ok(GroupingOutcome::new_all_success(outcome.len()))
+					}
+				},
+				10,
+			)
+			.await?;
+
+		let failed: Vec<usize> =
+			result.into_iter().flat_map(GroupingOutcome::into_original).collect();
+		assert_eq!(failed, vec![5]);
+
+		Ok(())
+	}
+}
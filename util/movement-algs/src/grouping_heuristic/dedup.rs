@@ -0,0 +1,169 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Collapses duplicate elements within and across groups, as identified by a key function,
+/// e.g., a resubmitted transaction appearing twice in a batch.
+///
+/// The first occurrence keeps its position and is applied as usual.
+/// Later pending occurrences are marked as successes as soon as they are found, so they are
+/// treated as already handled rather than applied again.
+pub struct Dedup<T, K> {
+	key: Box<dyn Fn(&T) -> K + Send + Sync>,
+}
+
+impl<T, K> Dedup<T, K> {
+	pub fn new(key: impl Fn(&T) -> K + Send + Sync + 'static) -> Self {
+		Self { key: Box::new(key) }
+	}
+
+	pub fn boxed(key: impl Fn(&T) -> K + Send + Sync + 'static) -> Box<Self> {
+		Box::new(Self::new(key))
+	}
+
+	fn key(&self, outcome: &ElementalOutcome<T>) -> Option<K> {
+		match outcome {
+			ElementalOutcome::Success => None,
+			ElementalOutcome::Apply(t)
+			| ElementalOutcome::Failure(
+				ElementalFailure::Instrumental(t) | ElementalFailure::Terminal(t),
+			) => Some((self.key)(t)),
+		}
+	}
+}
+
+impl<T, K> GroupingHeuristic<T> for Dedup<T, K>
+where
+	K: Eq + Hash,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let mut seen = HashSet::new();
+		let distribution = distribution
+			.into_iter()
+			.map(|outcome| {
+				GroupingOutcome::new(
+					outcome
+						.into_inner()
+						.into_iter()
+						.map(|outcome| match (self.key(&outcome), &outcome) {
+							// pending duplicates are already handled by their first occurrence
+							(
+								Some(key),
+								ElementalOutcome::Apply(_)
+								| ElementalOutcome::Failure(ElementalFailure::Instrumental(_)),
+							) if seen.contains(&key) => ElementalOutcome::Success,
+							(Some(key), _) => {
+								seen.insert(key);
+								outcome
+							}
+							(None, _) => outcome,
+						})
+						.collect(),
+				)
+			})
+			.collect();
+
+		Ok(distribution)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::GroupingHeuristicStack;
+
+	#[test]
+	fn test_dedup() -> Result<(), anyhow::Error> {
+		let mut heuristic = Dedup::new(|t: &u64| *t);
+		let distribution = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Apply(2),
+				ElementalOutcome::Apply(1),
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(3),
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(3),
+				ElementalOutcome::Failure(ElementalFailure::Terminal(3)),
+			]),
+		];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		// only the first occurrences are applied, pending duplicates succeed in place
+		let should_be = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Apply(2),
+				ElementalOutcome::Success,
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(3),
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Terminal(3)),
+			]),
+		];
+		assert_eq!(distribution, should_be);
+
+		// nothing is remembered across distributions
+		let distribution = heuristic.distribute(vec![GroupingOutcome::new_apply(vec![1])])?;
+		assert_eq!(distribution, vec![GroupingOutcome::new_apply(vec![1])]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_dedup_duplicates_succeed_right_away() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![Dedup::boxed(|t: &u64| *t)]);
+		let distribution = GroupingOutcome::new_apply_distribution(vec![1, 2, 1, 2]);
+
+		// 1 fails terminally, 2 succeeds
+		let result = stack
+			.run(
+				distribution,
+				|outcome| {
+					Ok(GroupingOutcome::new(
+						outcome
+							.into_inner()
+							.into_iter()
+							.map(|outcome| match outcome {
+								ElementalOutcome::Apply(1) => {
+									ElementalOutcome::Failure(ElementalFailure::Terminal(1))
+								}
+								_ => ElementalOutcome::Success,
+							})
+							.collect(),
+					))
+				},
+				2,
+			)
+			.await?;
+
+		let outcomes: Vec<_> = result.into_iter().flat_map(GroupingOutcome::into_inner).collect();
+		assert_eq!(
+			outcomes,
+			vec![
+				ElementalOutcome::Failure(ElementalFailure::Terminal(1)),
+				ElementalOutcome::Success,
+				ElementalOutcome::Success,
+				ElementalOutcome::Success,
+			]
+		);
+
+		Ok(())
+	}
+}
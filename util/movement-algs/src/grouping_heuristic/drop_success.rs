@@ -20,7 +20,7 @@ impl<T> GroupingHeuristic<T> for DropSuccess {
 		// remove all of the success outcomes
 		let distribution = distribution
 			.into_iter()
-			.filter(|outcome| outcome.0.iter().any(|outcome| !outcome.is_success()))
+			.filter(|outcome| outcome.iter().any(|outcome| !outcome.is_success()))
 			.collect::<Vec<_>>();
 
 		Ok(distribution)
@@ -0,0 +1,167 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Counts the instrumental failures of each element and forces an element that failed
+/// `max_attempts` times to a terminal failure, so that the stack runners complete instead of
+/// running into their iteration cap.
+///
+/// Counts are keyed by element and only kept while the element is pending, i.e., still applied
+/// or failing instrumentally.
+pub struct MaxAttempts<T> {
+	max_attempts: usize,
+	failures: HashMap<T, usize>,
+}
+
+impl<T> MaxAttempts<T> {
+	pub fn new(max_attempts: usize) -> Self {
+		Self { max_attempts, failures: HashMap::new() }
+	}
+
+	pub fn boxed(max_attempts: usize) -> Box<Self> {
+		Box::new(Self::new(max_attempts))
+	}
+
+	/// The number of instrumental failures after which an element is terminal.
+	pub fn max_attempts(&self) -> usize {
+		self.max_attempts
+	}
+}
+
+impl<T> MaxAttempts<T>
+where
+	T: Clone + Eq + Hash,
+{
+	/// Returns the number of instrumental failures counted for the element.
+	pub fn failures(&self, element: &T) -> usize {
+		self.failures.get(element).copied().unwrap_or(0)
+	}
+
+	/// Counts an instrumental failure and carries over the counts of pending elements from
+	/// `previous`, the counts before this distribution.
+	fn count(
+		&mut self,
+		outcome: ElementalOutcome<T>,
+		previous: &mut HashMap<T, usize>,
+	) -> ElementalOutcome<T> {
+		match outcome {
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(t)) => {
+				let failures = previous.remove(&t).unwrap_or(0) + 1;
+				if failures >= self.max_attempts {
+					ElementalOutcome::Failure(ElementalFailure::Terminal(t))
+				} else {
+					self.failures.insert(t.clone(), failures);
+					ElementalOutcome::Failure(ElementalFailure::Instrumental(t))
+				}
+			}
+			ElementalOutcome::Apply(t) => {
+				if let Some((key, failures)) = previous.remove_entry(&t) {
+					self.failures.insert(key, failures);
+				}
+				ElementalOutcome::Apply(t)
+			}
+			outcome => outcome,
+		}
+	}
+}
+
+impl<T> GroupingHeuristic<T> for MaxAttempts<T>
+where
+	T: Clone + Eq + Hash,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		// elements missing from the distribution are no longer pending
+		let mut previous = std::mem::take(&mut self.failures);
+		let distribution = distribution
+			.into_iter()
+			.map(|outcome| {
+				GroupingOutcome::new(
+					outcome
+						.into_inner()
+						.into_iter()
+						.map(|outcome| self.count(outcome, &mut previous))
+						.collect(),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		Ok(distribution)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::GroupingHeuristicStack;
+
+	#[test]
+	fn test_max_attempts_forces_terminal() -> Result<(), anyhow::Error> {
+		let mut heuristic = MaxAttempts::new(2);
+		let failing = || {
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+				ElementalOutcome::Apply(2),
+			])]
+		};
+
+		assert_eq!(heuristic.distribute(failing())?, failing());
+		assert_eq!(heuristic.failures(&1), 1);
+
+		assert_eq!(
+			heuristic.distribute(failing())?,
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Terminal(1)),
+				ElementalOutcome::Apply(2),
+			])]
+		);
+		// terminal elements are no longer counted
+		assert_eq!(heuristic.failures(&1), 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_max_attempts_forgets_elements_no_longer_pending() -> Result<(), anyhow::Error> {
+		let mut heuristic = MaxAttempts::new(3);
+		heuristic.distribute(vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+		])])?;
+
+		// 1 succeeded, 2 is applied again
+		heuristic.distribute(vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Apply(2),
+		])])?;
+		assert_eq!(heuristic.failures(&1), 0);
+		assert_eq!(heuristic.failures(&2), 1);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_max_attempts_completes_stack() -> Result<(), anyhow::Error> {
+		// every application fails instrumentally
+		let func = |outcome: GroupingOutcome<usize>| {
+			Ok::<_, anyhow::Error>(outcome.to_failures_prefer_instrumental())
+		};
+
+		let mut stack = GroupingHeuristicStack::new(vec![MaxAttempts::boxed(3)]);
+		let result =
+			stack.run(GroupingOutcome::new_apply_distribution(vec![0, 1]), func, 10).await?;
+		assert!(result.iter().all(|outcome| outcome.all_done()));
+
+		// without the cap, the elements fail instrumentally until the iteration cap
+		let mut stack = GroupingHeuristicStack::<usize>::new(vec![]);
+		let result = stack.run(GroupingOutcome::new_apply_distribution(vec![0, 1]), func, 10).await;
+		assert!(result.is_err());
+
+		Ok(())
+	}
+}
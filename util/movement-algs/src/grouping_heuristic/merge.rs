@@ -0,0 +1,97 @@
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+
+/// Coalesces consecutive groups as long as the merged group holds at most `size` elements,
+/// preserving element order. Groups already larger than `size` are left as they are.
+///
+/// This is the inverse of [super::chunking::Chunking].
+pub struct Merge {
+	pub size: usize,
+}
+
+impl Merge {
+	pub fn new(size: usize) -> Self {
+		Self { size }
+	}
+
+	pub fn boxed(size: usize) -> Box<Self> {
+		Box::new(Self::new(size))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for Merge {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let mut merged: Vec<GroupingOutcome<T>> = Vec::new();
+
+		for outcome in distribution {
+			match merged.last_mut() {
+				Some(last) if last.len() + outcome.len() <= self.size => {
+					last.0.extend(outcome.into_inner());
+				}
+				_ => merged.push(outcome),
+			}
+		}
+
+		Ok(merged)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::{chunking::Chunking, ElementalOutcome};
+
+	#[test]
+	fn test_merge() -> Result<(), anyhow::Error> {
+		let mut heuristic = Merge::new(3);
+		let distribution = vec![
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(1)]),
+			GroupingOutcome::new(vec![ElementalOutcome::Success, ElementalOutcome::Apply(2)]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(3)]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(4),
+				ElementalOutcome::Apply(5),
+				ElementalOutcome::Apply(6),
+				ElementalOutcome::Apply(7),
+			]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(8)]),
+		];
+
+		let distribution = heuristic.distribute(distribution)?;
+
+		let should_be = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(2),
+			]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(3)]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(4),
+				ElementalOutcome::Apply(5),
+				ElementalOutcome::Apply(6),
+				ElementalOutcome::Apply(7),
+			]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(8)]),
+		];
+		assert_eq!(distribution, should_be);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_merge_inverts_chunking() -> Result<(), anyhow::Error> {
+		let original = vec![GroupingOutcome::new_apply((0..6).collect::<Vec<usize>>())];
+
+		let chunked = Chunking::new(2).distribute(original.clone())?;
+		assert_eq!(chunked.len(), 3);
+
+		let merged = Merge::new(6).distribute(chunked)?;
+		assert_eq!(merged, original);
+
+		Ok(())
+	}
+}
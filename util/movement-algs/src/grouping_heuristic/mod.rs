@@ -1,11 +1,22 @@
 pub mod apply;
 pub mod binpacking;
+pub mod bisect;
 pub mod chunking;
+pub mod dedup;
 pub mod drop_success;
+pub mod max_attempts;
+pub mod merge;
+pub mod partition;
+pub mod rate_limit;
+pub mod retry;
 pub mod skip;
 pub mod splitting;
 
-use std::fmt::Debug;
+use futures::future;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
 /// A failure type for a single member of the heuristically formed group.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -58,6 +69,14 @@ impl<T> ElementalFailure<T> {
 			ElementalFailure::Terminal(t) => t,
 		}
 	}
+
+	/// Maps the element, keeping the instrumental or terminal status.
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ElementalFailure<U> {
+		match self {
+			ElementalFailure::Instrumental(t) => ElementalFailure::Instrumental(f(t)),
+			ElementalFailure::Terminal(t) => ElementalFailure::Terminal(f(t)),
+		}
+	}
 }
 
 /// An outcome for a single member of the heuristically formed group.
@@ -156,6 +175,15 @@ impl<T> ElementalOutcome<T> {
 			ElementalOutcome::Failure(f) => ElementalOutcome::Apply(f.into_inner()),
 		}
 	}
+
+	/// Maps the element, keeping the outcome.
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ElementalOutcome<U> {
+		match self {
+			ElementalOutcome::Apply(t) => ElementalOutcome::Apply(f(t)),
+			ElementalOutcome::Success => ElementalOutcome::Success,
+			ElementalOutcome::Failure(failure) => ElementalOutcome::Failure(failure.map(f)),
+		}
+	}
 }
 
 /// The outcomes for a particular group in a grouping heuristic.
@@ -221,8 +249,63 @@ impl<T> GroupingOutcome<T> {
 		self.0
 	}
 
+	/// The number of elements in the group.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> std::slice::Iter<'_, ElementalOutcome<T>> {
+		self.0.iter()
+	}
+
+	pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, ElementalOutcome<T>> {
+		self.0.iter_mut()
+	}
+
+	/// Maps each element, keeping the outcomes, e.g., to adapt the elements between stages of a pipeline.
+	pub fn map<U>(self, f: impl Fn(T) -> U) -> GroupingOutcome<U> {
+		GroupingOutcome(self.0.into_iter().map(|outcome| outcome.map(&f)).collect())
+	}
+
+	/// Maps each element with a fallible `f`, e.g., decoding it. `f` hands a failed element back
+	/// with its error, so elements aren't cloned.
+	///
+	/// Mapped elements keep their outcome as `Ok`. Elements `f` fails on become terminal failures
+	/// carrying the original element and the error, so they are dropped from further iterations
+	/// rather than aborting the run.
+	pub fn try_map<U, E>(
+		self,
+		f: impl Fn(T) -> Result<U, (T, E)>,
+	) -> GroupingOutcome<Result<U, (T, E)>> {
+		let terminal = |failed| ElementalOutcome::Failure(ElementalFailure::Terminal(Err(failed)));
+		GroupingOutcome(
+			self.0
+				.into_iter()
+				.map(|outcome| match outcome {
+					ElementalOutcome::Apply(t) => match f(t) {
+						Ok(u) => ElementalOutcome::Apply(Ok(u)),
+						Err(failed) => terminal(failed),
+					},
+					ElementalOutcome::Success => ElementalOutcome::Success,
+					ElementalOutcome::Failure(ElementalFailure::Instrumental(t)) => match f(t) {
+						Ok(u) => ElementalOutcome::Failure(ElementalFailure::Instrumental(Ok(u))),
+						Err(failed) => terminal(failed),
+					},
+					ElementalOutcome::Failure(ElementalFailure::Terminal(t)) => {
+						ElementalOutcome::Failure(ElementalFailure::Terminal(f(t)))
+					}
+				})
+				.collect(),
+		)
+	}
+
 	/// Converts a grouping to a Vec<T>, i.e., a collection of the original type without outcome wrappers.
-	/// Drops success elemental outcomes.
+	/// Drops success elemental outcomes, which carry no element. This includes the elements skipped
+	/// by [skip::SkipWhere], recover those with [GroupingOutcome::into_original_with_success].
 	pub fn into_original(self) -> Vec<T> {
 		// Collect the outcomes
 		self.0
@@ -237,6 +320,33 @@ impl<T> GroupingOutcome<T> {
 			})
 			.collect()
 	}
+
+	/// Converts a grouping to a Vec<T> like [GroupingOutcome::into_original], but keeps successes.
+	/// Success outcomes carry no element, so they are restored from `original`,
+	/// which must be the elements this grouping was formed from, in the same positions.
+	pub fn into_original_with_success(self, original: &[T]) -> Result<Vec<T>, anyhow::Error>
+	where
+		T: Clone,
+	{
+		if original.len() != self.0.len() {
+			return Err(anyhow::anyhow!(
+				"Expected {} original elements, got {}",
+				self.0.len(),
+				original.len()
+			));
+		}
+
+		Ok(self
+			.0
+			.into_iter()
+			.zip(original)
+			.map(|(outcome, original)| match outcome {
+				ElementalOutcome::Apply(t) => t,
+				ElementalOutcome::Success => original.clone(),
+				ElementalOutcome::Failure(failure) => failure.into_inner(),
+			})
+			.collect())
+	}
 }
 
 impl<T> From<Vec<ElementalOutcome<T>>> for GroupingOutcome<T> {
@@ -251,6 +361,38 @@ impl<T> From<Vec<T>> for GroupingOutcome<T> {
 	}
 }
 
+impl<T> IntoIterator for GroupingOutcome<T> {
+	type Item = ElementalOutcome<T>;
+	type IntoIter = std::vec::IntoIter<ElementalOutcome<T>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a GroupingOutcome<T> {
+	type Item = &'a ElementalOutcome<T>;
+	type IntoIter = std::slice::Iter<'a, ElementalOutcome<T>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a mut GroupingOutcome<T> {
+	type Item = &'a mut ElementalOutcome<T>;
+	type IntoIter = std::slice::IterMut<'a, ElementalOutcome<T>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter_mut()
+	}
+}
+
+/// Regroups a distribution of outcomes.
+///
+/// Neither the trait nor [GroupingOutcome] require `T: Clone`, heuristics move elements between groups.
+/// Heuristics that genuinely need to duplicate elements, like [retry::Retry] keying attempts by element,
+/// declare the bound on their own implementation.
 pub trait GroupingHeuristic<T>
 where
 	T: Sized,
@@ -259,6 +401,108 @@ where
 		&mut self,
 		distribution: Vec<GroupingOutcome<T>>,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error>;
+
+	/// The name reported to observers and tracing, defaults to the type name.
+	fn name(&self) -> &'static str {
+		std::any::type_name::<Self>()
+	}
+
+	/// How long after distributing the runners wait before applying the group at `index` of the
+	/// last distribution, e.g., to respect a rate limit. Defaults to not waiting.
+	/// Only read from heuristics that [pace](GroupingHeuristic::paces).
+	fn delay(&self, _index: usize) -> Option<Duration> {
+		None
+	}
+
+	/// Whether the heuristic delays groups. As the delays index the heuristic's own distribution,
+	/// a stack only accepts a pacing heuristic as its last one.
+	fn paces(&self) -> bool {
+		false
+	}
+}
+
+/// Observes how the distribution evolves through the heuristics of a stack.
+pub trait GroupingObserver {
+	/// Called after each heuristic distributes, with the group counts before and after.
+	fn on_distribute(&self, heuristic: &str, groups_before: usize, groups_after: usize);
+}
+
+/// Returned by the stack runners when the outcomes are not all done within the iteration cap.
+/// Carries the last distribution for debugging.
+#[derive(Debug)]
+pub struct MaxIterationsExceeded<T> {
+	pub max_iterations: usize,
+	pub distribution: Vec<GroupingOutcome<T>>,
+}
+
+impl<T> fmt::Display for MaxIterationsExceeded<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "grouping did not complete within {} iterations", self.max_iterations)
+	}
+}
+
+impl<T: Debug> std::error::Error for MaxIterationsExceeded<T> {}
+
+/// The element counts of one iteration of a stack runner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IterationReport {
+	/// Groups the function was run on.
+	pub groups: usize,
+	/// Elements handed to the function as [ElementalOutcome::Apply].
+	pub applied: usize,
+	/// Elements the function returned as [ElementalOutcome::Success].
+	pub succeeded: usize,
+	/// Elements the function returned as [ElementalOutcome::Failure].
+	pub failed: usize,
+}
+
+impl IterationReport {
+	fn count<T>(
+		distribution: &[GroupingOutcome<T>],
+		f: impl Fn(&ElementalOutcome<T>) -> bool,
+	) -> usize {
+		distribution.iter().flatten().filter(|outcome| f(outcome)).count()
+	}
+}
+
+/// How a run of a [GroupingHeuristicStack] went, e.g., to tune the heuristics of the stack.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunReport {
+	/// One report per iteration, in order.
+	pub iterations: Vec<IterationReport>,
+	/// Wall time of the whole run, including distributing and pacing.
+	pub elapsed: Duration,
+}
+
+/// Reports each distribution of the wrapped heuristic to an observer.
+struct Observed<T> {
+	heuristic: Box<dyn GroupingHeuristic<T>>,
+	observer: Arc<dyn GroupingObserver>,
+}
+
+impl<T> GroupingHeuristic<T> for Observed<T> {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let groups_before = distribution.len();
+		let distribution = self.heuristic.distribute(distribution)?;
+		self.observer
+			.on_distribute(self.heuristic.name(), groups_before, distribution.len());
+		Ok(distribution)
+	}
+
+	fn name(&self) -> &'static str {
+		self.heuristic.name()
+	}
+
+	fn delay(&self, index: usize) -> Option<Duration> {
+		self.heuristic.delay(index)
+	}
+
+	fn paces(&self) -> bool {
+		self.heuristic.paces()
+	}
 }
 
 pub struct GroupingHeuristicStack<T>(pub Vec<Box<dyn GroupingHeuristic<T>>>);
@@ -268,66 +512,156 @@ impl<T> GroupingHeuristicStack<T> {
 		Self(grouping)
 	}
 
+	/// Sets an observer notified after each heuristic of the stack distributes.
+	pub fn with_observer(self, observer: Box<dyn GroupingObserver>) -> Self
+	where
+		T: 'static,
+	{
+		let observer: Arc<dyn GroupingObserver> = Arc::from(observer);
+		Self::new(
+			self.0
+				.into_iter()
+				.map(|heuristic| {
+					Box::new(Observed { heuristic, observer: observer.clone() })
+						as Box<dyn GroupingHeuristic<T>>
+				})
+				.collect(),
+		)
+	}
+
 	pub fn distribute(
 		&mut self,
 		distribution: Vec<GroupingOutcome<T>>,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		if let Some(heuristic) = self.0.iter().rev().skip(1).find(|heuristic| heuristic.paces()) {
+			return Err(anyhow::anyhow!(
+				"{} paces the groups and has to be the last heuristic of the stack",
+				heuristic.name()
+			));
+		}
+
 		let mut distribution = distribution;
 		for heuristic in &mut self.0 {
+			let _span = tracing::debug_span!("distribute", heuristic = heuristic.name()).entered();
+			let groups_before = distribution.len();
 			distribution = heuristic.distribute(distribution)?;
+			tracing::debug!(groups_before, groups_after = distribution.len(), "distributed");
 		}
 		Ok(distribution)
 	}
 
+	/// The delay of each of the `groups` of the last distribution, as requested by the last
+	/// heuristic if it paces the groups.
+	pub fn delays(&self, groups: usize) -> Vec<Duration> {
+		let pacing = self.0.last().filter(|heuristic| heuristic.paces());
+		(0..groups)
+			.map(|index| pacing.and_then(|heuristic| heuristic.delay(index)).unwrap_or_default())
+			.collect()
+	}
+}
+
+/// Waits out the delay of a group, counted from when the distribution was paced.
+async fn pace(paced_at: tokio::time::Instant, delay: Duration) {
+	if delay.is_zero() {
+		return;
+	}
+	match paced_at.checked_add(delay) {
+		Some(deadline) => tokio::time::sleep_until(deadline).await,
+		// too far out for an instant, tokio sleeps until its far future instead
+		None => tokio::time::sleep(delay).await,
+	}
+}
+
+/// The runners only need `T: Debug + Send + Sync + 'static` to return the last distribution
+/// in [MaxIterationsExceeded], they never duplicate elements.
+impl<T> GroupingHeuristicStack<T>
+where
+	T: Debug + Send + Sync + 'static,
+{
 	/// Runs the grouping heuristic synchronously.
+	/// Fails with [MaxIterationsExceeded] if not all outcomes are done after `max_iterations`.
 	pub async fn run(
 		&mut self,
 		distribution: Vec<GroupingOutcome<T>>,
 		func: impl Fn(GroupingOutcome<T>) -> Result<GroupingOutcome<T>, anyhow::Error>,
+		max_iterations: usize,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let (distribution, _) = self.run_with_report(distribution, func, max_iterations).await?;
+		Ok(distribution)
+	}
+
+	/// Runs the grouping heuristic like [GroupingHeuristicStack::run],
+	/// also reporting the element counts of each iteration and the time taken.
+	pub async fn run_with_report(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+		func: impl Fn(GroupingOutcome<T>) -> Result<GroupingOutcome<T>, anyhow::Error>,
+		max_iterations: usize,
+	) -> Result<(Vec<GroupingOutcome<T>>, RunReport), anyhow::Error> {
+		let start = Instant::now();
+		let mut report = RunReport::default();
 		let mut distribution = distribution;
-		loop {
+		for _ in 0..max_iterations {
 			// distribute
 			distribution = self.distribute(distribution)?;
+			let paced_at = tokio::time::Instant::now();
+			let delays = self.delays(distribution.len());
+			let groups = distribution.len();
+			let applied = IterationReport::count(&distribution, ElementalOutcome::is_apply);
 
 			// run the function
 			let mut new_distribution = Vec::new();
-			for outcome in distribution {
+			for (outcome, delay) in distribution.into_iter().zip(delays) {
+				pace(paced_at, delay).await;
 				new_distribution.push(func(outcome)?);
 			}
+			report.iterations.push(IterationReport {
+				groups,
+				applied,
+				succeeded: IterationReport::count(&new_distribution, ElementalOutcome::is_success),
+				failed: IterationReport::count(&new_distribution, ElementalOutcome::is_failure),
+			});
 
 			// check if we're done
 			if new_distribution.iter().all(|outcome| outcome.all_done()) {
-				return Ok(new_distribution);
+				report.elapsed = start.elapsed();
+				return Ok((new_distribution, report));
 			}
 
 			// update the distribution
 			distribution = new_distribution;
 		}
+
+		Err(MaxIterationsExceeded { max_iterations, distribution }.into())
 	}
 
 	/// Runs the grouping heuristic asynchronously, but in a sequential manner.
+	/// Fails with [MaxIterationsExceeded] if not all outcomes are done after `max_iterations`.
 	pub async fn run_async_sequential_with_metadata<F, Fut, M>(
 		&mut self,
 		mut distribution: Vec<GroupingOutcome<T>>,
 		func: F,
 		mut metadata: M,
+		max_iterations: usize,
 	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error>
 	where
 		F: Fn(usize, GroupingOutcome<T>, M) -> Fut + Send + Sync,
 		Fut: std::future::Future<Output = Result<(GroupingOutcome<T>, M), anyhow::Error>> + Send,
 	{
-		loop {
+		for _ in 0..max_iterations {
 			// distribute
 			// this can be problematic for an async runtime
 			// it would be nice to spawn blocking
 			distribution = self.distribute(distribution)?;
+			let paced_at = tokio::time::Instant::now();
+			let delays = self.delays(distribution.len());
 
 			// run the function asynchronously
 			let mut new_distribution = Vec::new();
 
 			// include index in iteration and callback
-			for (index, outcome) in distribution.into_iter().enumerate() {
+			for (index, (outcome, delay)) in distribution.into_iter().zip(delays).enumerate() {
+				pace(paced_at, delay).await;
 				let (new_outcome, new_metadata) = func(index, outcome, metadata).await?;
 				metadata = new_metadata;
 				new_distribution.push(new_outcome);
@@ -341,6 +675,62 @@ impl<T> GroupingHeuristicStack<T> {
 			// update the distribution
 			distribution = new_distribution;
 		}
+
+		Err(MaxIterationsExceeded { max_iterations, distribution }.into())
+	}
+
+	/// Runs the grouping heuristic asynchronously, running the groups of each iteration concurrently.
+	/// At most `max_concurrency` groups are in flight at once, the others queue in order
+	/// and start as soon as any group finishes. Outcomes keep the order of their groups.
+	/// Fails with [MaxIterationsExceeded] if not all outcomes are done after `max_iterations`.
+	pub async fn run_async_parallel<F, Fut>(
+		&mut self,
+		mut distribution: Vec<GroupingOutcome<T>>,
+		func: F,
+		max_concurrency: usize,
+		max_iterations: usize,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error>
+	where
+		F: Fn(usize, GroupingOutcome<T>) -> Fut + Send + Sync,
+		Fut: std::future::Future<Output = Result<GroupingOutcome<T>, anyhow::Error>> + Send,
+	{
+		if max_concurrency == 0 {
+			return Err(anyhow::anyhow!("Concurrency must be greater than zero"));
+		}
+		let semaphore = Semaphore::new(max_concurrency);
+
+		for _ in 0..max_iterations {
+			// distribute
+			distribution = self.distribute(distribution)?;
+			let paced_at = tokio::time::Instant::now();
+			let delays = self.delays(distribution.len());
+
+			// run the function concurrently, each group waits out its delay
+			// before queuing for a permit of the fair semaphore
+			let new_distribution =
+				future::try_join_all(distribution.into_iter().zip(delays).enumerate().map(
+					|(index, (outcome, delay))| {
+						let semaphore = &semaphore;
+						let func = &func;
+						async move {
+							pace(paced_at, delay).await;
+							let _permit = semaphore.acquire().await?;
+							func(index, outcome).await
+						}
+					},
+				))
+				.await?;
+
+			// check if we're done
+			if new_distribution.iter().all(|outcome| outcome.all_done()) {
+				return Ok(new_distribution);
+			}
+
+			// update the distribution
+			distribution = new_distribution;
+		}
+
+		Err(MaxIterationsExceeded { max_iterations, distribution }.into())
 	}
 }
 
@@ -348,6 +738,7 @@ impl<T> GroupingHeuristicStack<T> {
 pub mod test {
 
 	use super::chunking::Chunking;
+	use super::drop_success::DropSuccess;
 	use super::*;
 	use std::sync::Arc;
 	use tokio::sync::RwLock;
@@ -368,6 +759,7 @@ pub mod test {
 					Ok((outcome, Some(1)))
 				},
 				Some(1),
+				10,
 			)
 			.await?;
 
@@ -377,4 +769,313 @@ pub mod test {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_preserves_order() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![Chunking::boxed(1)]);
+
+		let distribution = GroupingOutcome::new_apply_distribution(vec![0, 1, 2, 3]);
+
+		let result = stack
+			.run_async_parallel(
+				distribution,
+				|index, outcome| async move {
+					// later groups finish first
+					tokio::time::sleep(std::time::Duration::from_millis(10 * (4 - index as u64)))
+						.await;
+					Ok(outcome.all_to_terminal())
+				},
+				4,
+				10,
+			)
+			.await?;
+
+		let order: Vec<usize> =
+			result.into_iter().flat_map(GroupingOutcome::into_original).collect();
+		assert_eq!(order, vec![0, 1, 2, 3]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_async_run_parallel_limits_concurrency() -> Result<(), anyhow::Error> {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		let mut stack = GroupingHeuristicStack::new(vec![Chunking::boxed(1)]);
+		let distribution = GroupingOutcome::new_apply_distribution((0..6).collect::<Vec<usize>>());
+		let in_flight = AtomicUsize::new(0);
+		let max_in_flight = AtomicUsize::new(0);
+		let started = RwLock::new(Vec::new());
+
+		let result = stack
+			.run_async_parallel(
+				distribution,
+				|index, outcome| {
+					let (in_flight, max_in_flight, started) =
+						(&in_flight, &max_in_flight, &started);
+					async move {
+						let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+						max_in_flight.fetch_max(count, Ordering::SeqCst);
+						started.write().await.push(index);
+						// the first group is slow, but doesn't hold up the queue
+						let millis = if index == 0 { 50 } else { 5 };
+						tokio::time::sleep(std::time::Duration::from_millis(millis)).await;
+						in_flight.fetch_sub(1, Ordering::SeqCst);
+						Ok(outcome.all_to_terminal())
+					}
+				},
+				2,
+				10,
+			)
+			.await?;
+
+		assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+		assert_eq!(*started.read().await, vec![0, 1, 2, 3, 4, 5]);
+		let order: Vec<usize> =
+			result.into_iter().flat_map(GroupingOutcome::into_original).collect();
+		assert_eq!(order, vec![0, 1, 2, 3, 4, 5]);
+
+		Ok(())
+	}
+
+	/// Deliberately neither `Clone` nor `PartialEq`.
+	#[derive(Debug)]
+	struct NonClone(usize);
+
+	#[tokio::test]
+	async fn test_non_clone_elements() -> Result<(), anyhow::Error> {
+		let mut stack =
+			GroupingHeuristicStack::new(vec![super::bisect::Bisect::boxed(), DropSuccess::boxed()]);
+
+		let distribution = GroupingOutcome::new_apply_distribution((0..6).map(NonClone).collect());
+
+		let result = stack
+			.run(
+				distribution,
+				|outcome| {
+					if outcome
+						.0
+						.iter()
+						.any(|outcome| matches!(outcome, ElementalOutcome::Apply(NonClone(4))))
+					{
+						Ok(outcome.to_failures_prefer_instrumental())
+					} else {
+						Ok(outcome.all_to_terminal())
+					}
+				},
+				10,
+			)
+			.await?;
+
+		let elements: Vec<usize> = result
+			.into_iter()
+			.flat_map(GroupingOutcome::into_original)
+			.map(|element| element.0)
+			.collect();
+		assert!(elements.contains(&4));
+		assert_eq!(elements.len(), 6);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_with_report() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![
+			Chunking::boxed(2),
+			super::bisect::Bisect::boxed(),
+			DropSuccess::boxed(),
+		]);
+
+		let distribution = GroupingOutcome::new_apply_distribution(vec![0, 1, 2, 3]);
+
+		// groups containing 3 fail until it is on its own
+		let (result, report) = stack
+			.run_with_report(
+				distribution,
+				|outcome| {
+					let original = outcome.clone().into_original();
+					if original.contains(&3) && original.len() > 1 {
+						Ok(outcome.to_failures_prefer_instrumental())
+					} else if original.contains(&3) {
+						Ok(outcome.all_to_terminal())
+					} else {
+						Ok(GroupingOutcome::new_all_success(original.len()))
+					}
+				},
+				10,
+			)
+			.await?;
+
+		assert!(result.iter().all(|outcome| outcome.all_done()));
+		assert_eq!(
+			report.iterations,
+			vec![
+				IterationReport { groups: 2, applied: 4, succeeded: 2, failed: 2 },
+				IterationReport { groups: 2, applied: 2, succeeded: 1, failed: 1 },
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_into_original_with_success() -> Result<(), anyhow::Error> {
+		let outcome = GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Terminal(2)),
+			ElementalOutcome::Apply(3),
+			ElementalOutcome::Success,
+		]);
+
+		assert_eq!(outcome.clone().into_original(), vec![2, 3]);
+		assert_eq!(outcome.clone().into_original_with_success(&[1, 2, 3, 4])?, vec![1, 2, 3, 4]);
+		assert!(outcome.into_original_with_success(&[1, 2]).is_err());
+
+		Ok(())
+	}
+
+	struct RecordingObserver(Arc<std::sync::Mutex<Vec<(String, usize, usize)>>>);
+
+	impl GroupingObserver for RecordingObserver {
+		fn on_distribute(&self, heuristic: &str, groups_before: usize, groups_after: usize) {
+			self.0
+				.lock()
+				.unwrap()
+				.push((heuristic.to_string(), groups_before, groups_after));
+		}
+	}
+
+	#[test]
+	fn test_observer_sees_each_heuristic() -> Result<(), anyhow::Error> {
+		let records = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let mut stack = GroupingHeuristicStack::new(vec![Chunking::boxed(2), DropSuccess::boxed()])
+			.with_observer(Box::new(RecordingObserver(records.clone())));
+
+		let distribution = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Success,
+			ElementalOutcome::Apply(1),
+		])];
+		stack.distribute(distribution)?;
+
+		let records = records.lock().unwrap();
+		assert_eq!(records.len(), 2);
+		assert!(records[0].0.ends_with("Chunking"));
+		assert_eq!((records[0].1, records[0].2), (1, 2));
+		assert!(records[1].0.ends_with("DropSuccess"));
+		assert_eq!((records[1].1, records[1].2), (2, 1));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_max_iterations_exceeded() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![Chunking::boxed(2)]);
+
+		let distribution: Vec<GroupingOutcome<usize>> =
+			GroupingOutcome::new_apply_distribution(vec![0, 1, 2]);
+
+		// never settles: every element is reapplied
+		let error = stack
+			.run(distribution, |outcome| Ok(outcome.to_failures_prefer_instrumental()), 3)
+			.await
+			.unwrap_err();
+
+		let exceeded = error
+			.downcast::<MaxIterationsExceeded<usize>>()
+			.expect("expected MaxIterationsExceeded");
+		assert_eq!(exceeded.max_iterations, 3);
+		let remaining: Vec<usize> = exceeded
+			.distribution
+			.into_iter()
+			.flat_map(GroupingOutcome::into_original)
+			.collect();
+		assert_eq!(remaining, vec![0, 1, 2]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_map_keeps_outcomes() {
+		let outcome = GroupingOutcome::new(vec![
+			ElementalOutcome::Apply(1),
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+			ElementalOutcome::Failure(ElementalFailure::Terminal(3)),
+		]);
+
+		assert_eq!(
+			outcome.map(|element| element.to_string()),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply("1".to_string()),
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Instrumental("2".to_string())),
+				ElementalOutcome::Failure(ElementalFailure::Terminal("3".to_string())),
+			])
+		);
+	}
+
+	#[test]
+	fn test_try_map_fails_terminally() {
+		let outcome = GroupingOutcome::new(vec![
+			ElementalOutcome::Apply("1"),
+			ElementalOutcome::Apply("x"),
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Instrumental("y")),
+			ElementalOutcome::Failure(ElementalFailure::Instrumental("2")),
+			ElementalOutcome::Failure(ElementalFailure::Terminal("3")),
+		]);
+
+		let decoded = outcome.try_map(|raw| raw.parse::<u64>().map_err(|e| (raw, e)));
+		let invalid = || "x".parse::<u64>().unwrap_err();
+		assert_eq!(
+			decoded,
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(Ok(1)),
+				ElementalOutcome::Failure(ElementalFailure::Terminal(Err(("x", invalid())))),
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Terminal(Err(("y", invalid())))),
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(Ok(2))),
+				ElementalOutcome::Failure(ElementalFailure::Terminal(Ok(3))),
+			])
+		);
+
+		// failed elements come back without cloning
+		struct NotClone(u64);
+		let outcome = GroupingOutcome::new(vec![
+			ElementalOutcome::Apply(NotClone(4)),
+			ElementalOutcome::Apply(NotClone(5)),
+		]);
+		let halved = outcome
+			.try_map(|n| if n.0 % 2 == 0 { Ok(n.0 / 2) } else { Err((n, "odd")) })
+			.into_original();
+		assert!(matches!(halved.as_slice(), [Ok(2), Err((NotClone(5), "odd"))]));
+	}
+
+	#[test]
+	fn test_accessors() {
+		let mut outcome = GroupingOutcome::new(vec![
+			ElementalOutcome::Apply(1),
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(3)),
+		]);
+		assert_eq!(outcome.len(), 3);
+		assert!(!outcome.is_empty());
+		assert!(GroupingOutcome::<u64>::new(vec![]).is_empty());
+		assert_eq!(outcome.iter().filter(|outcome| outcome.is_success()).count(), 1);
+
+		for outcome in &mut outcome {
+			if let ElementalOutcome::Apply(t) = outcome {
+				*t *= 10;
+			}
+		}
+		assert_eq!(
+			outcome.into_iter().collect::<Vec<_>>(),
+			vec![
+				ElementalOutcome::Apply(10),
+				ElementalOutcome::Success,
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(3)),
+			]
+		);
+	}
 }
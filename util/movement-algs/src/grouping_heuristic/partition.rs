@@ -0,0 +1,133 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Regroups the distribution so the elements of each bucket, as assigned by a bucket function,
+/// form their own groups, e.g., to submit high priority transactions apart from the others.
+///
+/// Groups are ordered by bucket, lowest first, and elements keep their order within a bucket.
+/// Successes carry no element to bucket and are gathered in a trailing group.
+///
+/// [super::chunking::Chunking] flattens the distribution and would mix the buckets again,
+/// use [Partition::with_chunk_size] to chunk each bucket instead.
+pub struct Partition<T> {
+	bucket: Box<dyn Fn(&T) -> u8 + Send + Sync>,
+	chunk_size: Option<usize>,
+}
+
+impl<T> Partition<T> {
+	pub fn new(bucket: impl Fn(&T) -> u8 + Send + Sync + 'static) -> Self {
+		Self { bucket: Box::new(bucket), chunk_size: None }
+	}
+
+	pub fn boxed(bucket: impl Fn(&T) -> u8 + Send + Sync + 'static) -> Box<Self> {
+		Box::new(Self::new(bucket))
+	}
+
+	/// Splits each bucket into groups of at most `size` elements.
+	pub fn with_chunk_size(mut self, size: usize) -> Self {
+		self.chunk_size = Some(size);
+		self
+	}
+
+	fn bucket(&self, outcome: &ElementalOutcome<T>) -> Option<u8> {
+		match outcome {
+			ElementalOutcome::Success => None,
+			ElementalOutcome::Apply(t)
+			| ElementalOutcome::Failure(ElementalFailure::Instrumental(t))
+			| ElementalOutcome::Failure(ElementalFailure::Terminal(t)) => Some((self.bucket)(t)),
+		}
+	}
+}
+
+impl<T> GroupingHeuristic<T> for Partition<T> {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		if self.chunk_size == Some(0) {
+			return Err(anyhow::anyhow!("Partition chunk size must be greater than zero"));
+		}
+
+		let mut buckets: BTreeMap<u8, Vec<ElementalOutcome<T>>> = BTreeMap::new();
+		let mut successes = Vec::new();
+		for outcome in distribution.into_iter().flat_map(GroupingOutcome::into_inner) {
+			match self.bucket(&outcome) {
+				Some(bucket) => buckets.entry(bucket).or_default().push(outcome),
+				None => successes.push(outcome),
+			}
+		}
+
+		let groups = buckets.into_values().chain(Some(successes).filter(|s| !s.is_empty()));
+		let distribution = match self.chunk_size {
+			Some(size) => groups
+				.flat_map(|group| {
+					group
+						.into_iter()
+						.chunks(size)
+						.into_iter()
+						.map(|chunk| GroupingOutcome::new(chunk.collect()))
+						.collect::<Vec<_>>()
+				})
+				.collect(),
+			None => groups.map(GroupingOutcome::new).collect(),
+		};
+
+		Ok(distribution)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+
+	#[test]
+	fn test_partition() -> Result<(), anyhow::Error> {
+		// odd elements are high priority
+		let mut heuristic = Partition::new(|t: &u64| if t % 2 == 1 { 0 } else { 1 });
+		let distribution = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(2),
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Success,
+			]),
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(3)),
+				ElementalOutcome::Apply(4),
+			]),
+		];
+
+		let distribution = heuristic.distribute(distribution)?;
+		assert_eq!(
+			distribution,
+			vec![
+				GroupingOutcome::new(vec![
+					ElementalOutcome::Apply(1),
+					ElementalOutcome::Failure(ElementalFailure::Instrumental(3)),
+				]),
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(2), ElementalOutcome::Apply(4)]),
+				GroupingOutcome::new(vec![ElementalOutcome::Success]),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_partition_chunks_each_bucket() -> Result<(), anyhow::Error> {
+		let mut heuristic = Partition::new(|t: &u64| (t % 2) as u8).with_chunk_size(2);
+		let distribution = GroupingOutcome::new_apply_distribution(vec![0, 1, 2, 3, 4]);
+
+		let groups: Vec<Vec<u64>> = heuristic
+			.distribute(distribution)?
+			.into_iter()
+			.map(GroupingOutcome::into_original)
+			.collect();
+		assert_eq!(groups, vec![vec![0, 2], vec![4], vec![1, 3]]);
+
+		Ok(())
+	}
+}
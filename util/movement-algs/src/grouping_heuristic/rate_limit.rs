@@ -0,0 +1,209 @@
+use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Paces the groups handed to the runner with a token bucket, e.g., to avoid mempool rejections
+/// when submitting many groups of transactions.
+///
+/// Each distributed group takes a token, tokens refill at `rate` per second up to `burst`.
+/// The distribution passes through unchanged, but once the bucket runs short each further group
+/// waits for its own token, so the groups of a distribution start `1 / rate` seconds apart.
+///
+/// The delays are those of the groups it distributes, so it has to be the last heuristic of a stack.
+pub struct RateLimit {
+	rate: f64,
+	burst: f64,
+	tokens: f64,
+	last_refill: Option<Instant>,
+	delays: Vec<Option<Duration>>,
+}
+
+impl RateLimit {
+	/// Fails unless the rate is a positive, finite number.
+	pub fn new(rate: f64, burst: f64) -> Result<Self, anyhow::Error> {
+		if !rate.is_finite() || rate <= 0.0 {
+			return Err(anyhow::anyhow!("Rate must be positive and finite, got {}", rate));
+		}
+		let burst = burst.max(1.0);
+		Ok(Self { rate, burst, tokens: burst, last_refill: None, delays: Vec::new() })
+	}
+
+	pub fn boxed(rate: f64, burst: f64) -> Result<Box<Self>, anyhow::Error> {
+		Ok(Box::new(Self::new(rate, burst)?))
+	}
+
+	/// Groups per second.
+	pub fn rate(&self) -> f64 {
+		self.rate
+	}
+
+	/// The number of groups that can be applied at once after a quiet period, at least 1.
+	pub fn burst(&self) -> f64 {
+		self.burst
+	}
+
+	/// Takes `groups` tokens and returns how long until the bucket is out of debt.
+	fn take(&mut self, groups: usize, now: Instant) -> Option<Duration> {
+		if let Some(last_refill) = self.last_refill {
+			let refill = now.duration_since(last_refill).as_secs_f64() * self.rate;
+			self.tokens = (self.tokens + refill).min(self.burst);
+		}
+		self.last_refill = Some(now);
+
+		// the bucket may go into debt, which the refill pays off before the next distribution
+		self.tokens -= groups as f64;
+		if self.tokens >= 0.0 {
+			None
+		} else {
+			// a tiny rate can push the delay beyond what a duration holds
+			Some(Duration::try_from_secs_f64(-self.tokens / self.rate).unwrap_or(Duration::MAX))
+		}
+	}
+}
+
+impl<T> GroupingHeuristic<T> for RateLimit {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		let now = Instant::now();
+		self.delays = distribution.iter().map(|_| self.take(1, now)).collect();
+		Ok(distribution)
+	}
+
+	fn delay(&self, index: usize) -> Option<Duration> {
+		self.delays.get(index).copied().flatten()
+	}
+
+	fn paces(&self) -> bool {
+		true
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::chunking::Chunking;
+	use crate::grouping_heuristic::{ElementalOutcome, GroupingHeuristicStack};
+
+	#[test]
+	fn test_token_bucket() {
+		let mut heuristic = RateLimit::new(10.0, 2.0).unwrap();
+		assert_eq!(heuristic.rate(), 10.0);
+		assert_eq!(heuristic.burst(), 2.0);
+		let start = Instant::now();
+
+		// the burst is available right away
+		assert_eq!(heuristic.take(2, start), None);
+
+		// then groups are paced at the rate
+		assert_eq!(heuristic.take(1, start), Some(Duration::from_millis(100)));
+
+		// after paying off the debt and refilling a token, one group passes again
+		assert_eq!(heuristic.take(1, start + Duration::from_millis(200)), None);
+		assert_eq!(heuristic.tokens, 0.0);
+	}
+
+	#[tokio::test]
+	async fn test_rate_limit_paces_runner() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![RateLimit::boxed(100.0, 1.0)?]);
+		let distribution: Vec<GroupingOutcome<usize>> = (0..3)
+			.map(|element| GroupingOutcome::new(vec![ElementalOutcome::Apply(element)]))
+			.collect();
+
+		let start = Instant::now();
+		let result = stack
+			.run_async_parallel(
+				distribution,
+				|_, outcome| async move {
+					Ok(GroupingOutcome::new(
+						outcome
+							.into_inner()
+							.into_iter()
+							.map(|_| ElementalOutcome::Success)
+							.collect(),
+					))
+				},
+				3,
+				1,
+			)
+			.await?;
+
+		// three groups with a burst of one have to wait for two tokens
+		assert!(start.elapsed() >= Duration::from_millis(20));
+		assert!(result.iter().all(|outcome| outcome.all_done()));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_rate_limit_spreads_parallel_groups() -> Result<(), anyhow::Error> {
+		let groups = 5;
+		let mut stack = GroupingHeuristicStack::new(vec![RateLimit::boxed(20.0, 1.0)?]);
+		let distribution: Vec<GroupingOutcome<usize>> = (0..groups)
+			.map(|element| GroupingOutcome::new(vec![ElementalOutcome::Apply(element)]))
+			.collect();
+
+		let start = Instant::now();
+		let started = std::sync::Mutex::new(Vec::new());
+		stack
+			.run_async_parallel(
+				distribution,
+				|_, outcome| {
+					started.lock().unwrap().push(start.elapsed());
+					async move {
+						Ok(GroupingOutcome::new(
+							outcome
+								.into_inner()
+								.into_iter()
+								.map(|_| ElementalOutcome::Success)
+								.collect(),
+						))
+					}
+				},
+				groups,
+				1,
+			)
+			.await?;
+
+		// the groups may all run at once, but each waits for its token, 50ms apart
+		let started = started.into_inner().unwrap();
+		assert_eq!(started.len(), groups);
+		for (index, elapsed) in started.iter().enumerate() {
+			assert!(*elapsed >= Duration::from_millis(50) * index as u32);
+		}
+		assert!(started[groups - 1] >= Duration::from_millis(200));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rate_limit_rejects_bad_rates() {
+		for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+			assert!(RateLimit::new(rate, 1.0).is_err());
+		}
+		// a group always fits in the bucket
+		assert_eq!(RateLimit::new(1.0, 0.5).unwrap().burst(), 1.0);
+
+		// a tiny rate waits as long as a duration allows instead of panicking
+		let mut heuristic = RateLimit::new(f64::MIN_POSITIVE, 1.0).unwrap();
+		let now = Instant::now();
+		assert_eq!(heuristic.take(2, now), Some(Duration::MAX));
+	}
+
+	#[test]
+	fn test_rate_limit_has_to_be_last() -> Result<(), anyhow::Error> {
+		let mut stack =
+			GroupingHeuristicStack::new(vec![RateLimit::boxed(1.0, 1.0)?, Chunking::boxed(1)]);
+		assert!(stack.distribute(GroupingOutcome::new_apply_distribution(vec![1, 2])).is_err());
+
+		let mut stack =
+			GroupingHeuristicStack::new(vec![Chunking::boxed(1), RateLimit::boxed(1.0, 1.0)?]);
+		let distribution = stack.distribute(GroupingOutcome::new_apply_distribution(vec![1, 2]))?;
+		assert_eq!(distribution.len(), 2);
+		assert_eq!(stack.delays(distribution.len())[1], Duration::from_secs(1));
+
+		Ok(())
+	}
+}
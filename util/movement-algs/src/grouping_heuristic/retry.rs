@@ -0,0 +1,184 @@
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Reapplies instrumental failures until an element has failed `max_attempts` times,
+/// after which the failure is escalated to terminal.
+///
+/// Attempts are only kept while an element is in the retry loop. They are dropped once it is
+/// escalated or no longer pending in a distribution, e.g., because it succeeded.
+pub struct Retry<T> {
+	pub max_attempts: usize,
+	attempts: HashMap<T, usize>,
+}
+
+impl<T> Retry<T> {
+	pub fn new(max_attempts: usize) -> Self {
+		Self { max_attempts, attempts: HashMap::new() }
+	}
+
+	pub fn boxed(max_attempts: usize) -> Box<Self> {
+		Box::new(Self::new(max_attempts))
+	}
+}
+
+/// Attempts are keyed by element, so the element is cloned into the attempt map.
+impl<T> Retry<T>
+where
+	T: Clone + Eq + Hash,
+{
+	/// Returns the number of instrumental failures recorded for the element.
+	pub fn attempts(&self, element: &T) -> usize {
+		self.attempts.get(element).copied().unwrap_or(0)
+	}
+
+	/// Counts an instrumental failure and carries over the attempts of pending elements from
+	/// `previous`, the attempts before this distribution.
+	fn retry(
+		&mut self,
+		outcome: ElementalOutcome<T>,
+		previous: &mut HashMap<T, usize>,
+	) -> ElementalOutcome<T> {
+		match outcome {
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(t)) => {
+				let attempts = previous.remove(&t).unwrap_or(0) + 1;
+				if attempts >= self.max_attempts {
+					ElementalOutcome::Failure(ElementalFailure::Terminal(t))
+				} else {
+					self.attempts.insert(t.clone(), attempts);
+					ElementalOutcome::Apply(t)
+				}
+			}
+			ElementalOutcome::Apply(t) => {
+				if let Some((key, attempts)) = previous.remove_entry(&t) {
+					self.attempts.insert(key, attempts);
+				}
+				ElementalOutcome::Apply(t)
+			}
+			outcome => outcome,
+		}
+	}
+}
+
+impl<T> GroupingHeuristic<T> for Retry<T>
+where
+	T: Clone + Eq + Hash,
+{
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		// elements missing from the distribution have left the retry loop
+		let mut previous = std::mem::take(&mut self.attempts);
+		let distribution = distribution
+			.into_iter()
+			.map(|outcome| {
+				GroupingOutcome::new(
+					outcome
+						.into_inner()
+						.into_iter()
+						.map(|outcome| self.retry(outcome, &mut previous))
+						.collect(),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		Ok(distribution)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::{chunking::Chunking, GroupingHeuristicStack};
+
+	#[test]
+	fn test_retry_escalates_after_max_attempts() -> Result<(), anyhow::Error> {
+		let mut heuristic = Retry::new(2);
+		let distribution = vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+			ElementalOutcome::Failure(ElementalFailure::Terminal(2)),
+			ElementalOutcome::Apply(3),
+		])];
+
+		let distribution = heuristic.distribute(distribution)?;
+		assert_eq!(
+			distribution,
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Failure(ElementalFailure::Terminal(2)),
+				ElementalOutcome::Apply(3),
+			])]
+		);
+
+		let distribution =
+			heuristic.distribute(vec![GroupingOutcome::new(vec![ElementalOutcome::Failure(
+				ElementalFailure::Instrumental(1),
+			)])])?;
+		assert_eq!(
+			distribution,
+			vec![GroupingOutcome::new(vec![ElementalOutcome::Failure(
+				ElementalFailure::Terminal(1)
+			)])]
+		);
+		// escalated elements have left the retry loop
+		assert_eq!(heuristic.attempts(&1), 0);
+		assert_eq!(heuristic.attempts(&3), 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_retry_forgets_elements_leaving_the_loop() -> Result<(), anyhow::Error> {
+		let mut heuristic = Retry::new(3);
+		heuristic.distribute(vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(1)),
+			ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+		])])?;
+		assert_eq!(heuristic.attempts(&1), 1);
+		assert_eq!(heuristic.attempts(&2), 1);
+
+		// 1 succeeded, 2 is still pending
+		heuristic.distribute(vec![GroupingOutcome::new(vec![
+			ElementalOutcome::Success,
+			ElementalOutcome::Apply(2),
+		])])?;
+		assert_eq!(heuristic.attempts(&1), 0);
+		assert_eq!(heuristic.attempts(&2), 1);
+
+		// a pending element keeps counting towards escalation
+		let distribution =
+			heuristic.distribute(vec![GroupingOutcome::new(vec![ElementalOutcome::Failure(
+				ElementalFailure::Instrumental(2),
+			)])])?;
+		assert_eq!(distribution, vec![GroupingOutcome::new(vec![ElementalOutcome::Apply(2)])]);
+		assert_eq!(heuristic.attempts(&2), 2);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_retry_in_stack_terminates() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![Retry::boxed(3), Chunking::boxed(2)]);
+
+		let distribution: Vec<GroupingOutcome<usize>> =
+			GroupingOutcome::new_apply_distribution(vec![0, 1, 2]);
+
+		// every application fails instrumentally
+		let result = stack
+			.run(distribution, |outcome| Ok(outcome.to_failures_prefer_instrumental()), 10)
+			.await?;
+
+		assert!(result.iter().all(|outcome| outcome.all_done()));
+		let remaining: Vec<usize> =
+			result.into_iter().flat_map(GroupingOutcome::into_original).collect();
+		assert_eq!(remaining, vec![0, 1, 2]);
+
+		Ok(())
+	}
+}
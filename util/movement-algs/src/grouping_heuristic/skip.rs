@@ -1,5 +1,10 @@
-use crate::grouping_heuristic::{GroupingHeuristic, GroupingOutcome};
+use crate::grouping_heuristic::{
+	ElementalFailure, ElementalOutcome, GroupingHeuristic, GroupingOutcome,
+};
 
+/// Wraps a heuristic and passes the distribution through unchanged instead of applying it.
+///
+/// On its own this is a no-op; [SkipIf] and [SkipFor] decide when the wrapped heuristic runs.
 pub struct Skip<T>(pub Box<dyn GroupingHeuristic<T>>);
 
 impl<T> Skip<T> {
@@ -35,6 +40,7 @@ impl<T> GroupingHeuristic<T> for Skip<T> {
 	}
 }
 
+/// Skips the wrapped heuristic while the condition is set.
 pub struct SkipIf<T> {
 	pub condition: bool,
 	pub skip: Skip<T>,
@@ -67,6 +73,7 @@ impl<T> GroupingHeuristic<T> for SkipIf<T> {
 	}
 }
 
+/// Skips the wrapped heuristic for the first `for_count` distributions.
 pub struct SkipFor<T> {
 	pub skip_if: SkipIf<T>,
 	pub counter: usize,
@@ -102,3 +109,226 @@ impl<T> GroupingHeuristic<T> for SkipFor<T> {
 		distribution
 	}
 }
+
+/// Marks every element matching a predicate as a success, e.g., elements already handled or not
+/// applicable, so they are excluded from submission without being failures.
+/// Terminal failures are kept, a skip doesn't turn them into successes.
+///
+/// Skipped elements carry no value afterwards, so [GroupingOutcome::into_original] leaves them
+/// out. Restore them with [GroupingOutcome::into_original_with_success].
+pub struct SkipWhere<T> {
+	predicate: Box<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T> SkipWhere<T> {
+	pub fn new(predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+		Self { predicate: Box::new(predicate) }
+	}
+
+	pub fn boxed(predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Box<Self> {
+		Box::new(Self::new(predicate))
+	}
+}
+
+impl<T> GroupingHeuristic<T> for SkipWhere<T> {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		Ok(skip_matching(distribution, &self.predicate))
+	}
+}
+
+/// Marks every element whose key is below a watermark as a success, e.g., transactions below the
+/// last processed version when replaying an indexer stream after a restart.
+///
+/// The runner then treats those elements as done without applying them again.
+/// Terminal failures are kept, like for [SkipWhere].
+pub struct SkipUntil<T> {
+	pub watermark: u64,
+	key: Box<dyn Fn(&T) -> u64 + Send + Sync>,
+}
+
+impl<T> SkipUntil<T> {
+	pub fn new(watermark: u64, key: impl Fn(&T) -> u64 + Send + Sync + 'static) -> Self {
+		Self { watermark, key: Box::new(key) }
+	}
+
+	pub fn boxed(watermark: u64, key: impl Fn(&T) -> u64 + Send + Sync + 'static) -> Box<Self> {
+		Box::new(Self::new(watermark, key))
+	}
+
+	/// Moves the watermark, e.g., as the consumer checkpoints its progress.
+	pub fn set_watermark(&mut self, watermark: u64) {
+		self.watermark = watermark;
+	}
+}
+
+impl<T> GroupingHeuristic<T> for SkipUntil<T> {
+	fn distribute(
+		&mut self,
+		distribution: Vec<GroupingOutcome<T>>,
+	) -> Result<Vec<GroupingOutcome<T>>, anyhow::Error> {
+		Ok(skip_matching(distribution, |t| (self.key)(t) < self.watermark))
+	}
+}
+
+/// Replaces the pending elements matching `predicate` with successes, keeping the groups.
+/// Terminal failures stay failures, so skipping doesn't hide them.
+fn skip_matching<T>(
+	distribution: Vec<GroupingOutcome<T>>,
+	predicate: impl Fn(&T) -> bool,
+) -> Vec<GroupingOutcome<T>> {
+	distribution
+		.into_iter()
+		.map(|outcome| {
+			GroupingOutcome::new(
+				outcome
+					.into_inner()
+					.into_iter()
+					.map(|outcome| match &outcome {
+						ElementalOutcome::Apply(t)
+						| ElementalOutcome::Failure(ElementalFailure::Instrumental(t))
+							if predicate(t) =>
+						{
+							ElementalOutcome::Success
+						}
+						_ => outcome,
+					})
+					.collect(),
+			)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use crate::grouping_heuristic::{drop_success::DropSuccess, GroupingHeuristicStack};
+	use std::cell::RefCell;
+
+	#[test]
+	fn test_skip_until_watermark() -> Result<(), anyhow::Error> {
+		let mut heuristic = SkipUntil::new(3, |version: &u64| *version);
+		let failed =
+			GroupingOutcome::new(vec![ElementalOutcome::Failure(ElementalFailure::Terminal(0))]);
+		let distribution = vec![
+			GroupingOutcome::new(vec![
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Failure(ElementalFailure::Instrumental(2)),
+			]),
+			GroupingOutcome::new(vec![ElementalOutcome::Apply(3), ElementalOutcome::Apply(4)]),
+			failed.clone(),
+		];
+
+		let distribution = heuristic.distribute(distribution)?;
+		assert_eq!(
+			distribution,
+			vec![
+				GroupingOutcome::new(vec![ElementalOutcome::Success, ElementalOutcome::Success]),
+				GroupingOutcome::new(vec![ElementalOutcome::Apply(3), ElementalOutcome::Apply(4)]),
+				// terminal failures below the watermark aren't reported as successes
+				failed,
+			]
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_skip_until_replay() -> Result<(), anyhow::Error> {
+		let mut stack = GroupingHeuristicStack::new(vec![
+			SkipUntil::boxed(5, |version: &u64| *version),
+			DropSuccess::boxed(),
+		]);
+		let distribution = GroupingOutcome::new_apply_distribution((0..8).collect::<Vec<u64>>());
+
+		let applied = RefCell::new(Vec::new());
+		stack
+			.run(
+				distribution,
+				|outcome| {
+					let outcomes = outcome.into_inner();
+					applied.borrow_mut().extend(outcomes.iter().filter_map(
+						|outcome| match outcome {
+							ElementalOutcome::Apply(version) => Some(*version),
+							_ => None,
+						},
+					));
+					Ok(GroupingOutcome::new(
+						outcomes.into_iter().map(|_| ElementalOutcome::Success).collect(),
+					))
+				},
+				10,
+			)
+			.await?;
+		assert_eq!(applied.into_inner(), vec![5, 6, 7]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_skip_where() -> Result<(), anyhow::Error> {
+		let original = (0..6).collect::<Vec<u64>>();
+		let skipped = SkipWhere::new(|element: &u64| [0, 2, 4].contains(element))
+			.distribute(GroupingOutcome::new_apply_distribution(original.clone()))?;
+		assert_eq!(
+			skipped,
+			vec![GroupingOutcome::new(vec![
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(1),
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(3),
+				ElementalOutcome::Success,
+				ElementalOutcome::Apply(5),
+			])]
+		);
+		// skipped elements aren't failures, but can be restored
+		assert_eq!(skipped[0].clone().into_original(), vec![1, 3, 5]);
+		assert_eq!(skipped[0].clone().into_original_with_success(&original)?, original);
+
+		// composes with other skips in a stack
+		let mut stack = GroupingHeuristicStack::new(vec![
+			SkipWhere::boxed(|element: &u64| [0, 2, 4].contains(element)),
+			SkipUntil::boxed(3, |element: &u64| *element),
+			DropSuccess::boxed(),
+		]);
+		let applied = RefCell::new(Vec::new());
+		stack
+			.run(
+				GroupingOutcome::new_apply_distribution(original),
+				|outcome| {
+					applied.borrow_mut().extend(outcome.clone().into_original());
+					Ok(GroupingOutcome::new_all_success(outcome.len()))
+				},
+				10,
+			)
+			.await?;
+		assert_eq!(applied.into_inner(), vec![3, 5]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_skipped_elements_are_recovered_from_the_original() -> Result<(), anyhow::Error> {
+		let original = (0..4).collect::<Vec<u64>>();
+		let mut stack =
+			GroupingHeuristicStack::new(vec![SkipWhere::boxed(|element: &u64| *element % 2 == 0)]);
+		let result = stack
+			.run(
+				GroupingOutcome::new_apply_distribution(original.clone()),
+				|outcome| Ok(outcome.all_to_terminal()),
+				10,
+			)
+			.await?;
+
+		// into_original only returns the applied elements, here the failed ones
+		let outcome = result.into_iter().next().unwrap();
+		assert_eq!(outcome.clone().into_original(), vec![1, 3]);
+		// the skipped ones come back in place from the original elements
+		assert_eq!(outcome.into_original_with_success(&original)?, original);
+
+		Ok(())
+	}
+}
@@ -1,6 +1,7 @@
 use crate::transaction::Transaction;
 use core::fmt;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(
 	Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord,
@@ -46,12 +47,168 @@ pub struct TransactionEntry {
 	data: Transaction,
 }
 
+impl TransactionEntry {
+	pub fn new(consumer_id: Id, data: Transaction) -> Self {
+		Self { consumer_id, data }
+	}
+
+	pub fn consumer_id(&self) -> Id {
+		self.consumer_id
+	}
+
+	pub fn transaction(&self) -> &Transaction {
+		&self.data
+	}
+
+	pub fn into_parts(self) -> (Id, Transaction) {
+		(self.consumer_id, self.data)
+	}
+}
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct AtomicTransactionBundle {
 	sequencer_id: Id,
 	transactions: Vec<TransactionEntry>,
 }
 
+impl AtomicTransactionBundle {
+	pub fn new(sequencer_id: Id, transactions: Vec<TransactionEntry>) -> Self {
+		Self { sequencer_id, transactions }
+	}
+
+	pub fn sequencer_id(&self) -> Id {
+		self.sequencer_id
+	}
+
+	pub fn entries(&self) -> &[TransactionEntry] {
+		&self.transactions
+	}
+
+	pub fn into_entries(self) -> Vec<TransactionEntry> {
+		self.transactions
+	}
+
+	/// Recovers all of the transactions in the bundle, in order, dropping the consumer ids.
+	pub fn into_transactions(self) -> Vec<Transaction> {
+		self.transactions.into_iter().map(|entry| entry.data).collect()
+	}
+
+	/// Packs the bundle into a single blob.
+	///
+	/// The layout is the sequencer id, followed by each entry as its consumer id,
+	/// a little-endian u64 length prefix, and the BCS serialized transaction.
+	pub fn pack(&self) -> Result<Vec<u8>, anyhow::Error> {
+		let mut bytes = Vec::new();
+		bytes.extend_from_slice(self.sequencer_id.as_bytes());
+		for entry in &self.transactions {
+			let transaction = bcs::to_bytes(&entry.data)?;
+			bytes.extend_from_slice(entry.consumer_id.as_bytes());
+			bytes.extend_from_slice(&(transaction.len() as u64).to_le_bytes());
+			bytes.extend_from_slice(&transaction);
+		}
+		Ok(bytes)
+	}
+
+	/// Unpacks a blob produced by [AtomicTransactionBundle::pack].
+	pub fn unpack(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+		let mut cursor = bytes;
+		let sequencer_id = Id::new(take_array(&mut cursor)?);
+
+		let mut transactions = Vec::new();
+		while !cursor.is_empty() {
+			let consumer_id = Id::new(take_array(&mut cursor)?);
+			let length = usize::try_from(u64::from_le_bytes(take_array(&mut cursor)?))?;
+			if cursor.len() < length {
+				return Err(anyhow::anyhow!(
+					"Truncated transaction entry: expected {} bytes, found {}",
+					length,
+					cursor.len()
+				));
+			}
+			let (transaction, rest) = cursor.split_at(length);
+			cursor = rest;
+			transactions
+				.push(TransactionEntry { consumer_id, data: bcs::from_bytes(transaction)? });
+		}
+
+		Ok(Self { sequencer_id, transactions })
+	}
+}
+
+/// Assembles an [AtomicTransactionBundle], guarding against reused sequence numbers.
+///
+/// Within the bundle, each consumer's transactions must have strictly increasing sequence numbers.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBundleBuilder {
+	sequencer_id: Id,
+	transactions: Vec<TransactionEntry>,
+	last_sequence_numbers: BTreeMap<Id, u64>,
+}
+
+impl TransactionBundleBuilder {
+	pub fn new(sequencer_id: Id) -> Self {
+		Self { sequencer_id, transactions: Vec::new(), last_sequence_numbers: BTreeMap::new() }
+	}
+
+	/// Appends a transaction for a consumer.
+	/// Errors, leaving the builder unchanged, if the sequence number doesn't increase for the consumer.
+	pub fn push(&mut self, consumer_id: Id, transaction: Transaction) -> Result<(), anyhow::Error> {
+		let sequence_number = transaction.sequence_number();
+		if let Some(&last) = self.last_sequence_numbers.get(&consumer_id) {
+			if sequence_number == last {
+				return Err(anyhow::anyhow!(
+					"Duplicate sequence number {} for consumer {}",
+					sequence_number,
+					consumer_id
+				));
+			}
+			if sequence_number < last {
+				return Err(anyhow::anyhow!(
+					"Sequence number {} follows {} for consumer {}",
+					sequence_number,
+					last,
+					consumer_id
+				));
+			}
+		}
+
+		self.last_sequence_numbers.insert(consumer_id, sequence_number);
+		self.transactions.push(TransactionEntry::new(consumer_id, transaction));
+		Ok(())
+	}
+
+	pub fn with_transaction(
+		mut self,
+		consumer_id: Id,
+		transaction: Transaction,
+	) -> Result<Self, anyhow::Error> {
+		self.push(consumer_id, transaction)?;
+		Ok(self)
+	}
+
+	pub fn len(&self) -> usize {
+		self.transactions.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.transactions.is_empty()
+	}
+
+	pub fn build(self) -> AtomicTransactionBundle {
+		AtomicTransactionBundle::new(self.sequencer_id, self.transactions)
+	}
+}
+
+/// Takes a fixed size array off the front of the cursor.
+fn take_array<const N: usize>(cursor: &mut &[u8]) -> Result<[u8; N], anyhow::Error> {
+	if cursor.len() < N {
+		return Err(anyhow::anyhow!("Unexpected end of bundle: expected {} bytes", N));
+	}
+	let (head, rest) = cursor.split_at(N);
+	*cursor = rest;
+	Ok(head.try_into()?)
+}
+
 impl TryFrom<AtomicTransactionBundle> for Transaction {
 	type Error = anyhow::Error;
 
@@ -72,3 +229,76 @@ impl From<Transaction> for AtomicTransactionBundle {
 		}
 	}
 }
+
+impl From<Vec<Transaction>> for AtomicTransactionBundle {
+	fn from(transactions: Vec<Transaction>) -> Self {
+		Self {
+			sequencer_id: Id::default(),
+			transactions: transactions
+				.into_iter()
+				.map(|transaction| TransactionEntry::new(Id::default(), transaction))
+				.collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_pack_round_trip() -> Result<(), anyhow::Error> {
+		let bundle = AtomicTransactionBundle::new(
+			Id::new([1; 32]),
+			vec![
+				TransactionEntry::new(Id::new([2; 32]), Transaction::new(vec![1, 2, 3], 0)),
+				TransactionEntry::new(Id::new([3; 32]), Transaction::new(vec![], 1)),
+				TransactionEntry::new(Id::new([2; 32]), Transaction::new(vec![4], 2)),
+			],
+		);
+
+		let unpacked = AtomicTransactionBundle::unpack(&bundle.pack()?)?;
+		assert_eq!(unpacked, bundle);
+		assert_eq!(unpacked.entries()[1].consumer_id(), Id::new([3; 32]));
+		assert_eq!(unpacked.into_transactions().len(), 3);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unpack_truncated() -> Result<(), anyhow::Error> {
+		let bundle: AtomicTransactionBundle = vec![Transaction::test()].into();
+		let bytes = bundle.pack()?;
+		assert!(AtomicTransactionBundle::unpack(&bytes[..bytes.len() - 1]).is_err());
+		assert!(AtomicTransactionBundle::unpack(&bytes[..16]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_builder_enforces_ordering() -> Result<(), anyhow::Error> {
+		let alice = Id::new([1; 32]);
+		let bob = Id::new([2; 32]);
+
+		let mut builder = TransactionBundleBuilder::new(Id::test())
+			.with_transaction(alice, Transaction::new(vec![1], 0))?
+			.with_transaction(bob, Transaction::new(vec![2], 0))?
+			.with_transaction(alice, Transaction::new(vec![3], 2))?;
+
+		// reusing a sequence number is rejected
+		let duplicate = builder.push(alice, Transaction::new(vec![4], 2)).unwrap_err();
+		assert!(duplicate.to_string().contains("Duplicate sequence number 2"));
+
+		// going backwards is rejected
+		let decreasing = builder.push(alice, Transaction::new(vec![5], 1)).unwrap_err();
+		assert!(decreasing.to_string().contains("Sequence number 1 follows 2"));
+
+		// other consumers are unaffected
+		builder.push(bob, Transaction::new(vec![6], 1))?;
+
+		let bundle = builder.build();
+		assert_eq!(bundle.entries().len(), 4);
+		assert_eq!(bundle.entries()[2].consumer_id(), alice);
+
+		Ok(())
+	}
+}
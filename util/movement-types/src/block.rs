@@ -1,9 +1,16 @@
+use crate::hash::{
+	domain_hasher, versioned_domain_hasher, DefaultHasher, Hasher, BLOCK_DOMAIN,
+	BLOCK_ID_SCHEME_VERSION, COMMITMENT_AGGREGATE_DOMAIN,
+};
 use crate::transaction::Transaction;
+use aptos_types::account_address::AccountAddress;
 use aptos_types::state_proof::StateProof;
 use core::fmt;
 use serde::{Deserialize, Serialize};
 use std::collections::btree_set;
 use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
 pub type Transactions<'a> = btree_set::Iter<'a, Transaction>;
 
@@ -49,10 +56,61 @@ impl fmt::Display for Id {
 	}
 }
 
+/// When and by whom a block was proposed.
+///
+/// Orders by epoch, then round, then timestamp.
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub enum BlockMetadata {
-	#[default]
-	BlockMetadata,
+pub struct BlockMetadata {
+	epoch: u64,
+	round: u64,
+	timestamp_micros: u64,
+	proposer: Option<AccountAddress>,
+}
+
+impl BlockMetadata {
+	pub fn new(
+		epoch: u64,
+		round: u64,
+		timestamp_micros: u64,
+		proposer: Option<AccountAddress>,
+	) -> Self {
+		Self { epoch, round, timestamp_micros, proposer }
+	}
+
+	pub fn epoch(&self) -> u64 {
+		self.epoch
+	}
+
+	pub fn round(&self) -> u64 {
+		self.round
+	}
+
+	/// The proposal time in microseconds since the Unix epoch.
+	pub fn timestamp_micros(&self) -> u64 {
+		self.timestamp_micros
+	}
+
+	/// The proposer, `None` for blocks not proposed by a validator, e.g., built by a sequencer.
+	pub fn proposer(&self) -> Option<AccountAddress> {
+		self.proposer
+	}
+
+	pub fn test() -> Self {
+		Self::new(1, 2, 1_000_000, Some(AccountAddress::ONE))
+	}
+
+	fn hash_into(&self, hasher: &mut impl Hasher) {
+		hasher.update(&self.epoch.to_le_bytes());
+		hasher.update(&self.round.to_le_bytes());
+		hasher.update(&self.timestamp_micros.to_le_bytes());
+		match &self.proposer {
+			Some(proposer) => {
+				hasher.update(&[1]);
+				hasher.update(proposer.as_ref());
+			}
+			None => hasher.update(&[0]),
+		}
+	}
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -65,16 +123,41 @@ pub struct Block {
 
 impl Block {
 	pub fn new(metadata: BlockMetadata, parent: Id, transactions: BTreeSet<Transaction>) -> Self {
-		let mut hasher = blake3::Hasher::new();
-		hasher.update(parent.as_bytes());
-		for transaction in &transactions {
-			hasher.update(&transaction.id().as_ref());
-		}
-		let id = Id(hasher.finalize().into());
+		Self::new_with::<DefaultHasher>(metadata, parent, transactions)
+	}
 
+	/// Like [Block::new], but hashes the id with `H`.
+	/// The transaction ids are taken as they are, so they should be hashed with `H` as well.
+	pub fn new_with<H: Hasher>(
+		metadata: BlockMetadata,
+		parent: Id,
+		transactions: BTreeSet<Transaction>,
+	) -> Self {
+		let id = Self::compute_id::<H>(&metadata, &parent, &transactions);
 		Self { metadata, parent, transactions, id }
 	}
 
+	/// Starts assembling a block transaction-by-transaction.
+	/// The id is only hashed once, when the builder is finalized.
+	pub fn builder(metadata: BlockMetadata, parent: Id) -> BlockBuilder {
+		BlockBuilder::new(metadata, parent)
+	}
+
+	/// Hashes the parent, the metadata and the (already cached) transaction ids in set order.
+	fn compute_id<H: Hasher>(
+		metadata: &BlockMetadata,
+		parent: &Id,
+		transactions: &BTreeSet<Transaction>,
+	) -> Id {
+		let mut hasher: H = versioned_domain_hasher(BLOCK_DOMAIN, BLOCK_ID_SCHEME_VERSION);
+		hasher.update(parent.as_bytes());
+		metadata.hash_into(&mut hasher);
+		for transaction in transactions {
+			hasher.update(transaction.id().as_ref());
+		}
+		Id(hasher.finalize())
+	}
+
 	pub fn into_parts(self) -> (BlockMetadata, Id, BTreeSet<Transaction>, Id) {
 		(self.metadata, self.parent, self.transactions, self.id)
 	}
@@ -83,6 +166,27 @@ impl Block {
 		self.id
 	}
 
+	/// Checks that the parent, metadata and transactions hash to the stored id, and that it is the
+	/// expected one, e.g., for a block received over the wire or loaded from storage.
+	///
+	/// The transaction ids are recomputed from their contents too, as the block id only covers
+	/// the stored ids.
+	pub fn verify(&self, expected: &Id) -> bool {
+		self.verify_with::<DefaultHasher>(expected)
+	}
+
+	/// Like [Block::verify], for a block hashed with `H`.
+	pub fn verify_with<H: Hasher>(&self, expected: &Id) -> bool {
+		self.id == *expected
+			&& self.transactions.iter().all(Transaction::verify_with::<H>)
+			&& Self::compute_id::<H>(&self.metadata, &self.parent, &self.transactions) == self.id
+	}
+
+	/// Checks that the block links to the given parent.
+	pub fn verify_parent(&self, parent: &Id) -> bool {
+		self.parent == *parent
+	}
+
 	pub fn parent(&self) -> Id {
 		self.parent
 	}
@@ -96,18 +200,136 @@ impl Block {
 	}
 
 	pub fn test() -> Self {
-		Self::new(
-			BlockMetadata::BlockMetadata,
-			Id::test(),
-			BTreeSet::from_iter(vec![Transaction::test()]),
-		)
+		Self::new(BlockMetadata::test(), Id::test(), BTreeSet::from_iter(vec![Transaction::test()]))
 	}
 
+	/// Adds a transaction and refreshes the cached id.
+	/// Each call rehashes the block, so prefer [Block::builder] when adding many transactions.
 	pub fn add_transaction(&mut self, transaction: Transaction) {
+		self.add_transaction_with::<DefaultHasher>(transaction)
+	}
+
+	/// Like [Block::add_transaction], for a block hashed with `H`.
+	pub fn add_transaction_with<H: Hasher>(&mut self, transaction: Transaction) {
+		if self.transactions.insert(transaction) {
+			self.id = Self::compute_id::<H>(&self.metadata, &self.parent, &self.transactions);
+		}
+	}
+
+	/// Serializes the block with BCS.
+	/// This is the wire format commitments are computed over, see the golden vector test.
+	pub fn to_bcs(&self) -> Result<Vec<u8>, anyhow::Error> {
+		Ok(bcs::to_bytes(self)?)
+	}
+
+	/// Deserializes a block from BCS.
+	pub fn from_bcs(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+		Ok(bcs::from_bytes(bytes)?)
+	}
+}
+
+/// Incrementally collects transactions for a [Block], deferring the id computation to [BlockBuilder::build].
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct BlockBuilder {
+	metadata: BlockMetadata,
+	parent: Id,
+	transactions: BTreeSet<Transaction>,
+}
+
+impl BlockBuilder {
+	pub fn new(metadata: BlockMetadata, parent: Id) -> Self {
+		Self { metadata, parent, transactions: BTreeSet::new() }
+	}
+
+	pub fn add_transaction(&mut self, transaction: Transaction) -> &mut Self {
+		self.transactions.insert(transaction);
+		self
+	}
+
+	pub fn with_transaction(mut self, transaction: Transaction) -> Self {
 		self.transactions.insert(transaction);
+		self
+	}
+
+	pub fn len(&self) -> usize {
+		self.transactions.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.transactions.is_empty()
+	}
+
+	/// Finalizes the block, hashing the collected transactions exactly once.
+	pub fn build(self) -> Block {
+		Block::new(self.metadata, self.parent, self.transactions)
+	}
+
+	/// Like [BlockBuilder::build], but hashes the id with `H`.
+	pub fn build_with<H: Hasher>(self) -> Block {
+		Block::new_with::<H>(self.metadata, self.parent, self.transactions)
 	}
 }
 
+/// Where a sequence of blocks stops forming a chain, by index into the checked slice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+	/// The block doesn't hash to its id, so its links can't be trusted.
+	InvalidId { index: usize, id: Id },
+	/// The block doesn't link to the block before it.
+	BrokenLink { index: usize, expected: Id, found: Id },
+	/// A block other than the first one links to the genesis parent.
+	UnexpectedGenesis { index: usize },
+}
+
+impl fmt::Display for ChainError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ChainError::InvalidId { index, id } => {
+				write!(f, "Block {} at index {} doesn't hash to its id", id, index)
+			}
+			ChainError::BrokenLink { index, expected, found } => write!(
+				f,
+				"Block at index {} links to {} instead of the previous block {}",
+				index, found, expected
+			),
+			ChainError::UnexpectedGenesis { index } => {
+				write!(f, "Block at index {} links to the genesis parent", index)
+			}
+		}
+	}
+}
+
+impl std::error::Error for ChainError {}
+
+/// Checks that each block, transactions included, hashes to its id and links to the block before
+/// it.
+///
+/// The first block may link to anything, so a chain can be verified starting from any height,
+/// but only it may link to the [Id::genesis_block] parent.
+pub fn verify_chain(blocks: &[Block]) -> Result<(), ChainError> {
+	verify_chain_with::<DefaultHasher>(blocks)
+}
+
+/// Like [verify_chain], for blocks hashed with `H`.
+pub fn verify_chain_with<H: Hasher>(blocks: &[Block]) -> Result<(), ChainError> {
+	for (index, block) in blocks.iter().enumerate() {
+		if !block.verify_with::<H>(&block.id()) {
+			return Err(ChainError::InvalidId { index, id: block.id() });
+		}
+		if index == 0 {
+			continue;
+		}
+		if block.verify_parent(&Id::genesis_block()) {
+			return Err(ChainError::UnexpectedGenesis { index });
+		}
+		let expected = blocks[index - 1].id();
+		if !block.verify_parent(&expected) {
+			return Err(ChainError::BrokenLink { index, expected, found: block.parent() });
+		}
+	}
+	Ok(())
+}
+
 #[derive(
 	Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord,
 )]
@@ -128,9 +350,119 @@ impl Commitment {
 
 	/// Creates a commitment by making a cryptographic digest of the state proof.
 	pub fn digest_state_proof(state_proof: &StateProof) -> Self {
-		let mut hasher = blake3::Hasher::new();
-		bcs::serialize_into(&mut hasher, &state_proof).expect("unexpected serialization error");
-		Self(hasher.finalize().into())
+		Self::digest_state_proof_with::<DefaultHasher>(state_proof)
+	}
+
+	/// Like [Commitment::digest_state_proof], but digests with `H`.
+	pub fn digest_state_proof_with<H: Hasher>(state_proof: &StateProof) -> Self {
+		let bytes = bcs::to_bytes(state_proof).expect("unexpected serialization error");
+		Self(H::digest(&bytes))
+	}
+
+	/// Checks that the state proof digests to this commitment.
+	/// The comparison runs in constant time.
+	pub fn verify_state_proof(&self, state_proof: &StateProof) -> bool {
+		self.verify_state_proof_with::<DefaultHasher>(state_proof)
+	}
+
+	/// Like [Commitment::verify_state_proof], for a commitment digested with `H`.
+	pub fn verify_state_proof_with<H: Hasher>(&self, state_proof: &StateProof) -> bool {
+		self.constant_time_eq(&Self::digest_state_proof_with::<H>(state_proof))
+	}
+
+	/// Folds the commitments of a range of blocks into one, e.g., to settle once per epoch.
+	///
+	/// Hashes the height, block id and commitment of each block in height order.
+	/// The input may be unordered, but has to cover contiguous heights without duplicates.
+	pub fn aggregate(commitments: &[BlockCommitment]) -> Result<Self, anyhow::Error> {
+		Self::aggregate_with::<DefaultHasher>(commitments)
+	}
+
+	/// Like [Commitment::aggregate], but hashes with `H`.
+	pub fn aggregate_with<H: Hasher>(
+		commitments: &[BlockCommitment],
+	) -> Result<Self, anyhow::Error> {
+		let mut commitments: Vec<&BlockCommitment> = commitments.iter().collect();
+		commitments.sort_by_key(|commitment| commitment.height());
+		if commitments.is_empty() {
+			return Err(anyhow::anyhow!("No block commitments to aggregate"));
+		}
+		for pair in commitments.windows(2) {
+			let (previous, next) = (pair[0].height(), pair[1].height());
+			if previous == next {
+				return Err(anyhow::anyhow!("Duplicate block commitment at height {}", next));
+			}
+			if previous + 1 != next {
+				return Err(anyhow::anyhow!(
+					"Missing block commitments between heights {} and {}",
+					previous,
+					next
+				));
+			}
+		}
+
+		let mut hasher: H = domain_hasher(COMMITMENT_AGGREGATE_DOMAIN);
+		for commitment in commitments {
+			hasher.update(&commitment.height().to_le_bytes());
+			hasher.update(commitment.block_id().as_bytes());
+			hasher.update(commitment.commitment().as_bytes());
+		}
+		Ok(Self(hasher.finalize()))
+	}
+
+	/// Checks that the block commitments aggregate to this commitment.
+	/// The comparison runs in constant time.
+	pub fn verify_aggregate(&self, commitments: &[BlockCommitment]) -> bool {
+		self.verify_aggregate_with::<DefaultHasher>(commitments)
+	}
+
+	/// Like [Commitment::verify_aggregate], for a commitment hashed with `H`.
+	pub fn verify_aggregate_with<H: Hasher>(&self, commitments: &[BlockCommitment]) -> bool {
+		Self::aggregate_with::<H>(commitments)
+			.is_ok_and(|aggregate| self.constant_time_eq(&aggregate))
+	}
+
+	fn constant_time_eq(&self, other: &Self) -> bool {
+		self.0.iter().zip(other.0.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+	}
+}
+
+impl Commitment {
+	/// Serializes the commitment with BCS.
+	pub fn to_bcs(&self) -> Result<Vec<u8>, anyhow::Error> {
+		Ok(bcs::to_bytes(self)?)
+	}
+
+	/// Deserializes a commitment from BCS.
+	pub fn from_bcs(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+		Ok(bcs::from_bytes(bytes)?)
+	}
+
+	/// Parses the 64 hex characters the commitment is displayed as.
+	pub fn from_hex(hex: &str) -> Result<Self, anyhow::Error> {
+		if hex.len() != 64 {
+			return Err(anyhow::anyhow!(
+				"Expected 64 hex characters for a commitment, got {}",
+				hex.len()
+			));
+		}
+		if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+			return Err(anyhow::anyhow!("Invalid hex character {:?} in commitment", c));
+		}
+		let mut bytes = [0u8; 32];
+		for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+			// the pair is ASCII hex digits, checked above
+			*byte = u8::from_str_radix(std::str::from_utf8(pair)?, 16)?;
+		}
+		Ok(Self(bytes))
+	}
+}
+
+impl FromStr for Commitment {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::from_hex(s)
 	}
 }
 
@@ -167,6 +499,16 @@ impl BlockCommitment {
 		Self { height, block_id, commitment }
 	}
 
+	/// Commits to the block by the digest of its state proof, see [Commitment::digest_state_proof].
+	pub fn from_state_proof(height: u64, block_id: Id, state_proof: &StateProof) -> Self {
+		Self::new(height, block_id, Commitment::digest_state_proof(state_proof))
+	}
+
+	/// Checks that the state proof digests to the commitment, in constant time.
+	pub fn verify_state_proof(&self, state_proof: &StateProof) -> bool {
+		self.commitment.verify_state_proof(state_proof)
+	}
+
 	pub fn height(&self) -> u64 {
 		self.height
 	}
@@ -182,9 +524,36 @@ impl BlockCommitment {
 	pub fn test() -> Self {
 		Self::new(0, Id::test(), Commitment::test())
 	}
+
+	/// Returns the contiguous range of heights starting at the lowest committed height.
+	/// The input may be unordered and contain duplicate heights.
+	pub fn contiguous_range(commitments: &[Self]) -> Option<RangeInclusive<u64>> {
+		let heights = Self::sorted_heights(commitments);
+		let start = *heights.first()?;
+		let end = heights
+			.windows(2)
+			.find(|pair| pair[1] != pair[0] + 1)
+			.map_or(heights[heights.len() - 1], |pair| pair[0]);
+		Some(start..=end)
+	}
+
+	/// Returns the first height missing after the contiguous committed range,
+	/// or `None` if there are no commitments or the range ends at the last possible height.
+	pub fn first_missing_height(commitments: &[Self]) -> Option<u64> {
+		Self::contiguous_range(commitments).and_then(|range| range.end().checked_add(1))
+	}
+
+	fn sorted_heights(commitments: &[Self]) -> Vec<u64> {
+		let mut heights: Vec<u64> = commitments.iter().map(Self::height).collect();
+		heights.sort_unstable();
+		heights.dedup();
+		heights
+	}
 }
 
+/// Serialized as a snake_case string, e.g. `"invalid_block_id"`.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
 pub enum BlockCommitmentRejectionReason {
 	InvalidBlockId,
 	InvalidCommitment,
@@ -192,8 +561,394 @@ pub enum BlockCommitmentRejectionReason {
 	ContractError,
 }
 
+/// Serialized with an explicit `type` tag, e.g.
+/// `{"type": "rejected", "height": 1, "reason": "invalid_commitment"}`.
+/// Accepted events carry the commitment fields alongside the tag.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum BlockCommitmentEvent {
 	Accepted(BlockCommitment),
 	Rejected { height: u64, reason: BlockCommitmentRejectionReason },
 }
+
+impl BlockCommitmentEvent {
+	/// The height of the block the event is about.
+	pub fn height(&self) -> u64 {
+		match self {
+			BlockCommitmentEvent::Accepted(commitment) => commitment.height(),
+			BlockCommitmentEvent::Rejected { height, .. } => *height,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::hash::Sha256;
+
+	#[test]
+	fn test_block_commitment_event_json() -> Result<(), anyhow::Error> {
+		let accepted = BlockCommitmentEvent::Accepted(BlockCommitment::test());
+		assert_eq!(
+			serde_json::to_value(&accepted)?,
+			serde_json::json!({
+				"type": "accepted",
+				"height": 0,
+				"block_id": vec![0u8; 32],
+				"commitment": vec![0u8; 32],
+			})
+		);
+
+		let rejected = BlockCommitmentEvent::Rejected {
+			height: 1,
+			reason: BlockCommitmentRejectionReason::InvalidCommitment,
+		};
+		let json = serde_json::to_value(&rejected)?;
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"type": "rejected",
+				"height": 1,
+				"reason": "invalid_commitment",
+			})
+		);
+		assert_eq!(serde_json::from_value::<BlockCommitmentEvent>(json)?, rejected);
+		assert_eq!(rejected.height(), 1);
+		assert_eq!(accepted.height(), 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_commitment_hex_round_trip() -> Result<(), anyhow::Error> {
+		let commitment = Commitment::new(core::array::from_fn(|i| (i * 9) as u8));
+		let hex = commitment.to_string();
+		assert_eq!(Commitment::from_hex(&hex)?, commitment);
+		assert_eq!(hex.to_uppercase().parse::<Commitment>()?, commitment);
+
+		assert!(Commitment::from_hex(&hex[..62]).is_err());
+		assert!(Commitment::from_hex(&format!("{}00", hex)).is_err());
+		assert!(Commitment::from_hex(&format!("+{}", &hex[1..])).is_err());
+		assert!(Commitment::from_hex(&"zz".repeat(32)).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_commitment_range_helpers() {
+		let commitment = |height| BlockCommitment::new(height, Id::test(), Commitment::test());
+
+		assert_eq!(BlockCommitment::contiguous_range(&[]), None);
+		assert_eq!(BlockCommitment::first_missing_height(&[]), None);
+
+		let commitments =
+			vec![commitment(4), commitment(2), commitment(3), commitment(3), commitment(7)];
+		assert_eq!(BlockCommitment::contiguous_range(&commitments), Some(2..=4));
+		assert_eq!(BlockCommitment::first_missing_height(&commitments), Some(5));
+
+		let commitments = vec![commitment(1), commitment(0), commitment(2)];
+		assert_eq!(BlockCommitment::contiguous_range(&commitments), Some(0..=2));
+		assert_eq!(BlockCommitment::first_missing_height(&commitments), Some(3));
+
+		let commitments = vec![commitment(u64::MAX - 1), commitment(u64::MAX)];
+		assert_eq!(BlockCommitment::contiguous_range(&commitments), Some(u64::MAX - 1..=u64::MAX));
+		assert_eq!(BlockCommitment::first_missing_height(&commitments), None);
+	}
+
+	#[test]
+	fn test_aggregate_commitments() -> Result<(), anyhow::Error> {
+		let commitment =
+			|height| BlockCommitment::new(height, Id::new([height as u8; 32]), Commitment::test());
+		let commitments = vec![commitment(3), commitment(1), commitment(2)];
+
+		let aggregate = Commitment::aggregate(&commitments)?;
+		// the order of the input doesn't matter
+		assert_eq!(
+			Commitment::aggregate(&[commitment(1), commitment(2), commitment(3)])?,
+			aggregate
+		);
+		assert!(aggregate.verify_aggregate(&commitments));
+		assert!(!aggregate.verify_aggregate(&commitments[..2]));
+		assert_ne!(Commitment::aggregate(&[commitment(1)])?, aggregate);
+
+		assert!(Commitment::aggregate(&[]).is_err());
+		assert!(Commitment::aggregate(&[commitment(1), commitment(3)]).is_err());
+		assert!(Commitment::aggregate(&[commitment(1), commitment(1)]).is_err());
+		assert!(!aggregate.verify_aggregate(&[commitment(1), commitment(1)]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_builder_matches_new() {
+		let transactions = vec![Transaction::new(vec![1], 1), Transaction::new(vec![2], 0)];
+
+		let mut builder = Block::builder(BlockMetadata::default(), Id::test());
+		for transaction in transactions.iter().cloned() {
+			builder.add_transaction(transaction);
+		}
+		let built = builder.build();
+
+		let block =
+			Block::new(BlockMetadata::default(), Id::test(), BTreeSet::from_iter(transactions));
+		assert_eq!(built, block);
+		assert_eq!(built.id(), block.id());
+	}
+
+	#[test]
+	fn test_verify() -> Result<(), anyhow::Error> {
+		let block = Block::test();
+		assert!(block.verify(&block.id()));
+		assert!(!block.verify(&Id::test()));
+		assert!(block.verify_parent(&Id::test()));
+		assert!(!block.verify_parent(&block.id()));
+
+		// a block whose transactions were tampered with no longer hashes to its stored id
+		let (metadata, parent, mut transactions, id) = block.into_parts();
+		transactions.insert(Transaction::new(vec![7], 3));
+		let tampered = Block { metadata, parent, transactions, id };
+		assert!(!tampered.verify(&id));
+
+		// same for the wire format
+		let tampered = Block::from_bcs(&tampered.to_bcs()?)?;
+		assert!(!tampered.verify(&id));
+
+		Ok(())
+	}
+
+	/// Replaces the data of a transaction, keeping its stored id, as a peer could over the wire.
+	fn with_stale_id(transaction: &Transaction, data: Vec<u8>) -> Transaction {
+		let mut json = serde_json::to_value(transaction).expect("transaction serializes");
+		json["data"] = serde_json::to_value(data).expect("data serializes");
+		serde_json::from_value(json).expect("transaction deserializes")
+	}
+
+	#[test]
+	fn test_verify_recomputes_transaction_ids() {
+		let transaction = Transaction::new(vec![1], 1);
+		let stale = with_stale_id(&transaction, vec![2]);
+		assert_eq!(stale.id(), transaction.id());
+		assert!(transaction.verify());
+		assert!(!stale.verify());
+
+		// the block id only covers the stored transaction ids, which still match
+		let block = Block::new(BlockMetadata::test(), Id::test(), BTreeSet::from([transaction]));
+		let (metadata, parent, _, id) = block.clone().into_parts();
+		let tampered = Block { metadata, parent, transactions: BTreeSet::from([stale]), id };
+		assert!(block.verify(&id));
+		assert!(!tampered.verify(&id));
+	}
+
+	#[test]
+	fn test_metadata_is_hashed() {
+		let block = Block::test();
+		let metadata = block.metadata();
+		assert_eq!(metadata.epoch(), 1);
+		assert_eq!(metadata.round(), 2);
+		assert_eq!(metadata.timestamp_micros(), 1_000_000);
+		assert_eq!(metadata.proposer(), Some(AccountAddress::ONE));
+
+		let transactions = BTreeSet::from_iter(block.transactions().cloned());
+		for other in [
+			BlockMetadata::new(1, 3, 1_000_000, Some(AccountAddress::ONE)),
+			BlockMetadata::new(1, 2, 1_000_001, Some(AccountAddress::ONE)),
+			BlockMetadata::new(1, 2, 1_000_000, Some(AccountAddress::TWO)),
+			BlockMetadata::new(1, 2, 1_000_000, None),
+		] {
+			let other = Block::new(other, Id::test(), transactions.clone());
+			assert_ne!(other.id(), block.id());
+			// swapping the metadata of a block breaks its id
+			let (_, parent, transactions, id) = block.clone().into_parts();
+			let tampered = Block { metadata: other.metadata().clone(), parent, transactions, id };
+			assert!(!tampered.verify(&id));
+		}
+
+		// ordered by epoch before round
+		assert!(BlockMetadata::new(1, 9, 9, None) < BlockMetadata::new(2, 0, 0, None));
+	}
+
+	#[test]
+	fn test_verify_chain() {
+		let genesis = Block::test();
+		let next = Block::new(
+			BlockMetadata::default(),
+			genesis.id(),
+			BTreeSet::from([Transaction::new(vec![1], 1)]),
+		);
+		let last = Block::new(
+			BlockMetadata::default(),
+			next.id(),
+			BTreeSet::from([Transaction::new(vec![2], 2)]),
+		);
+		assert_eq!(verify_chain(&[]), Ok(()));
+		assert_eq!(verify_chain(&[genesis.clone(), next.clone(), last.clone()]), Ok(()));
+		// the first block doesn't have to be the genesis block
+		assert_eq!(verify_chain(&[next.clone(), last.clone()]), Ok(()));
+
+		assert_eq!(
+			verify_chain(&[genesis.clone(), last.clone()]),
+			Err(ChainError::BrokenLink { index: 1, expected: genesis.id(), found: next.id() })
+		);
+		assert_eq!(
+			verify_chain(&[next.clone(), genesis.clone()]),
+			Err(ChainError::UnexpectedGenesis { index: 1 })
+		);
+
+		let (metadata, parent, mut transactions, id) = last.into_parts();
+		transactions.insert(Transaction::new(vec![7], 3));
+		let tampered = Block { metadata, parent, transactions, id };
+		assert_eq!(
+			verify_chain(&[genesis, next, tampered]),
+			Err(ChainError::InvalidId { index: 2, id })
+		);
+	}
+
+	#[test]
+	fn test_verify_chain_rejects_altered_payloads() {
+		let genesis = Block::test();
+		let transaction = Transaction::new(vec![1], 1);
+		let next = Block::new(
+			BlockMetadata::default(),
+			genesis.id(),
+			BTreeSet::from([transaction.clone()]),
+		);
+		let last = Block::new(
+			BlockMetadata::default(),
+			next.id(),
+			BTreeSet::from([Transaction::new(vec![2], 2)]),
+		);
+
+		// the middle block keeps its id and links, only a transaction payload changes
+		let (metadata, parent, _, id) = next.into_parts();
+		let stale = with_stale_id(&transaction, vec![9]);
+		let altered = Block { metadata, parent, transactions: BTreeSet::from([stale]), id };
+		assert_eq!(
+			verify_chain(&[genesis, altered, last]),
+			Err(ChainError::InvalidId { index: 1, id })
+		);
+	}
+
+	#[test]
+	fn test_hasher_is_chosen_per_call() {
+		let transaction = Transaction::new_with::<Sha256>(vec![1], 1);
+		assert_ne!(transaction.id(), Transaction::new(vec![1], 1).id());
+
+		let block = Block::builder(BlockMetadata::test(), Id::test())
+			.with_transaction(transaction.clone())
+			.build_with::<Sha256>();
+		assert!(block.verify_with::<Sha256>(&block.id()));
+		assert!(!block.verify(&block.id()));
+		assert_eq!(verify_chain_with::<Sha256>(&[block.clone()]), Ok(()));
+		assert_eq!(
+			verify_chain(&[block.clone()]),
+			Err(ChainError::InvalidId { index: 0, id: block.id() })
+		);
+
+		let proof = state_proof();
+		let commitment = Commitment::digest_state_proof_with::<Sha256>(&proof);
+		assert_ne!(commitment, Commitment::digest_state_proof(&proof));
+		assert!(commitment.verify_state_proof_with::<Sha256>(&proof));
+		assert!(!commitment.verify_state_proof(&proof));
+
+		let commitments = [BlockCommitment::new(0, block.id(), commitment)];
+		let aggregate = Commitment::aggregate_with::<Sha256>(&commitments).unwrap();
+		assert!(aggregate.verify_aggregate_with::<Sha256>(&commitments));
+		assert!(!aggregate.verify_aggregate(&commitments));
+	}
+
+	#[test]
+	fn test_add_transaction_refreshes_id() {
+		let mut block = Block::test();
+		let before = block.id();
+
+		let transaction = Transaction::new(vec![7], 3);
+		block.add_transaction(transaction.clone());
+		assert_ne!(block.id(), before);
+
+		let expected = Block::builder(BlockMetadata::test(), Id::test())
+			.with_transaction(Transaction::test())
+			.with_transaction(transaction)
+			.build();
+		assert_eq!(block.id(), expected.id());
+
+		let mut block = Block::builder(BlockMetadata::test(), Id::test())
+			.with_transaction(Transaction::new_with::<Sha256>(vec![1], 1))
+			.build_with::<Sha256>();
+		block.add_transaction_with::<Sha256>(Transaction::new_with::<Sha256>(vec![7], 3));
+		assert!(block.verify_with::<Sha256>(&block.id()));
+		assert!(!block.verify(&block.id()));
+	}
+
+	/// The BCS encoding of [Block::test], with the default BLAKE3 ids.
+	/// If this changes, the wire format changed and every commitment computed over it changes too.
+	const BLOCK_TEST_BCS: &str = concat!(
+		// metadata: epoch, round, timestamp, proposer
+		"0100000000000000",
+		"0200000000000000",
+		"40420f0000000000",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		// parent
+		"0000000000000000000000000000000000000000000000000000000000000000",
+		// transactions: length, data, sequence number, id
+		"01",
+		"0100",
+		"0000000000000000",
+		"1dfe343de371f1d9d325b8e33100c4afcd0838d64a29c93b0894f181352b36aa",
+		// id
+		"6e77efcba25196163734a4e105e7e116ffc5cde97cd071d51309fe05c1813786",
+	);
+
+	fn to_hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+	}
+
+	/// A state proof as served by the REST `state_proof` endpoint.
+	const STATE_PROOF_JSON: &str = include_str!("../tests/test_tx/state_proof.json");
+	const STATE_PROOF_CONSENSUS_DATA_HASH: &str =
+		"73e23839dc53190b1ccf3d748bd0a7c6d4cd37cd84e781069eb08e4de74175c1";
+
+	fn state_proof() -> StateProof {
+		serde_json::from_str(STATE_PROOF_JSON).expect("invalid state proof fixture")
+	}
+
+	/// The fixture with the consensus data hash of its ledger info replaced.
+	fn tampered_state_proof() -> StateProof {
+		assert!(STATE_PROOF_JSON.contains(STATE_PROOF_CONSENSUS_DATA_HASH));
+		let tampered = STATE_PROOF_JSON.replace(STATE_PROOF_CONSENSUS_DATA_HASH, &"01".repeat(32));
+		serde_json::from_str(&tampered).expect("invalid state proof fixture")
+	}
+
+	#[test]
+	fn test_verify_state_proof() {
+		let proof = state_proof();
+		let other_proof = tampered_state_proof();
+		assert_eq!(proof.latest_ledger_info().version(), 9643);
+
+		let commitment = Commitment::digest_state_proof(&proof);
+		assert!(commitment.verify_state_proof(&proof));
+		assert!(!commitment.verify_state_proof(&other_proof));
+		assert!(!Commitment::test().verify_state_proof(&proof));
+	}
+
+	#[test]
+	fn test_block_commitment_from_state_proof() {
+		let proof = state_proof();
+		let block_commitment = BlockCommitment::from_state_proof(3, Id::test(), &proof);
+
+		assert_eq!(block_commitment.height(), 3);
+		assert_eq!(block_commitment.block_id(), &Id::test());
+		assert_eq!(block_commitment.commitment(), Commitment::digest_state_proof(&proof));
+		assert!(block_commitment.verify_state_proof(&proof));
+		assert!(!block_commitment.verify_state_proof(&tampered_state_proof()));
+	}
+
+	#[test]
+	fn test_block_bcs_golden_vector() -> Result<(), anyhow::Error> {
+		let block = Block::test();
+		let bytes = block.to_bcs()?;
+		assert_eq!(to_hex(&bytes), BLOCK_TEST_BCS);
+		assert_eq!(Block::from_bcs(&bytes)?, block);
+		Ok(())
+	}
+}
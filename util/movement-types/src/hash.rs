@@ -0,0 +1,136 @@
+/// The version of the id hashing scheme.
+/// It's hashed after the domain tag, so ids from a future scheme can't collide with current ones.
+pub const ID_SCHEME_VERSION: u8 = 1;
+
+/// The version of the [crate::block::Id] hashing scheme, which also hashes the block metadata
+/// since version 2.
+pub const BLOCK_ID_SCHEME_VERSION: u8 = 2;
+
+/// The domain tag of [crate::transaction::Id]s.
+pub const TRANSACTION_DOMAIN: &[u8] = b"MOVEMENT_TX";
+
+/// The domain tag of [crate::block::Id]s.
+pub const BLOCK_DOMAIN: &[u8] = b"MOVEMENT_BLOCK";
+
+/// The domain tag of aggregated [crate::block::Commitment]s.
+pub const COMMITMENT_AGGREGATE_DOMAIN: &[u8] = b"MOVEMENT_COMMITMENT_AGGREGATE";
+
+/// A 256-bit digest function for ids and commitments.
+pub trait Hasher: Default {
+	fn update(&mut self, data: &[u8]);
+
+	fn finalize(self) -> [u8; 32];
+
+	/// Hashes the data in one go.
+	fn digest(data: &[u8]) -> [u8; 32] {
+		let mut hasher = Self::default();
+		hasher.update(data);
+		hasher.finalize()
+	}
+}
+
+/// BLAKE3, the default.
+#[derive(Default)]
+pub struct Blake3(blake3::Hasher);
+
+impl Hasher for Blake3 {
+	fn update(&mut self, data: &[u8]) {
+		self.0.update(data);
+	}
+
+	fn finalize(self) -> [u8; 32] {
+		self.0.finalize().into()
+	}
+}
+
+/// SHA-256, e.g., for a settlement contract which can only verify SHA-256.
+#[derive(Default)]
+pub struct Sha256(sha2::Sha256);
+
+impl Hasher for Sha256 {
+	fn update(&mut self, data: &[u8]) {
+		sha2::Digest::update(&mut self.0, data);
+	}
+
+	fn finalize(self) -> [u8; 32] {
+		sha2::Digest::finalize(self.0).into()
+	}
+}
+
+/// The hasher used for transaction and block ids and state proof commitments.
+/// Pass another [Hasher] to the `_with` variants, e.g., [crate::transaction::Transaction::new_with],
+/// to hash with it instead. It's a type parameter rather than a feature, so that one crate in a
+/// build can't change the ids every other crate computes.
+pub type DefaultHasher = Blake3;
+
+/// Starts a hasher separated by the domain tag and the scheme version,
+/// so that different types with identical byte content produce different ids.
+pub(crate) fn domain_hasher<H: Hasher>(domain: &[u8]) -> H {
+	versioned_domain_hasher(domain, ID_SCHEME_VERSION)
+}
+
+/// Like [domain_hasher], for a domain whose scheme is versioned separately.
+pub(crate) fn versioned_domain_hasher<H: Hasher>(domain: &[u8], version: u8) -> H {
+	let mut hasher = H::default();
+	hasher.update(domain);
+	hasher.update(&[version]);
+	hasher
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::block::{Block, BlockMetadata, Id};
+	use crate::transaction::Transaction;
+	use std::collections::BTreeSet;
+
+	fn to_hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+	}
+
+	#[test]
+	fn test_hashers() {
+		assert_eq!(
+			to_hex(&Blake3::digest(b"abc")),
+			"6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+		);
+		assert_eq!(
+			to_hex(&Sha256::digest(b"abc")),
+			"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+		);
+
+		// incremental updates hash the concatenation
+		let mut hasher = Sha256::default();
+		hasher.update(b"a");
+		hasher.update(b"bc");
+		assert_eq!(hasher.finalize(), Sha256::digest(b"abc"));
+	}
+
+	#[test]
+	fn test_domains_separate_identical_content() {
+		let content = [7u8; 40];
+		let mut transaction_hasher: DefaultHasher = domain_hasher(TRANSACTION_DOMAIN);
+		transaction_hasher.update(&content);
+		let transaction_hash = transaction_hasher.finalize();
+		let mut block_hasher: DefaultHasher =
+			versioned_domain_hasher(BLOCK_DOMAIN, BLOCK_ID_SCHEME_VERSION);
+		block_hasher.update(&content);
+
+		assert_ne!(transaction_hash, block_hasher.finalize());
+		assert_ne!(transaction_hash, DefaultHasher::digest(&content));
+	}
+
+	#[test]
+	fn test_block_id_differs_from_transaction_id() {
+		// a block over no transactions hashes little more than its parent,
+		// which is also the content of a transaction over the parent bytes
+		let parent = Id::new([1; 32]);
+		let block = Block::new(BlockMetadata::default(), parent, BTreeSet::new());
+
+		let mut data = parent.to_vec();
+		let sequence_number = u64::from_le_bytes(data.split_off(24).try_into().unwrap());
+		let transaction = Transaction::new(data, sequence_number);
+
+		assert_ne!(block.id().as_bytes(), transaction.id().as_bytes());
+	}
+}
@@ -1,3 +1,8 @@
 pub mod atomic_transaction_bundle;
 pub mod block;
+pub mod hash;
+pub mod proof;
 pub mod transaction;
+
+pub use block::{verify_chain, verify_chain_with, ChainError};
+pub use proof::verify_transaction_proof;
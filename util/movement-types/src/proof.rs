@@ -0,0 +1,294 @@
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_types::{
+	account_address::AccountAddress,
+	account_config::AccountResource,
+	proof::{SparseMerkleProof, TransactionInfoWithProof},
+	state_proof::StateProof,
+	state_store::{state_key::StateKey, state_value::StateValue},
+	transaction::Version,
+};
+use serde::{Deserialize, Serialize};
+
+/// The proof of a transaction served by the REST service, shared so clients can deserialize it.
+///
+/// `version` is the schema version, clients should reject versions they don't know.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateProofResponse {
+	pub version: u32,
+	/// The index of the transaction in the ledger the proof is for.
+	pub tx_index: u64,
+	/// The state proof of the ledger info the transaction proof is anchored in.
+	pub state_proof: StateProof,
+	/// The transaction info with its accumulator proof up to that ledger info.
+	pub tx_proof: TransactionInfoWithProof,
+}
+
+impl StateProofResponse {
+	/// The schema version responses are created with.
+	pub const VERSION: u32 = 1;
+
+	pub fn new(tx_index: u64, state_proof: StateProof, tx_proof: TransactionInfoWithProof) -> Self {
+		Self { version: Self::VERSION, tx_index, state_proof, tx_proof }
+	}
+
+	/// Verifies the transaction proof against a transaction accumulator root the client trusts,
+	/// see [verify_transaction_proof].
+	pub fn verify_transaction(&self, expected_root: HashValue) -> Result<(), anyhow::Error> {
+		verify_transaction_proof(&self.tx_proof, self.tx_index, expected_root)
+	}
+}
+
+/// The proof of the value of a state key, e.g., a table item, served by the REST service.
+///
+/// The value is proven in the state checkpoint of the transaction `proof` is for.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateValueProofResponse {
+	pub state_key: StateKey,
+	/// None if the key has no value at that version, which the proof shows as well.
+	pub state_value: Option<StateValue>,
+	/// The sparse Merkle proof of the value up to the state checkpoint hash of the transaction.
+	pub state_value_proof: SparseMerkleProof,
+	/// The proof of the state checkpoint transaction the value is read at.
+	pub proof: StateProofResponse,
+}
+
+impl StateValueProofResponse {
+	/// Verifies the transaction proof against a transaction accumulator root the client trusts,
+	/// as [StateProofResponse::verify_transaction], and the state value proof against the state
+	/// checkpoint hash of that transaction.
+	pub fn verify(&self, expected_root: HashValue) -> Result<(), anyhow::Error> {
+		self.proof.verify_transaction(expected_root)?;
+		let state_root =
+			self.proof.tx_proof.transaction_info().state_checkpoint_hash().ok_or_else(|| {
+				anyhow::anyhow!("Transaction {} isn't a state checkpoint", self.proof.tx_index)
+			})?;
+		self.state_value_proof
+			.verify(state_root, self.state_key.hash(), self.state_value.as_ref())
+			.map_err(|e| anyhow::anyhow!("Invalid proof of state key {:?}: {}", self.state_key, e))
+	}
+}
+
+/// The proof of the `0x1::account::Account` resource of an account, served by the REST service.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountProofResponse {
+	pub address: AccountAddress,
+	/// The proof of the value of the resource, see [account_state_key].
+	pub proof: StateValueProofResponse,
+}
+
+impl AccountProofResponse {
+	/// Verifies the state value proof as [StateValueProofResponse::verify], and that it is of the
+	/// resource of `address`.
+	pub fn verify(&self, expected_root: HashValue) -> Result<(), anyhow::Error> {
+		self.proof.verify(expected_root)?;
+		if self.proof.state_key != account_state_key(self.address)? {
+			anyhow::bail!("The proof isn't of the account resource of {}", self.address);
+		}
+		Ok(())
+	}
+
+	/// Decodes the account resource from the proven value, None if the account doesn't exist.
+	///
+	/// The value is only proven once [AccountProofResponse::verify] succeeds.
+	pub fn account(&self) -> Result<Option<AccountResource>, anyhow::Error> {
+		self.proof
+			.state_value
+			.as_ref()
+			.map(|value| bcs::from_bytes(value.bytes()))
+			.transpose()
+			.map_err(|e| anyhow::anyhow!("Invalid account resource of {}: {}", self.address, e))
+	}
+}
+
+/// The state key of the `0x1::account::Account` resource of `address`.
+pub fn account_state_key(address: AccountAddress) -> Result<StateKey, anyhow::Error> {
+	// a `StateKey::AccessPath`, the first variant, to a `Path::Resource`, the second variant,
+	// of the struct tag with no type arguments
+	let struct_tag = (AccountAddress::ONE, "account", "Account", Vec::<()>::new());
+	let path = bcs::to_bytes(&(1u8, struct_tag))?;
+	Ok(bcs::from_bytes(&bcs::to_bytes(&(0u8, address, path))?)?)
+}
+
+/// Verifies `proof` shows the transaction info is the one at `version` in the transaction
+/// accumulator with root `expected_root`.
+///
+/// The root has to come from a ledger info the client already trusts, e.g., one verified
+/// through the epoch change proof; the proof itself doesn't vouch for it.
+pub fn verify_transaction_proof(
+	proof: &TransactionInfoWithProof,
+	version: Version,
+	expected_root: HashValue,
+) -> Result<(), anyhow::Error> {
+	proof
+		.ledger_info_to_transaction_info_proof()
+		.verify(expected_root, proof.transaction_info().hash(), version)
+		.map_err(|e| anyhow::anyhow!("Invalid proof of transaction {}: {}", version, e))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use aptos_types::{
+		aggregate_signature::AggregateSignature,
+		block_info::BlockInfo,
+		epoch_change::EpochChangeProof,
+		ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+		proof::{SparseMerkleLeafNode, TransactionAccumulatorProof, TransactionInfo},
+		transaction::ExecutionStatus,
+	};
+
+	fn empty_state_proof() -> StateProof {
+		StateProof::new(
+			LedgerInfoWithSignatures::new(
+				LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+				AggregateSignature::empty(),
+			),
+			EpochChangeProof::new(vec![], false),
+		)
+	}
+
+	/// The proof of the only transaction of a ledger, checkpointing `state_root`.
+	fn checkpoint_proof(state_root: Option<HashValue>) -> StateProofResponse {
+		let transaction_info = TransactionInfo::new(
+			HashValue::zero(),
+			HashValue::zero(),
+			HashValue::zero(),
+			state_root,
+			0,
+			ExecutionStatus::Success,
+		);
+		let tx_proof = TransactionInfoWithProof::new(
+			TransactionAccumulatorProof::new(vec![]),
+			transaction_info,
+		);
+		StateProofResponse::new(0, empty_state_proof(), tx_proof)
+	}
+
+	#[test]
+	fn test_state_proof_response_json() -> Result<(), anyhow::Error> {
+		let state_proof = empty_state_proof();
+		let tx_proof = TransactionInfoWithProof::new(
+			TransactionAccumulatorProof::new(vec![]),
+			TransactionInfo::new(
+				HashValue::zero(),
+				HashValue::zero(),
+				HashValue::zero(),
+				None,
+				0,
+				ExecutionStatus::Success,
+			),
+		);
+		let response = StateProofResponse::new(7, state_proof, tx_proof);
+
+		let json = serde_json::to_string(&response)?;
+		let value: serde_json::Value = serde_json::from_str(&json)?;
+		assert_eq!(value["version"], StateProofResponse::VERSION);
+		assert_eq!(value["tx_index"], 7);
+		assert_eq!(serde_json::from_str::<StateProofResponse>(&json)?, response);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_verify_transaction_proof() -> Result<(), anyhow::Error> {
+		let transaction_info = TransactionInfo::new(
+			HashValue::zero(),
+			HashValue::zero(),
+			HashValue::zero(),
+			Some(HashValue::new([1; 32])),
+			0,
+			ExecutionStatus::Success,
+		);
+		// the root of an accumulator holding just this transaction is the transaction info hash
+		let root = transaction_info.hash();
+		let proof = TransactionInfoWithProof::new(
+			TransactionAccumulatorProof::new(vec![]),
+			transaction_info,
+		);
+
+		verify_transaction_proof(&proof, 0, root)?;
+		assert!(verify_transaction_proof(&proof, 0, HashValue::zero()).is_err());
+		// the proof has no siblings to place the transaction at another version
+		assert!(verify_transaction_proof(&proof, 1, root).is_err());
+
+		let state_proof = empty_state_proof();
+		StateProofResponse::new(0, state_proof.clone(), proof.clone()).verify_transaction(root)?;
+		assert!(StateProofResponse::new(1, state_proof, proof).verify_transaction(root).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_verify_state_value_proof() -> Result<(), anyhow::Error> {
+		// a raw state key, the third variant of its BCS encoding
+		let state_key: StateKey = bcs::from_bytes(&[2, 3, 1, 2, 3])?;
+		let state_value = StateValue::from(vec![7; 4]);
+		// the only leaf of a tree is its root
+		let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+		let state_root = leaf.hash();
+		let response = StateValueProofResponse {
+			state_key,
+			state_value: Some(state_value),
+			state_value_proof: SparseMerkleProof::new(Some(leaf), vec![]),
+			proof: checkpoint_proof(Some(state_root)),
+		};
+		let root = response.proof.tx_proof.transaction_info().hash();
+		response.verify(root)?;
+		assert!(response.verify(HashValue::zero()).is_err());
+
+		let tampered = StateValueProofResponse {
+			state_value: Some(StateValue::from(vec![8; 4])),
+			..response.clone()
+		};
+		assert!(tampered.verify(root).is_err());
+		// the proof shows the key has a value
+		let tampered = StateValueProofResponse { state_value: None, ..response.clone() };
+		assert!(tampered.verify(root).is_err());
+
+		let not_checkpoint = StateValueProofResponse { proof: checkpoint_proof(None), ..response };
+		let root = not_checkpoint.proof.tx_proof.transaction_info().hash();
+		assert!(not_checkpoint.verify(root).is_err());
+
+		let json = serde_json::to_string(&not_checkpoint)?;
+		assert_eq!(serde_json::from_str::<StateValueProofResponse>(&json)?, not_checkpoint);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_verify_account_proof() -> Result<(), anyhow::Error> {
+		let address = AccountAddress::from_hex_literal("0xa11ce")?;
+		let state_key = account_state_key(address)?;
+		// the tree holds another key, which proves the account doesn't exist
+		let other_key: StateKey = bcs::from_bytes(&[2, 3, 1, 2, 3])?;
+		let leaf = SparseMerkleLeafNode::new(other_key.hash(), StateValue::from(vec![7; 4]).hash());
+		let state_root = leaf.hash();
+		let response = AccountProofResponse {
+			address,
+			proof: StateValueProofResponse {
+				state_key,
+				state_value: None,
+				state_value_proof: SparseMerkleProof::new(Some(leaf), vec![]),
+				proof: checkpoint_proof(Some(state_root)),
+			},
+		};
+		let root = response.proof.proof.tx_proof.transaction_info().hash();
+		response.verify(root)?;
+		assert!(response.account()?.is_none());
+
+		// the proof holds for the key, not for the account of another address
+		let other = AccountProofResponse { address: AccountAddress::ONE, ..response.clone() };
+		assert!(other.verify(root).is_err());
+		let other = AccountProofResponse {
+			proof: StateValueProofResponse {
+				state_key: account_state_key(AccountAddress::ONE)?,
+				..response.proof.clone()
+			},
+			..response
+		};
+		other.proof.verify(root)?;
+		assert!(other.verify(root).is_err());
+
+		Ok(())
+	}
+}
@@ -1,6 +1,10 @@
+use crate::hash::{domain_hasher, DefaultHasher, Hasher, TRANSACTION_DOMAIN};
+use aptos_types::account_address::AccountAddress;
+use aptos_types::transaction::SignedTransaction;
 use core::fmt;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 #[derive(
 	Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord,
@@ -49,13 +53,33 @@ pub struct Transaction {
 
 impl Transaction {
 	pub fn new(data: Vec<u8>, sequence_number: u64) -> Self {
-		let mut hasher = blake3::Hasher::new();
-		hasher.update(&data);
-		hasher.update(&sequence_number.to_le_bytes());
-		let id = Id(hasher.finalize().into());
+		Self::new_with::<DefaultHasher>(data, sequence_number)
+	}
+
+	/// Like [Transaction::new], but hashes the id with `H`.
+	pub fn new_with<H: Hasher>(data: Vec<u8>, sequence_number: u64) -> Self {
+		let id = Self::compute_id::<H>(&data, sequence_number);
 		Self { data, sequence_number, id }
 	}
 
+	fn compute_id<H: Hasher>(data: &[u8], sequence_number: u64) -> Id {
+		let mut hasher: H = domain_hasher(TRANSACTION_DOMAIN);
+		hasher.update(data);
+		hasher.update(&sequence_number.to_le_bytes());
+		Id(hasher.finalize())
+	}
+
+	/// Checks that the data and sequence number hash to the stored id,
+	/// e.g., for a transaction received over the wire or loaded from storage.
+	pub fn verify(&self) -> bool {
+		self.verify_with::<DefaultHasher>()
+	}
+
+	/// Like [Transaction::verify], for a transaction hashed with `H`.
+	pub fn verify_with<H: Hasher>(&self) -> bool {
+		Self::compute_id::<H>(&self.data, self.sequence_number) == self.id
+	}
+
 	pub fn id(&self) -> Id {
 		self.id
 	}
@@ -68,9 +92,26 @@ impl Transaction {
 		self.sequence_number
 	}
 
+	/// Returns the sender of the wrapped Aptos transaction.
+	/// The data is expected to be a JSON serialized [SignedTransaction], as produced by the transaction ingress.
+	pub fn sender(&self) -> Result<AccountAddress, anyhow::Error> {
+		let signed_transaction: SignedTransaction = serde_json::from_slice(&self.data)?;
+		Ok(signed_transaction.sender())
+	}
+
 	pub fn test() -> Self {
 		Self::new(vec![0], 0)
 	}
+
+	/// Serializes the transaction with BCS.
+	pub fn to_bcs(&self) -> Result<Vec<u8>, anyhow::Error> {
+		Ok(bcs::to_bytes(self)?)
+	}
+
+	/// Deserializes a transaction from BCS.
+	pub fn from_bcs(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+		Ok(bcs::from_bytes(bytes)?)
+	}
 }
 
 impl Ord for Transaction {
@@ -92,9 +133,62 @@ impl PartialOrd for Transaction {
 	}
 }
 
+/// Groups transactions by sender, ordering each sender's transactions by sequence number.
+/// Transactions from different senders can be executed in parallel,
+/// while each group has to be executed in order.
+///
+/// In strict mode, a duplicate or a gap in a sender's sequence numbers is an error.
+pub fn group_by_sender(
+	transactions: &[Transaction],
+	strict: bool,
+) -> Result<BTreeMap<AccountAddress, Vec<&Transaction>>, anyhow::Error> {
+	group_by_key(transactions, strict, Transaction::sender)
+}
+
+/// Groups transactions by an arbitrary key, ordering each group by sequence number.
+/// See [group_by_sender].
+pub fn group_by_key<'a, K, F>(
+	transactions: &'a [Transaction],
+	strict: bool,
+	key: F,
+) -> Result<BTreeMap<K, Vec<&'a Transaction>>, anyhow::Error>
+where
+	K: Ord + fmt::Debug,
+	F: Fn(&Transaction) -> Result<K, anyhow::Error>,
+{
+	let mut groups: BTreeMap<K, Vec<&'a Transaction>> = BTreeMap::new();
+	for transaction in transactions {
+		groups.entry(key(transaction)?).or_default().push(transaction);
+	}
+
+	for (key, group) in groups.iter_mut() {
+		// the transaction ordering is by sequence number first
+		group.sort();
+
+		if strict {
+			for pair in group.windows(2) {
+				let (previous, next) = (pair[0].sequence_number(), pair[1].sequence_number());
+				if previous.checked_add(1) != Some(next) {
+					return Err(anyhow::anyhow!(
+						"Non-contiguous sequence numbers for {:?}: {} followed by {}",
+						key,
+						previous,
+						next
+					));
+				}
+			}
+		}
+	}
+
+	Ok(groups)
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
+	use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+	use aptos_types::chain_id::ChainId;
+	use aptos_types::transaction::{RawTransaction, Script, TransactionPayload};
 
 	#[test]
 	fn test_transaction_ordering() {
@@ -105,4 +199,108 @@ mod test {
 		assert!(transaction < transaction2);
 		assert!(transaction2 < transaction3);
 	}
+
+	/// Uses the first byte of the data as the sender, so that tests don't need signed transactions.
+	fn first_byte(transaction: &Transaction) -> Result<u8, anyhow::Error> {
+		transaction.data().first().copied().ok_or_else(|| anyhow::anyhow!("empty data"))
+	}
+
+	#[test]
+	fn test_group_by_key_interleaved() -> Result<(), anyhow::Error> {
+		let transactions = vec![
+			Transaction::new(vec![1, 0], 2),
+			Transaction::new(vec![2, 0], 0),
+			Transaction::new(vec![1, 1], 0),
+			Transaction::new(vec![2, 1], 1),
+			Transaction::new(vec![1, 2], 1),
+		];
+
+		let groups = group_by_key(&transactions, true, first_byte)?;
+		assert_eq!(groups.len(), 2);
+
+		let sequence_numbers = |key: u8| -> Vec<u64> {
+			groups[&key].iter().map(|transaction| transaction.sequence_number()).collect()
+		};
+		assert_eq!(sequence_numbers(1), vec![0, 1, 2]);
+		assert_eq!(sequence_numbers(2), vec![0, 1]);
+
+		Ok(())
+	}
+
+	/// Wraps a JSON serialized signed transaction, as the transaction ingress does.
+	fn signed_by(
+		sender: AccountAddress,
+		sequence_number: u64,
+	) -> Result<Transaction, anyhow::Error> {
+		let private_key = Ed25519PrivateKey::try_from([1u8; 32].as_ref())?;
+		let raw_transaction = RawTransaction::new(
+			sender,
+			sequence_number,
+			TransactionPayload::Script(Script::new(vec![0], vec![], vec![])),
+			0,
+			0,
+			0,
+			ChainId::test(),
+		);
+		let signed_transaction = SignedTransaction::new(
+			raw_transaction,
+			Ed25519PublicKey::from(&private_key),
+			Ed25519Signature::try_from([0u8; 64].as_ref())?,
+		);
+		Ok(Transaction::new(serde_json::to_vec(&signed_transaction)?, sequence_number))
+	}
+
+	#[test]
+	fn test_group_by_sender_interleaved() -> Result<(), anyhow::Error> {
+		let (alice, bob) = (AccountAddress::ONE, AccountAddress::TWO);
+		let transactions = vec![
+			signed_by(bob, 1)?,
+			signed_by(alice, 2)?,
+			signed_by(alice, 0)?,
+			signed_by(bob, 0)?,
+			signed_by(alice, 1)?,
+		];
+
+		let groups = group_by_sender(&transactions, true)?;
+		assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![alice, bob]);
+
+		for (sender, group) in &groups {
+			for transaction in group {
+				assert_eq!(transaction.sender()?, *sender);
+			}
+		}
+
+		let sequence_numbers = |sender: AccountAddress| -> Vec<u64> {
+			groups[&sender]
+				.iter()
+				.map(|transaction| transaction.sequence_number())
+				.collect()
+		};
+		assert_eq!(sequence_numbers(alice), vec![0, 1, 2]);
+		assert_eq!(sequence_numbers(bob), vec![0, 1]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_group_by_key_strict_gaps() -> Result<(), anyhow::Error> {
+		let transactions = vec![
+			Transaction::new(vec![1, 0], 0),
+			Transaction::new(vec![1, 1], 2),
+			Transaction::new(vec![2, 0], 5),
+		];
+
+		// lenient mode only orders
+		let groups = group_by_key(&transactions, false, first_byte)?;
+		assert_eq!(groups[&1].len(), 2);
+
+		// strict mode rejects the gap between 0 and 2
+		assert!(group_by_key(&transactions, true, first_byte).is_err());
+
+		// duplicates are rejected in strict mode as well
+		let duplicates = vec![Transaction::new(vec![1, 0], 0), Transaction::new(vec![1, 1], 0)];
+		assert!(group_by_key(&duplicates, true, first_byte).is_err());
+
+		Ok(())
+	}
 }
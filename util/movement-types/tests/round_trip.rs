@@ -0,0 +1,99 @@
+//! Round trips of the types that are serialized and hashed, over arbitrary inputs.
+
+use aptos_types::account_address::AccountAddress;
+use movement_types::atomic_transaction_bundle::{self, AtomicTransactionBundle, TransactionEntry};
+use movement_types::block::{self, Block, BlockCommitment, BlockMetadata, Commitment};
+use movement_types::transaction::Transaction;
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+fn transaction() -> impl Strategy<Value = Transaction> {
+	let data = prop_oneof![Just(Vec::new()), vec(any::<u8>(), 0..256)];
+	let sequence_number = prop_oneof![Just(0), Just(u64::MAX), any::<u64>()];
+	(data, sequence_number)
+		.prop_map(|(data, sequence_number)| Transaction::new(data, sequence_number))
+}
+
+fn metadata() -> impl Strategy<Value = BlockMetadata> {
+	(any::<u64>(), any::<u64>(), any::<u64>(), proptest::option::of(any::<[u8; 32]>())).prop_map(
+		|(epoch, round, timestamp_micros, proposer)| {
+			BlockMetadata::new(epoch, round, timestamp_micros, proposer.map(AccountAddress::new))
+		},
+	)
+}
+
+fn block() -> impl Strategy<Value = Block> {
+	(metadata(), any::<[u8; 32]>(), btree_set(transaction(), 0..16)).prop_map(
+		|(metadata, parent, transactions)| {
+			Block::new(metadata, block::Id::new(parent), transactions)
+		},
+	)
+}
+
+fn bundle() -> impl Strategy<Value = AtomicTransactionBundle> {
+	(any::<[u8; 32]>(), vec((any::<[u8; 32]>(), transaction()), 0..16)).prop_map(
+		|(sequencer_id, entries)| {
+			let entries = entries
+				.into_iter()
+				.map(|(consumer_id, transaction)| {
+					TransactionEntry::new(
+						atomic_transaction_bundle::Id::new(consumer_id),
+						transaction,
+					)
+				})
+				.collect();
+			AtomicTransactionBundle::new(atomic_transaction_bundle::Id::new(sequencer_id), entries)
+		},
+	)
+}
+
+proptest! {
+	#[test]
+	fn test_transaction_round_trip(transaction in transaction()) {
+		let decoded = Transaction::from_bcs(&transaction.to_bcs().unwrap()).unwrap();
+		prop_assert_eq!(&decoded, &transaction);
+		prop_assert_eq!(decoded.id(), transaction.id());
+		prop_assert_eq!(transaction.clone().id(), transaction.id());
+
+		// the id only depends on the data and the sequence number
+		let rebuilt = Transaction::new(transaction.data().to_vec(), transaction.sequence_number());
+		prop_assert_eq!(rebuilt.id(), transaction.id());
+	}
+
+	#[test]
+	fn test_block_round_trip(block in block()) {
+		let decoded = Block::from_bcs(&block.to_bcs().unwrap()).unwrap();
+		prop_assert_eq!(&decoded, &block);
+		prop_assert!(decoded.verify(&block.id()));
+		prop_assert_eq!(block.clone().id(), block.id());
+
+		let mut builder = Block::builder(block.metadata().clone(), block.parent());
+		for transaction in block.transactions() {
+			builder.add_transaction(transaction.clone());
+		}
+		prop_assert_eq!(builder.build().id(), block.id());
+	}
+
+	#[test]
+	fn test_bundle_round_trip(bundle in bundle()) {
+		let decoded: AtomicTransactionBundle =
+			bcs::from_bytes(&bcs::to_bytes(&bundle).unwrap()).unwrap();
+		prop_assert_eq!(&decoded, &bundle);
+		prop_assert_eq!(&AtomicTransactionBundle::unpack(&bundle.pack().unwrap()).unwrap(), &bundle);
+	}
+
+	#[test]
+	fn test_commitment_round_trip(
+		bytes in any::<[u8; 32]>(),
+		height in prop_oneof![Just(0), Just(u64::MAX), any::<u64>()],
+		block_id in any::<[u8; 32]>(),
+	) {
+		let commitment = Commitment::new(bytes);
+		prop_assert_eq!(Commitment::from_bcs(&commitment.to_bcs().unwrap()).unwrap(), commitment);
+
+		let block_commitment = BlockCommitment::new(height, block::Id::new(block_id), commitment);
+		let decoded: BlockCommitment =
+			bcs::from_bytes(&bcs::to_bytes(&block_commitment).unwrap()).unwrap();
+		prop_assert_eq!(decoded, block_commitment);
+	}
+}